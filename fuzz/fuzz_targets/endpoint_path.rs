@@ -0,0 +1,19 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use stream_rust_test::path::EndpointPath;
+
+fuzz_target!(|path: &str| {
+    if let Ok(endpoint) = EndpointPath::parse(path) {
+        // a parsed endpoint has exactly two non empty components and is printed back unchanged
+        assert!(!endpoint.sensor_id.is_empty() && !endpoint.parameter.is_empty());
+        assert!(!endpoint.sensor_id.contains('/') && !endpoint.parameter.contains('/'));
+        assert_eq!(endpoint.to_string(), path);
+    }
+});
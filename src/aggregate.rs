@@ -0,0 +1,109 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Object aggregated data, with fields correlated to the generated value.
+
+use astarte_device_sdk::AstarteType;
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Standard gravity in m/s^2
+const GRAVITY: f64 = 9.80665;
+
+/// Object aggregated data sent instead of individual values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Aggregate {
+    /// Three axis accelerometer, sending `x`, `y` and `z`
+    ///
+    /// The `x` axis follows the generated value, `y` half of it and `z` completes the gravity
+    /// vector.
+    Accelerometer,
+    /// Environmental sensor, sending `temperature`, `humidity` and `pressure`
+    ///
+    /// The temperature and the pressure follow the generated value, while the humidity decreases
+    /// as the temperature rises.
+    Environment,
+}
+
+impl Display for Aggregate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Aggregate::Accelerometer => write!(f, "accelerometer"),
+            Aggregate::Environment => write!(f, "environment"),
+        }
+    }
+}
+
+impl Aggregate {
+    /// Name of the bundled object aggregated interface
+    pub fn default_interface(&self) -> &'static str {
+        match self {
+            Aggregate::Accelerometer => "org.astarte-platform.stream-rust-test.AccelerometerValues",
+            Aggregate::Environment => "org.astarte-platform.stream-rust-test.EnvironmentValues",
+        }
+    }
+
+    /// Derive the fields of the object from a generated value
+    pub fn fields(&self, value: f64) -> eyre::Result<HashMap<String, AstarteType>> {
+        let fields = match self {
+            Aggregate::Accelerometer => {
+                let x = value;
+                let y = value / 2.0;
+                let z = (GRAVITY.powi(2) - x.powi(2) - y.powi(2)).max(0.0).sqrt();
+
+                [("x", x), ("y", y), ("z", z)]
+            }
+            Aggregate::Environment => {
+                let temperature = 20.0 + value;
+                let humidity = (50.0 - 2.0 * value).clamp(0.0, 100.0);
+                let pressure = 1013.25 + value / 2.0;
+
+                [
+                    ("temperature", temperature),
+                    ("humidity", humidity),
+                    ("pressure", pressure),
+                ]
+            }
+        };
+
+        fields
+            .into_iter()
+            .map(|(name, value)| {
+                AstarteType::try_from(value)
+                    .map(|value| (name.to_string(), value))
+                    .wrap_err_with(|| format!("invalid {name} value {value}"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_fields() {
+        let fields = Aggregate::Accelerometer.fields(2.0).unwrap();
+
+        assert_eq!(fields["x"], AstarteType::Double(2.0));
+        assert_eq!(fields["y"], AstarteType::Double(1.0));
+
+        let z = f64::try_from(fields["z"].clone()).unwrap();
+        assert!((z.powi(2) + 5.0 - GRAVITY.powi(2)).abs() < 1e-9);
+
+        let fields = Aggregate::Environment.fields(30.0).unwrap();
+
+        assert_eq!(fields["temperature"], AstarteType::Double(50.0));
+        assert_eq!(fields["humidity"], AstarteType::Double(0.0));
+        assert_eq!(fields.len(), 3);
+
+        assert!(Aggregate::Environment.fields(f64::NAN).is_err());
+    }
+}
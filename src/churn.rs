@@ -0,0 +1,125 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Churn of the simulated sensors, appearing and disappearing over time to test the dashboards and
+//! the triggers against a changing sensor population.
+//!
+//! At every step a new sensor appears or a random churned sensor disappears, keeping at most the
+//! configured number of churned sensors at the same time.
+
+use rand::Rng;
+use std::fmt::Display;
+
+/// Change of the sensor population
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChurnEvent {
+    /// A sensor with the given id appears
+    Appear(String),
+    /// The sensor with the given id disappears
+    Disappear(String),
+}
+
+impl Display for ChurnEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChurnEvent::Appear(id) => write!(f, "sensor {id} appeared"),
+            ChurnEvent::Disappear(id) => write!(f, "sensor {id} disappeared"),
+        }
+    }
+}
+
+/// Sensors created by the churn
+#[derive(Debug, Clone, Default)]
+pub struct Churn {
+    /// Maximum churned sensors at the same time
+    max: usize,
+    /// Ids of the churned sensors currently present
+    present: Vec<String>,
+    /// Sensors created so far, used to generate unique ids
+    created: u64,
+}
+
+impl Churn {
+    /// Keep at most the given number of churned sensors
+    pub fn new(max: usize) -> Self {
+        Self {
+            max: max.max(1),
+            ..Default::default()
+        }
+    }
+
+    /// Next change of the population, the new sensors ids are prefixed by the given one
+    pub fn next(&mut self, prefix: &str) -> ChurnEvent {
+        let appear = match self.present.len() {
+            0 => true,
+            len if len >= self.max => false,
+            _ => rand::thread_rng().gen_bool(0.5),
+        };
+
+        if appear {
+            let id = format!("{prefix}-churn-{}", self.created);
+
+            self.created += 1;
+            self.present.push(id.clone());
+
+            ChurnEvent::Appear(id)
+        } else {
+            let idx = rand::thread_rng().gen_range(0..self.present.len());
+
+            ChurnEvent::Disappear(self.present.swap_remove(idx))
+        }
+    }
+
+    /// Forget a churned sensor removed by other means, e.g. from Astarte
+    pub fn forget(&mut self, sensor_id: &str) {
+        self.present.retain(|id| id != sensor_id);
+    }
+
+    /// Number of churned sensors currently present
+    pub fn len(&self) -> usize {
+        self.present.len()
+    }
+
+    /// Check if no churned sensor is present
+    pub fn is_empty(&self) -> bool {
+        self.present.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_churn() {
+        let mut churn = Churn::new(2);
+
+        assert_eq!(
+            churn.next("test"),
+            ChurnEvent::Appear("test-churn-0".into())
+        );
+
+        let mut appeared = 1;
+
+        for _ in 0..100 {
+            if let ChurnEvent::Appear(id) = churn.next("test") {
+                assert_eq!(id, format!("test-churn-{appeared}"));
+                appeared += 1;
+            }
+
+            assert!(churn.len() <= 2);
+        }
+
+        // once full, the next event removes a sensor
+        while churn.len() < 2 {
+            churn.next("test");
+        }
+        assert!(matches!(churn.next("test"), ChurnEvent::Disappear(_)));
+
+        churn.forget("unknown");
+        assert_eq!(churn.len(), 1);
+    }
+}
@@ -0,0 +1,147 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cleanup of the test devices created during the load runs.
+//!
+//! Each device is unregistered through the Pairing API, revoking its credentials, and its local
+//! store is wiped, so that it can be registered again from scratch.
+
+use crate::astarte::{ConnectionConfigBuilder, CERTIFICATE_FILES, CREDENTIAL_FILE};
+use crate::remote_config::REMOTE_CONFIG_FILE;
+use crate::snapshot::SNAPSHOT_FILE;
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Suffixes of the SQLite database and of its journals
+const SQLITE_SUFFIXES: [&str; 4] = ["", "-wal", "-shm", "-journal"];
+
+/// Unregister the devices, unless only their local store is wiped
+pub async fn cleanup(devices: &[ConnectionConfigBuilder], local_only: bool) -> eyre::Result<()> {
+    for device in devices {
+        let device_id = device.device_id().unwrap_or_default();
+
+        if !local_only {
+            device
+                .unregister()
+                .await
+                .wrap_err_with(|| format!("couldn't unregister the device {device_id}"))?;
+
+            info!("unregistered the device {device_id}");
+        }
+
+        wipe_store(device, &device_id).await?;
+    }
+
+    Ok(())
+}
+
+/// Remove the files written by the device in its store directory, and the directory itself if it
+/// belongs to a device of a fleet
+async fn wipe_store(device: &ConnectionConfigBuilder, device_id: &str) -> eyre::Result<()> {
+    let Some(dir) = device.store_directory() else {
+        return Ok(());
+    };
+
+    let mut files: Vec<PathBuf> = [CREDENTIAL_FILE, REMOTE_CONFIG_FILE, SNAPSHOT_FILE]
+        .into_iter()
+        .chain(CERTIFICATE_FILES)
+        .map(|file| dir.join(file))
+        .collect();
+
+    if let Some(database) = device.sqlite_database()? {
+        files.extend(SQLITE_SUFFIXES.iter().map(|suffix| {
+            let mut file = database.clone().into_os_string();
+            file.push(suffix);
+
+            PathBuf::from(file)
+        }));
+    }
+
+    for file in &files {
+        match tokio::fs::remove_file(file).await {
+            Ok(()) => debug!("removed {}", file.display()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err).wrap_err_with(|| format!("couldn't remove {}", file.display()))
+            }
+        }
+    }
+
+    if is_fleet_store(dir, device_id) {
+        // the other files written in the directory are kept
+        if let Err(err) = tokio::fs::remove_dir(dir).await {
+            debug!("couldn't remove {}: {err}", dir.display());
+        }
+    }
+
+    info!("wiped the local store of the device {device_id}");
+
+    Ok(())
+}
+
+/// Check if the store directory is the subdirectory of a device of a fleet, named after its id
+fn is_fleet_store(dir: &Path, device_id: &str) -> bool {
+    !device_id.is_empty() && dir.file_name() == Some(device_id.as_ref())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config_file::ConfigLocation;
+    use crate::store::DEFAULT_SQLITE_FILE;
+
+    #[tokio::test]
+    async fn test_wipe_store() {
+        let base = std::env::temp_dir().join(format!("cleanup-{}", uuid::Uuid::new_v4()));
+        let toml = format!(
+            r#"
+            [astarte]
+            connection = "mqtt"
+            store_directory = "{}"
+
+            [astarte.mqtt]
+            realm = "realm"
+            device_id = "device"
+            pairing_url = "http://127.0.0.1:1"
+            credentials_secret = "secret"
+            ignore_ssl_errors = false
+            "#,
+            base.display()
+        );
+        let config = base.with_extension("toml");
+        tokio::fs::write(&config, toml).await.unwrap();
+
+        let base_device = ConnectionConfigBuilder::load(&ConfigLocation::new(&config))
+            .await
+            .unwrap();
+
+        let device = base_device.fleet_device(&crate::fleet::FleetDevice {
+            index: 0,
+            device_id: "device".to_string(),
+            credentials_secret: None,
+        });
+        let dir = device.store_directory().unwrap().to_path_buf();
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        for file in [CREDENTIAL_FILE, DEFAULT_SQLITE_FILE, "prop-cache.db-wal"] {
+            tokio::fs::write(dir.join(file), "").await.unwrap();
+        }
+
+        // the secret can't unregister the device
+        assert!(cleanup(std::slice::from_ref(&device), false).await.is_err());
+        assert!(dir.join(CREDENTIAL_FILE).exists());
+
+        cleanup(&[device], true).await.unwrap();
+        assert!(!dir.exists());
+        assert!(base.exists());
+
+        tokio::fs::remove_dir_all(base).await.unwrap();
+        tokio::fs::remove_file(config).await.unwrap();
+    }
+}
@@ -6,27 +6,1229 @@
 
 //! CLI configuration options
 
+use crate::astarte::MqttOptions;
+use crate::correlation::Correlation;
+use crate::degradation::DegradationConfig;
+use crate::duration;
+use crate::duty_cycle::DutyCycle;
+use crate::env_prefix::{EnvPrefix, ENV_PREFIX_ARG};
+use crate::extra_stream::ExtraStreamConfig;
+use crate::fleet::DeviceIdTemplate;
+use crate::log_file::LogFileOptions;
+use crate::logging::{LogFormat, Verbosity};
 use crate::math::MathFunction;
-use clap::Parser;
+use crate::output::OutputConfig;
+use crate::path_template::{PathTemplate, DEFAULT_PATH_TEMPLATE};
+use crate::privacy::PrivacyConfig;
+use crate::retention::{Eviction, RetentionLimits};
+use crate::sensor_id::{addresses, is_group, parse_group, SensorIdTemplate};
+use crate::store::{SqliteOptions, StoreType};
+use crate::supervisor::RestartPolicy;
+use crate::timestamp::TimestampConfig;
+use crate::window::ActiveWindow;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, eyre, WrapErr};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Configuration for the values to be sent to Astarte
+///
+/// The options of the connection and of the streams are declared by the commands using them, while
+/// the logging options and the ones selecting the configuration are shared by every command. The
+/// options of the `run` command can also be given without a command, streaming data by default.
 #[derive(Debug, Clone, Parser)]
 #[clap(version, about)]
 pub struct Config {
+    /// Command to execute, streaming data if missing
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+    /// Profile of the `config.toml` file, whose `[profile.<name>]` values override the top level
+    /// ones
+    #[clap(long, env = "ASTARTE_PROFILE", global = true)]
+    pub profile: Option<String>,
+    /// Prefix of the environment variables, replacing the `ASTARTE_` one and prepended to the
+    /// others, e.g. `MYTEST_` to read `MYTEST_REALM` and `MYTEST_MATH_FUNCTION`
+    ///
+    /// It can only be set on the command line, since it selects the variables to read.
+    #[clap(long, global = true)]
+    pub env_prefix: Option<String>,
+    /// Connection to Astarte, given without a command
+    #[clap(flatten)]
+    pub connection: ConnectionOptions,
+    /// Streams of the device, given without a command
+    #[clap(flatten)]
+    pub stream: StreamOptions,
+    /// Logs of the application
+    #[clap(flatten)]
+    pub logging: LoggingOptions,
+}
+
+/// Options of the connection to Astarte and of the devices connecting
+#[derive(Debug, Clone, Args)]
+pub struct ConnectionOptions {
+    /// PEM bundle of the root certificates trusted when connecting to Astarte through MQTT and
+    /// pairing the device, e.g. for Astarte instances with a private PKI
+    ///
+    /// The bundle replaces the native root certificates. Takes precedence over the
+    /// `ca_certificate` of the `config.toml`.
+    #[clap(long, env = "ASTARTE_CA_CERTIFICATE")]
+    pub ca_certificate: Option<PathBuf>,
+    /// URL of the HTTP or SOCKS proxy the pairing connections go through, e.g.
+    /// `http://proxy:3128` or `socks5://proxy:1080`
+    ///
+    /// The MQTT connection to the broker doesn't go through the proxy, since the Astarte Device SDK
+    /// doesn't allow to tunnel it, so the broker must be reachable directly. Takes precedence over
+    /// the `proxy` of the `config.toml`.
+    #[clap(long, env = "ASTARTE_PROXY")]
+    pub proxy: Option<String>,
+    /// Connection attempts retried, after a backoff, while Astarte or the Message Hub are
+    /// unreachable at startup
+    #[clap(long, env = "CONNECT_RETRIES", default_value = "0")]
+    pub connect_retries: u32,
+    /// Time before retrying to connect, doubled at every failed attempt (e.g. `500ms`, `2s`; bare
+    /// numbers are milliseconds)
+    #[clap(
+        long,
+        env = "CONNECT_BACKOFF",
+        default_value = "1000",
+        value_parser = duration::parse_millis
+    )]
+    pub connect_backoff: u64,
+    /// Maximum time before retrying to connect (e.g. `30s`, `1m`; bare numbers are milliseconds)
+    #[clap(
+        long,
+        env = "CONNECT_BACKOFF_MAX",
+        default_value = "30000",
+        value_parser = duration::parse_millis
+    )]
+    pub connect_backoff_max: u64,
+    /// Maximum random deviation of the backoff before retrying to connect, as a percentage
+    #[clap(
+        long,
+        env = "CONNECT_JITTER_PERCENT",
+        default_value = "20",
+        value_parser = clap::value_parser!(u8).range(0..=100)
+    )]
+    pub connect_jitter_percent: u8,
+    /// Re-attach the node to the Message Hub when it's detached or the Message Hub restarts,
+    /// instead of exiting
+    #[clap(long, env = "REATTACH")]
+    pub reattach: bool,
+    /// Attempts retried, after the connection backoff, to re-attach the node, retrying forever if
+    /// not set
+    #[clap(long, env = "REATTACH_RETRIES", requires = "reattach")]
+    pub reattach_retries: Option<u32>,
+    /// Store of the device properties and of the data retained while offline
+    #[clap(long, env = "ASTARTE_STORE", value_enum, default_value_t)]
+    pub store: StoreType,
+    /// File name and pragmas of the SQLite store, taking precedence over the ones of the
+    /// `config.toml`
+    #[clap(flatten)]
+    pub sqlite: SqliteOptions,
+    /// Maximum number of messages retained while offline
+    #[clap(long, env = "RETENTION_MAX_MESSAGES")]
+    pub retention_max_messages: Option<u64>,
+    /// Maximum bytes used by the SQLite store, evicting the retained messages exceeding it
+    #[clap(long, env = "RETENTION_MAX_SIZE")]
+    pub retention_max_size: Option<u64>,
+    /// Retained messages evicted when exceeding the limits
+    #[clap(long, env = "RETENTION_EVICTION", value_enum, default_value_t)]
+    pub retention_eviction: Eviction,
+    /// Time the primary connection can stay down before failing over to the fallback one, if
+    /// configured (e.g. `500ms`, `2s`; bare numbers are milliseconds)
+    #[clap(long, env = "FAILOVER_AFTER", value_parser = duration::parse_millis)]
+    pub failover_after: Option<u64>,
+    /// Time without data sent or received through the connection before rebuilding it, disabled
+    /// if not set (e.g. `30s`, `1m`; bare numbers are milliseconds)
+    #[clap(long, env = "CONNECTION_WATCHDOG", value_parser = duration::parse_positive_millis)]
+    pub connection_watchdog: Option<u64>,
+    /// Number of devices simulated by the process, each one with its own connection and streams
+    ///
+    /// Defaults to the devices listed in the `--device-ids` file, or to a single device.
+    #[clap(long, env = "DEVICES", value_parser = clap::value_parser!(u32).range(1..))]
+    pub devices: Option<u32>,
+    /// Template of the ids of the simulated devices
+    ///
+    /// The `{n}` and `{id}` placeholders are replaced by the index of the device and by a device id
+    /// derived from the configured one and the index. If not set, the first device uses the
+    /// configured id, while the others derive theirs from it.
+    #[clap(long, env = "DEVICE_ID_TEMPLATE", value_parser = DeviceIdTemplate::parse)]
+    pub device_id_template: Option<DeviceIdTemplate>,
+    /// File listing the ids of the simulated devices, one per line followed by its optional
+    /// credentials secret
+    #[clap(
+        long = "device-ids",
+        env = "DEVICE_IDS",
+        conflicts_with = "device_id_template"
+    )]
+    pub device_ids: Option<PathBuf>,
+    /// Generate a random device id and register it at startup with the pairing token, instead of
+    /// using the configured one
+    ///
+    /// The credentials secret is persisted in the subdirectory of the store directory named after
+    /// the device id.
+    #[clap(
+        long,
+        env = "RANDOM_DEVICE_ID",
+        conflicts_with_all = ["device_ids", "device_id_template"]
+    )]
+    pub random_device_id: bool,
+    /// Log the values that would be sent, with the endpoint and the timestamp, without connecting
+    /// to Astarte
+    #[clap(long, env = "DRY_RUN", conflicts_with = "random_device_id")]
+    pub dry_run: bool,
+    /// Keepalive, connection timeout and channel size of the MQTT connection, taking precedence
+    /// over the ones of the `config.toml`
+    #[clap(flatten)]
+    pub mqtt: MqttOptions,
+}
+
+/// Options of the data streamed by the device
+#[derive(Debug, Clone, Args)]
+pub struct StreamOptions {
     /// Math function the device will use to send data to Astarte
     #[clap(short, long, default_value = "default", env = "MATH_FUNCTION")]
     pub math_function: MathFunction,
     /// Interface name to send data to
+    ///
+    /// Defaults to the bundled interface matching the output type.
+    #[clap(long, env = "INTERFACE_NAME")]
+    pub interface_datastream_do: Option<String>,
+    /// Template of the endpoint the data is sent to
+    ///
+    /// The `{sensor_id}` and `{mapping}` placeholders are replaced by the id of the sensor and by
+    /// the name of the mapping. When sending objects, the `{mapping}` level is dropped.
+    #[clap(
+        long,
+        env = "PATH_TEMPLATE",
+        default_value = DEFAULT_PATH_TEMPLATE,
+        value_parser = PathTemplate::parse
+    )]
+    pub path_template: PathTemplate,
+    /// Mappings the data is sent to at each sample, replacing the `{mapping}` placeholder
+    ///
+    /// The values of the first mapping are the ones of the stream, while the values of the others
+    /// are derived independently, with a phase and amplitude offset specific to each mapping.
+    #[clap(
+        long,
+        default_value = "value",
+        value_delimiter = ',',
+        conflicts_with = "aggregate",
+        env = "MAPPINGS"
+    )]
+    pub mappings: Vec<String>,
+    /// Id of the sensor used in the endpoints of the interfaces
+    ///
+    /// The `{n}` and `{uuid}` placeholders generate the ids of multiple sensors, replaced by the
+    /// index of the sensor and by a UUID derived from the device id and the index. Defaults to the
+    /// `sensor_id` of the `config.toml` file, or to `test` if missing.
+    #[clap(long, env = "SENSOR_ID", value_parser = SensorIdTemplate::parse)]
+    pub sensor_id: Option<SensorIdTemplate>,
+    /// Number of independent sensors simulated by the device
+    ///
+    /// Each sensor has its own configuration, generator state and timer. Without placeholders in
+    /// the `--sensor-id`, the first sensor uses it as is, while the others append `-1`, `-2` and so
+    /// on to it. Defaults to the `sensors` of the `config.toml` file, or to 1 if missing.
+    #[clap(long, env = "SENSORS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub sensors: Option<u32>,
+    /// Math functions assigned round-robin to the sensors, instead of the `--math-function`
+    #[clap(long, value_delimiter = ',', env = "SENSOR_FUNCTIONS")]
+    pub sensor_functions: Vec<MathFunction>,
+    /// Math functions of the groups of sensors, e.g. `hvac.*=saw`
+    ///
+    /// Takes precedence over the `--sensor-functions`, while the math functions set for a sensor
+    /// id take precedence over the ones of its group.
+    #[clap(
+        long,
+        value_delimiter = ',',
+        env = "GROUP_FUNCTIONS",
+        value_parser = parse_group_function
+    )]
+    pub group_functions: Vec<(String, MathFunction)>,
+    /// Math functions of the sensors by id or group, declared in the `[functions]` table of the
+    /// `config.toml`
+    ///
+    /// Takes precedence over the `--sensor-functions`.
+    #[clap(skip)]
+    pub functions_by_sensor: BTreeMap<String, MathFunction>,
+    /// Intervals between two samples assigned round-robin to the sensors, instead of the
+    /// `--interval-btw-samples` (e.g. `500ms`, `2s`; bare numbers are milliseconds)
+    #[clap(
+        long,
+        value_delimiter = ',',
+        env = "SENSOR_INTERVALS",
+        value_parser = duration::parse_positive_millis
+    )]
+    pub sensor_intervals: Vec<u64>,
+    /// Interval between two samples of the groups of sensors, e.g. `hvac.*=500ms` (bare numbers are
+    /// milliseconds)
+    ///
+    /// Takes precedence over the `--sensor-intervals`, while the intervals set for a sensor id take
+    /// precedence over the ones of its group.
     #[clap(
         long,
-        default_value = "org.astarte-platform.genericsensors.Values",
-        env = "INTERFACE_NAME"
+        value_delimiter = ',',
+        env = "GROUP_INTERVALS",
+        value_parser = parse_group_interval
+    )]
+    pub group_intervals: Vec<(String, u64)>,
+    /// Interval between two samples of the sensors by id or group, declared in the
+    /// `[intervals]` table of the `config.toml`
+    ///
+    /// Takes precedence over the `--sensor-intervals`.
+    #[clap(skip)]
+    pub intervals_by_sensor: BTreeMap<String, u64>,
+    /// Scales of the sensors by id or group, declared in the `[scales]` table of the `config.toml`
+    ///
+    /// Takes precedence over the `--scale`.
+    #[clap(skip)]
+    pub scales_by_sensor: BTreeMap<String, f64>,
+    /// Priorities assigned round-robin to the sensors, the ones with a higher priority are sampled
+    /// first when due at the same time
+    #[clap(long, value_delimiter = ',', env = "SENSOR_PRIORITIES")]
+    pub sensor_priorities: Vec<u8>,
+    /// Priorities of the sensors by id or group, declared in the `[priorities]` table of the
+    /// `config.toml`
+    ///
+    /// Takes precedence over the `--sensor-priorities`.
+    #[clap(skip)]
+    pub priorities_by_sensor: BTreeMap<String, u8>,
+    /// Sensors whose values are derived from the last value of another sensor, e.g.
+    /// `humidity=temperature:-0.5:80:2` for `-0.5 * temperature + 80` plus a random noise up to
+    /// `2`
+    ///
+    /// Takes precedence over the math function of the correlated sensor.
+    #[clap(long, value_delimiter = ',', env = "CORRELATIONS", value_parser = Correlation::parse)]
+    pub correlations: Vec<Correlation>,
+    /// Name of the sensor published on the AvailableSensors interface
+    ///
+    /// Defaults to the math function and the output type.
+    #[clap(long, env = "SENSOR_NAME")]
+    pub sensor_name: Option<String>,
+    /// Measurement unit of the sensor published on the AvailableSensors interface
+    #[clap(long, env = "SENSOR_UNIT")]
+    pub sensor_unit: Option<String>,
+    /// Additional stream sending data on another interface, can be repeated
+    ///
+    /// The stream is configured with a comma separated list of options: `interface` (required),
+    /// `path`, `function`, `interval` and `type`, for instance
+    /// `interface=org.astarte-platform.stream-rust-test.IntegerValues,type=integer,interval=200`.
+    /// Multiple streams can be set in the environment variable, separated by `;`.
+    #[clap(
+        long = "stream",
+        value_parser = ExtraStreamConfig::parse,
+        value_delimiter = ';',
+        env = "EXTRA_STREAMS"
+    )]
+    pub extra_streams: Vec<ExtraStreamConfig>,
+    /// Send the edge-case values of every Astarte type instead of the generated ones
+    ///
+    /// At each sample, the next boundary value of a type (e.g. extreme doubles and integers, empty
+    /// arrays and huge strings) is sent on the bundled interface of the type.
+    #[clap(
+        long,
+        conflicts_with_all = ["aggregate", "array_length", "interface_datastream_do", "mappings"],
+        env = "FUZZ_TYPES"
+    )]
+    pub fuzz_types: bool,
+    /// Conversion of the values sent to the interface
+    #[clap(flatten)]
+    pub output: OutputConfig,
+    /// Calibrated noise added to the values sent to the interface
+    #[clap(flatten)]
+    pub privacy: PrivacyConfig,
+    /// Explicit timestamps of the values sent to the interface
+    #[clap(flatten)]
+    pub timestamp: TimestampConfig,
+    /// Interval the device waits before sending data to Astarte (e.g. `500ms`, `2s`; bare numbers
+    /// are milliseconds)
+    #[clap(
+        short,
+        long,
+        default_value = "1000",
+        env = "INTERVAL_BTW_SAMPLES",
+        value_parser = duration::parse_positive_millis
     )]
-    pub interface_datastream_do: String,
-    /// Milliseconds the device must wait before sending data to Astarte
-    #[clap(short, long, default_value = "1000", env = "INTERVAL_BTW_SAMPLES")]
     pub interval_btw_samples: u64,
+    /// Samples per second sent by each sensor, instead of the `--interval-btw-samples`
+    ///
+    /// The interval is derived from the rate, and the samples missed because sending took too long
+    /// are sent right after to catch up, maintaining the throughput.
+    #[clap(
+        long,
+        env = "RATE",
+        conflicts_with = "interval_btw_samples",
+        value_parser = clap::value_parser!(u32).range(1..=1000)
+    )]
+    pub rate: Option<u32>,
     /// Scale for the generation of the data to send
     #[clap(short, long, default_value = "1.0", env = "SCALE")]
     pub scale: f64,
+    /// Path to a TOML file describing a piecewise schedule of math functions
+    ///
+    /// When set, the stream switches between the functions of the schedule, ignoring the
+    /// `--math-function` option.
+    #[clap(long, env = "SCHEDULE_PATH")]
+    pub schedule: Option<PathBuf>,
+    /// Command computing the values of the `exec` math function
+    ///
+    /// The process receives each base value as a line on its standard input and must answer with
+    /// a line containing the computed value on its standard output. The arguments are split
+    /// following the quoting rules of a POSIX shell, e.g. `python3 -c 'print(1)'`, but the command
+    /// is not run by a shell.
+    #[clap(long, env = "EXEC_COMMAND")]
+    pub exec_command: Option<String>,
+    /// Time to wait for the `exec` math function process to answer (e.g. `500ms`, `2s`; bare
+    /// numbers are milliseconds)
+    #[clap(
+        long,
+        default_value = "1000",
+        env = "EXEC_TIMEOUT",
+        value_parser = duration::parse_millis
+    )]
+    pub exec_timeout: u64,
+    /// Path to a TOML file with the constraints of the parameters updatable from Astarte
+    #[clap(long, env = "VALIDATION_SCHEMA_PATH")]
+    pub validation_schema: Option<PathBuf>,
+    /// Interval between two heartbeats sent on the critical lane (e.g. `500ms`, `2s`; bare numbers
+    /// are milliseconds)
+    ///
+    /// Heartbeats are sent even when the stream is paused. If not set, no heartbeat is sent.
+    #[clap(long, env = "HEARTBEAT_INTERVAL", value_parser = duration::parse_positive_millis)]
+    pub heartbeat_interval: Option<u64>,
+    /// Threshold above which an alarm is sent on the critical lane
+    ///
+    /// The alarm is raised when the generated value crosses the threshold and cleared when it
+    /// returns below it, even when the stream is paused. If not set, no alarm is sent.
+    #[clap(long, env = "ALARM_THRESHOLD")]
+    pub alarm_threshold: Option<f64>,
+    /// Degradation of the stream under sustained send errors
+    #[clap(flatten)]
+    pub degradation: DegradationConfig,
+    /// Pause the streams while the connection to Astarte is down, instead of storing their data to
+    /// be sent once reconnected
+    #[clap(long, env = "PAUSE_WHILE_DISCONNECTED")]
+    pub pause_while_disconnected: bool,
+    /// Reload the stream options of the `config.toml` whenever the file changes
+    ///
+    /// The file is reloaded only with this option, the SIGHUP doesn't reload it since it rotates
+    /// the credentials.
+    #[clap(long, env = "WATCH_CONFIG")]
+    pub watch_config: bool,
+    /// Print the effective configuration, merging the CLI, the environment variables and the
+    /// `config.toml`, and exit
+    ///
+    /// The secrets are redacted.
+    #[clap(long)]
+    pub print_config: bool,
+    /// Interval between two steps of the property lifecycle (e.g. `500ms`, `2s`; bare numbers are
+    /// milliseconds)
+    ///
+    /// At each step a device property is alternately set and unset, to exercise the property unset
+    /// and purge flow. If not set, no property is cycled.
+    #[clap(long, env = "PROPERTY_CYCLE_INTERVAL", value_parser = duration::parse_positive_millis)]
+    pub property_cycle_interval: Option<u64>,
+    /// Interval between two reliability probes (e.g. `500ms`, `2s`; bare numbers are milliseconds)
+    ///
+    /// Each probe sends an increasing sequence number on mappings with unreliable, guaranteed and
+    /// unique reliability, counting the messages sent with each one. If not set, no probe is sent.
+    #[clap(long, env = "RELIABILITY_INTERVAL", value_parser = duration::parse_positive_millis)]
+    pub reliability_interval: Option<u64>,
+    /// Interval between two changes of the sensor population (e.g. `500ms`, `2s`; bare numbers are
+    /// milliseconds)
+    ///
+    /// At each step a new sensor appears or a sensor previously appeared disappears, publishing or
+    /// removing its configuration and metadata. If not set, the sensors don't churn.
+    #[clap(long, env = "CHURN_INTERVAL", value_parser = duration::parse_positive_millis)]
+    pub churn_interval: Option<u64>,
+    /// Maximum sensors appeared through the churn at the same time
+    #[clap(
+        long,
+        env = "CHURN_MAX_SENSORS",
+        default_value = "5",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub churn_max_sensors: u32,
+    /// Maximum sensors created from Astarte through the enable property, including the churned
+    /// ones, at the same time
+    #[clap(
+        long,
+        env = "REMOTE_MAX_SENSORS",
+        default_value = "100",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub remote_max_sensors: u32,
+    /// Interval between two status reports (e.g. `500ms`, `2s`; bare numbers are milliseconds)
+    ///
+    /// Each report publishes the uptime, the messages sent and failed, the last error and the
+    /// current math function on the Status interface. If not set, no status is published.
+    #[clap(long, env = "STATUS_INTERVAL", value_parser = duration::parse_positive_millis)]
+    pub status_interval: Option<u64>,
+    /// Interval between two diagnostics reports (e.g. `500ms`, `2s`; bare numbers are milliseconds)
+    ///
+    /// Each report publishes the transport, the hash of the broker URL, the reconnections and the
+    /// last disconnection reason on the Diagnostics interface, also when the connection is
+    /// restored. If not set, no diagnostics are published.
+    #[clap(long, env = "DIAGNOSTICS_INTERVAL", value_parser = duration::parse_positive_millis)]
+    pub diagnostics_interval: Option<u64>,
+    /// Interval between two snapshots of the runtime state of the streams (e.g. `500ms`, `2s`; bare
+    /// numbers are milliseconds)
+    ///
+    /// The snapshot is saved in the store directory and restored at startup, so that the signals
+    /// continue from where they left off after a restart. If not set, no snapshot is saved.
+    #[clap(long, env = "SNAPSHOT_INTERVAL", value_parser = duration::parse_positive_millis)]
+    pub snapshot_interval: Option<u64>,
+    /// Maximum time the stream runs before shutting down (e.g. `90s`, `10m`; bare numbers are
+    /// seconds)
+    ///
+    /// When exceeded, the stream is stopped, the pending data is flushed and a summary is logged.
+    /// If the shutdown doesn't complete in time, the process is forcefully terminated.
+    #[clap(
+        long,
+        env = "MAX_RUNTIME",
+        conflicts_with = "duration",
+        value_parser = duration::parse_whole_secs
+    )]
+    pub max_runtime: Option<u64>,
+    /// Runtime before shutting down (e.g. `90s`, `1h30m`; bare numbers are seconds)
+    ///
+    /// Same as the `--max-runtime`, with a human friendly duration.
+    #[clap(long, env = "DURATION", value_parser = duration::parse_secs)]
+    pub duration: Option<Duration>,
+    /// Number of samples sent by the sensors before shutting down
+    ///
+    /// Once sent, the stream is stopped, the pending data is flushed and a summary is logged, as
+    /// for the `--max-runtime`.
+    #[clap(long, env = "COUNT", value_parser = clap::value_parser!(u64).range(1..))]
+    pub count: Option<u64>,
+    /// Time before restarting a sensor or an additional stream that failed to send a sample,
+    /// doubled at every consecutive failure (e.g. `500ms`, `2s`; bare numbers are milliseconds)
+    #[clap(
+        long,
+        env = "RESTART_BACKOFF",
+        default_value = "1000",
+        value_parser = duration::parse_millis
+    )]
+    pub restart_backoff: u64,
+    /// Maximum time before restarting a failed sensor or additional stream (e.g. `30s`, `1m`; bare numbers are
+    /// milliseconds)
+    #[clap(
+        long,
+        env = "RESTART_BACKOFF_MAX",
+        default_value = "60000",
+        value_parser = duration::parse_millis
+    )]
+    pub restart_backoff_max: u64,
+    /// Consecutive restarts of a failed sensor or additional stream before stopping the process,
+    /// unlimited if missing
+    #[clap(long, env = "MAX_RESTARTS")]
+    pub max_restarts: Option<u32>,
+    /// Seed of the per-sensor phase and amplitude offsets
+    ///
+    /// When set, each sensor applies a persistent offset derived from the device id, the sensor
+    /// id and the seed, so that devices using the same math function don't send identical data.
+    #[clap(long, env = "OFFSET_SEED")]
+    pub offset_seed: Option<u64>,
+    /// Directory containing additional interface JSON files to load at startup
+    ///
+    /// The bundled interfaces are always loaded; a custom interface with the same name as a
+    /// bundled one must have a newer version to replace it.
+    #[clap(long, env = "INTERFACES_DIR")]
+    pub interfaces_dir: Option<PathBuf>,
+    /// Speedup of the simulated clock with respect to the wall clock
+    ///
+    /// The schedule and the generated values advance this many times faster than the wall clock,
+    /// while only one of every `clock-speedup` samples is sent.
+    #[clap(
+        long,
+        env = "CLOCK_SPEEDUP",
+        default_value = "1",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub clock_speedup: u32,
+    /// Maximum random deviation of each interval between two samples, as a percentage of the
+    /// nominal interval
+    #[clap(
+        long,
+        env = "JITTER_PERCENT",
+        default_value = "0",
+        value_parser = clap::value_parser!(u8).range(0..=100)
+    )]
+    pub jitter_percent: u8,
+    /// Warm-up time, during which the sample rate grows linearly from the `--ramp-up-start-percent`
+    /// of the configured rate to the full rate (e.g. `30s`, `5m`; bare numbers are seconds)
+    #[clap(long, env = "RAMP_UP", default_value = "0", value_parser = duration::parse_whole_secs)]
+    pub ramp_up: u64,
+    /// Initial sample rate of the ramp-up, as a percentage of the configured rate
+    #[clap(
+        long,
+        env = "RAMP_UP_START_PERCENT",
+        default_value = "10",
+        value_parser = clap::value_parser!(u8).range(1..=100)
+    )]
+    pub ramp_up_start_percent: u8,
+    /// Maximum telemetry messages per second sent by the device, across every stream
+    ///
+    /// The messages exceeding the rate wait for their turn, while the critical events are never
+    /// limited. If not set, the rate isn't limited.
+    #[clap(long, env = "MAX_RATE", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_rate: Option<u32>,
+    /// Messages sent back-to-back after an idle period before the `--max-rate` applies, defaults
+    /// to one second of messages
+    #[clap(
+        long,
+        env = "MAX_RATE_BURST",
+        requires = "max_rate",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub max_rate_burst: Option<u32>,
+    /// Number of samples sent back-to-back every interval, updatable from Astarte
+    #[clap(long, env = "BURST_SIZE", default_value = "1", value_parser = parse_burst_size)]
+    pub burst_size: usize,
+    /// Daily time windows, in UTC, during which the streams send data, e.g. `08:00-18:00` or
+    /// `mon-fri@08:00-18:00`
+    ///
+    /// Outside the windows the samples are generated but not sent. Defaults to always sending.
+    #[clap(long, value_delimiter = ',', env = "ACTIVE_WINDOW", value_parser = ActiveWindow::parse)]
+    pub active_window: Vec<ActiveWindow>,
+    /// Duty cycle of the sensors, sending data for `<on>` and then silent for `<off>`, e.g.
+    /// `30s:90s` or `1m:5m` (bare numbers are seconds)
+    #[clap(long, env = "DUTY_CYCLE", value_parser = DutyCycle::parse)]
+    pub duty_cycle: Option<DutyCycle>,
+    /// Duty cycles of the sensors by id or group, declared in the `[duty_cycles]` table of the
+    /// `config.toml`
+    ///
+    /// Takes precedence over the `--duty-cycle`.
+    #[clap(skip)]
+    pub duty_cycles_by_sensor: BTreeMap<String, DutyCycle>,
+}
+
+/// Options of the logs, shared by every command
+#[derive(Debug, Clone, Args)]
+pub struct LoggingOptions {
+    /// Increase the verbosity of the logs, `-v` traces the application and `-vv` everything
+    ///
+    /// Takes precedence over the `RUST_LOG` environment variable.
+    #[clap(
+        short,
+        long,
+        action = clap::ArgAction::Count,
+        conflicts_with = "quiet",
+        global = true
+    )]
+    pub verbose: u8,
+    /// Only log the warnings, the errors and the final summary
+    ///
+    /// Takes precedence over the `RUST_LOG` environment variable.
+    #[clap(short, long, global = true)]
+    pub quiet: bool,
+    /// Format of the logs, either human readable `text` or `json` objects to be ingested by a log
+    /// collector
+    #[clap(long, env = "LOG_FORMAT", value_enum, default_value_t, global = true)]
+    pub log_format: LogFormat,
+    /// Logs written to a rotated file
+    #[clap(flatten)]
+    pub log_file: LogFileOptions,
+}
+
+/// Options of the commands streaming data to Astarte
+#[derive(Debug, Clone, Args)]
+pub struct StreamCommand {
+    /// Connection to Astarte
+    #[clap(flatten)]
+    pub connection: ConnectionOptions,
+    /// Streams of the device
+    #[clap(flatten)]
+    pub stream: StreamOptions,
+}
+
+impl Config {
+    /// Parse the CLI options, reading the environment variables with the prefix of the
+    /// `--env-prefix` option
+    pub fn parse_with_env_prefix() -> Self {
+        // the prefix is read first, ignoring the other options
+        let prefix = Self::command()
+            .ignore_errors(true)
+            .try_get_matches()
+            .ok()
+            .and_then(|matches| matches.get_one::<String>(ENV_PREFIX_ARG).cloned());
+
+        let mut command = EnvPrefix::new(prefix).command(Self::command());
+        let mut matches = command.get_matches_mut();
+
+        Self::from_command_matches(&command, &mut matches)
+            .unwrap_or_else(|err| err.format(&mut command).exit())
+    }
+
+    /// Parse the matches of the CLI, using the options given after the command in place of the
+    /// ones given without a command
+    ///
+    /// The options of the connection and of the streams given without a command only configure the
+    /// default streaming, so they are rejected before a command.
+    fn from_command_matches(
+        command: &clap::Command,
+        matches: &mut ArgMatches,
+    ) -> Result<Self, clap::Error> {
+        if let Some((name, _)) = matches.subcommand() {
+            let before_command = command
+                .get_arguments()
+                .filter(|arg| !arg.is_global_set())
+                .find(|arg| {
+                    matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+                });
+
+            if let Some(arg) = before_command {
+                return Err(clap::Error::raw(
+                    ErrorKind::ArgumentConflict,
+                    format!("the option '{arg}' must be given after the '{name}' command"),
+                ));
+            }
+        }
+
+        let mut cfg = Self::from_arg_matches_mut(matches)?;
+
+        match &cfg.command {
+            Some(
+                Command::Run(options)
+                | Command::Bench(options)
+                | Command::Validate(options)
+                | Command::Replay { options, .. },
+            ) => {
+                cfg.connection = options.connection.clone();
+                cfg.stream = options.stream.clone();
+            }
+            Some(Command::ValidateInterfaces(stream)) => cfg.stream = stream.clone(),
+            Some(Command::Cleanup { connection, .. }) => cfg.connection = connection.clone(),
+            Some(
+                Command::Describe | Command::GenerateConfig { .. } | Command::Completions { .. },
+            )
+            | None => {}
+        }
+
+        Ok(cfg)
+    }
+
+    /// Prefix of the environment variables
+    pub fn env_prefix(&self) -> EnvPrefix {
+        EnvPrefix::new(self.env_prefix.clone())
+    }
+}
+
+impl ConnectionOptions {
+    /// Policy retrying the initial connection to Astarte
+    pub fn connect_retry_policy(&self) -> RestartPolicy {
+        RestartPolicy::new(
+            Duration::from_millis(self.connect_backoff),
+            Duration::from_millis(self.connect_backoff_max),
+            Some(self.connect_retries),
+        )
+    }
+
+    /// Limits of the data retained while offline, none if not capped
+    pub fn retention_limits(&self) -> Option<RetentionLimits> {
+        RetentionLimits::new(
+            self.retention_max_messages,
+            self.retention_max_size,
+            self.retention_eviction,
+        )
+    }
+
+    /// Policy retrying to re-attach the node to the Message Hub, none if disabled
+    pub fn reattach_policy(&self) -> Option<RestartPolicy> {
+        self.reattach.then(|| {
+            RestartPolicy::new(
+                Duration::from_millis(self.connect_backoff),
+                Duration::from_millis(self.connect_backoff_max),
+                self.reattach_retries,
+            )
+        })
+    }
+}
+
+impl StreamOptions {
+    /// Number of simulated sensors, defaulting to 1
+    pub fn sensors(&self) -> u32 {
+        self.sensors.unwrap_or(1)
+    }
+
+    /// Template of the ids of the sensors, defaulting to [`DEFAULT_SENSOR_ID`]
+    ///
+    /// [`DEFAULT_SENSOR_ID`]: crate::sensor_id::DEFAULT_SENSOR_ID
+    pub fn sensor_id(&self) -> SensorIdTemplate {
+        self.sensor_id.clone().unwrap_or_default()
+    }
+
+    /// Runtime before shutting down, set by either the `--max-runtime` or the `--duration`
+    pub fn runtime_limit(&self) -> Option<Duration> {
+        self.duration
+            .or_else(|| self.max_runtime.map(Duration::from_secs))
+    }
+
+    /// Math functions configured for any sensor, regardless of their assignment
+    pub fn math_functions(&self) -> impl Iterator<Item = &MathFunction> {
+        std::iter::once(&self.math_function)
+            .chain(&self.sensor_functions)
+            .chain(self.group_functions.iter().map(|(_, function)| function))
+            .chain(self.functions_by_sensor.values())
+    }
+
+    /// Math function of the n-th sensor, with the given id
+    pub fn sensor_function(&self, n: usize, sensor_id: &str) -> &MathFunction {
+        assigned(
+            &self.functions_by_sensor,
+            &self.group_functions,
+            &self.sensor_functions,
+            n,
+            sensor_id,
+        )
+        .unwrap_or(&self.math_function)
+    }
+
+    /// Time between two samples, derived from the `--rate` if set
+    pub fn sample_interval(&self) -> Duration {
+        match self.rate {
+            Some(rate) => Duration::from_secs(1) / rate,
+            None => Duration::from_millis(self.interval_btw_samples),
+        }
+    }
+
+    /// Time between two samples of the n-th sensor, with the given id
+    pub fn sensor_interval(&self, n: usize, sensor_id: &str) -> Duration {
+        assigned(
+            &self.intervals_by_sensor,
+            &self.group_intervals,
+            &self.sensor_intervals,
+            n,
+            sensor_id,
+        )
+        .copied()
+        .map(Duration::from_millis)
+        .unwrap_or_else(|| self.sample_interval())
+    }
+
+    /// Scale of the n-th sensor, with the given id
+    pub fn sensor_scale(&self, n: usize, sensor_id: &str) -> f64 {
+        assigned(&self.scales_by_sensor, &[], &[], n, sensor_id)
+            .copied()
+            .unwrap_or(self.scale)
+    }
+
+    /// Priority of the n-th sensor, with the given id, defaulting to 0
+    pub fn sensor_priority(&self, n: usize, sensor_id: &str) -> u8 {
+        assigned(
+            &self.priorities_by_sensor,
+            &[],
+            &self.sensor_priorities,
+            n,
+            sensor_id,
+        )
+        .copied()
+        .unwrap_or_default()
+    }
+
+    /// Duty cycle of the n-th sensor, with the given id
+    pub fn sensor_duty_cycle(&self, n: usize, sensor_id: &str) -> Option<DutyCycle> {
+        assigned(&self.duty_cycles_by_sensor, &[], &[], n, sensor_id)
+            .copied()
+            .or(self.duty_cycle)
+    }
+}
+
+impl LoggingOptions {
+    /// Verbosity of the logs selected by the flags
+    pub fn verbosity(&self) -> Verbosity {
+        Verbosity::from_flags(self.verbose, self.quiet)
+    }
+}
+
+/// Value assigned to the n-th sensor, either explicitly by id, by group or round-robin from a list
+///
+/// The most specific group of the sensor is used, preferring the CLI groups to the ones of the
+/// file.
+fn assigned<'a, T>(
+    by_sensor: &'a BTreeMap<String, T>,
+    by_group: &'a [(String, T)],
+    round_robin: &'a [T],
+    n: usize,
+    sensor_id: &str,
+) -> Option<&'a T> {
+    by_sensor
+        .get(sensor_id)
+        .or_else(|| {
+            by_group
+                .iter()
+                .map(|(group, value)| (group, value))
+                .chain(by_sensor)
+                .filter(|(group, _)| is_group(group) && addresses(group, sensor_id))
+                .min_by_key(|(group, _)| Reverse(group.len()))
+                .map(|(_, value)| value)
+        })
+        .or_else(|| round_robin.get(n.checked_rem(round_robin.len())?))
+}
+
+/// Maximum number of samples sent back-to-back, also when updated from Astarte, since a burst
+/// delays the commands and the shutdown until it's sent
+pub const MAX_BURST_SIZE: usize = 1000;
+
+/// Parse a positive burst size, up to [`MAX_BURST_SIZE`]
+fn parse_burst_size(s: &str) -> eyre::Result<usize> {
+    let size: usize = s
+        .parse()
+        .wrap_err_with(|| format!("invalid burst size {s}"))?;
+
+    ensure!(size > 0, "the burst size must be positive");
+    ensure!(
+        size <= MAX_BURST_SIZE,
+        "the burst size {size} exceeds the maximum {MAX_BURST_SIZE}"
+    );
+
+    Ok(size)
+}
+
+/// Split a `<group>=<value>` option
+fn split_group(s: &str) -> eyre::Result<(String, &str)> {
+    let (group, value) = s
+        .split_once('=')
+        .ok_or_else(|| eyre!("expected <group>=<value>, e.g. hvac.*=saw"))?;
+
+    Ok((parse_group(group)?, value))
+}
+
+/// Parse the math function of a group of sensors
+fn parse_group_function(s: &str) -> eyre::Result<(String, MathFunction)> {
+    let (group, value) = split_group(s)?;
+    let function = MathFunction::from_str(value, true)
+        .map_err(|err| eyre!("invalid math function {value}: {err}"))?;
+
+    Ok((group, function))
+}
+
+/// Parse the interval of a group of sensors
+fn parse_group_interval(s: &str) -> eyre::Result<(String, u64)> {
+    let (group, value) = split_group(s)?;
+    let interval =
+        duration::parse_millis(value).wrap_err_with(|| format!("invalid interval {value}"))?;
+
+    ensure!(
+        interval > 0,
+        "the interval of group {group} must be positive"
+    );
+
+    Ok((group, interval))
+}
+
+/// Mode of the application, each one with its own options
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Stream data to Astarte, the default if no command is given
+    Run(StreamCommand),
+    /// Measure the throughput of the streams, without connecting to Astarte
+    ///
+    /// The values are generated as in a dry run, without logging them, until the `--count` or the
+    /// `--duration` is reached. The summary reports the messages sent per second.
+    Bench(StreamCommand),
+    /// Send the values recorded in a file to Astarte, then exit
+    Replay {
+        /// File with a JSON object per line, with the `interface`, `path`, `type`, `value` and
+        /// optional `timestamp` of a value
+        #[clap(id = "recording", value_name = "FILE")]
+        file: PathBuf,
+        /// Connection to Astarte and streams the interfaces are loaded from
+        #[clap(flatten)]
+        options: StreamCommand,
+    },
+    /// Check the whole configuration and the interfaces, without connecting to Astarte
+    #[clap(visible_alias = "validate-config")]
+    Validate(StreamCommand),
+    /// Check the interfaces against the configured streams, without connecting to Astarte
+    ValidateInterfaces(StreamOptions),
+    /// Print the supported generators, output types and options as JSON
+    Describe,
+    /// Write a commented example of the config.toml file
+    GenerateConfig {
+        /// File to write, printing the example if missing
+        #[clap(long)]
+        output: Option<PathBuf>,
+        /// Overwrite the file if it already exists
+        #[clap(long)]
+        force: bool,
+    },
+    /// Print the completion script of the shell, e.g. `source <(stream-rust-test completions bash)`
+    Completions {
+        /// Shell to complete the options for
+        #[clap(value_enum)]
+        shell: Shell,
+    },
+    /// Unregister the devices from the realm and wipe their local store and credentials
+    Cleanup {
+        /// Only wipe the local store and credentials, keeping the devices registered
+        #[clap(long)]
+        local_only: bool,
+        /// Connection to Astarte of the devices to clean up
+        #[clap(flatten)]
+        connection: ConnectionOptions,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::duration::Secs;
+
+    fn parse_command(args: &[&str]) -> Result<Config, clap::Error> {
+        let mut command = Config::command();
+        let mut matches = command.try_get_matches_from_mut(args)?;
+
+        Config::from_command_matches(&command, &mut matches)
+    }
+
+    #[test]
+    fn test_command_options() {
+        let cfg =
+            parse_command(&["stream-rust-test", "run", "--scale", "2", "--devices", "3"]).unwrap();
+        assert_eq!(cfg.stream.scale, 2.0);
+        assert_eq!(cfg.connection.devices, Some(3));
+
+        // the default streaming
+        let cfg = parse_command(&["stream-rust-test", "--scale", "2"]).unwrap();
+        assert!(cfg.command.is_none());
+        assert_eq!(cfg.stream.scale, 2.0);
+
+        let cfg = parse_command(&["stream-rust-test", "cleanup", "--store", "volatile"]).unwrap();
+        assert_eq!(cfg.connection.store, StoreType::Volatile);
+
+        // the logging options are shared by every command
+        for args in [
+            ["stream-rust-test", "-v", "cleanup"],
+            ["stream-rust-test", "cleanup", "-v"],
+        ] {
+            assert_eq!(parse_command(&args).unwrap().logging.verbose, 1);
+        }
+
+        assert!(parse_command(&["stream-rust-test", "cleanup", "--scale", "2"]).is_err());
+        assert!(parse_command(&["stream-rust-test", "describe", "--devices", "3"]).is_err());
+        assert!(parse_command(&["stream-rust-test", "--scale", "2", "run"]).is_err());
+    }
+
+    #[test]
+    fn test_sensor_function() {
+        let mut cfg = Config::parse_from([
+            "stream-rust-test",
+            "--math-function",
+            "saw",
+            "--sensor-functions",
+            "sin,random",
+        ]);
+
+        assert_eq!(*cfg.stream.sensor_function(0, "test"), MathFunction::Sin);
+        assert_eq!(
+            *cfg.stream.sensor_function(1, "test-1"),
+            MathFunction::Random
+        );
+        assert_eq!(*cfg.stream.sensor_function(2, "test-2"), MathFunction::Sin);
+
+        cfg.stream
+            .functions_by_sensor
+            .insert("test-1".to_string(), MathFunction::Const);
+        assert_eq!(
+            *cfg.stream.sensor_function(1, "test-1"),
+            MathFunction::Const
+        );
+
+        cfg.stream.sensor_functions.clear();
+        assert_eq!(*cfg.stream.sensor_function(2, "test-2"), MathFunction::Saw);
+    }
+
+    #[test]
+    fn test_rate() {
+        let cfg = Config::parse_from(["stream-rust-test", "--rate", "50"]);
+        assert_eq!(cfg.stream.sample_interval(), Duration::from_millis(20));
+        assert_eq!(
+            cfg.stream.sensor_interval(1, "test-1"),
+            Duration::from_millis(20)
+        );
+
+        let cfg = Config::parse_from(["stream-rust-test", "--rate", "3"]);
+        assert_eq!(
+            cfg.stream.sample_interval(),
+            Duration::from_nanos(333_333_333)
+        );
+
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--rate",
+            "50",
+            "--sensor-intervals",
+            "100",
+        ]);
+        assert_eq!(
+            cfg.stream.sensor_interval(0, "test"),
+            Duration::from_millis(100)
+        );
+
+        assert!(Config::try_parse_from(["stream-rust-test", "--rate", "50", "-i", "10"]).is_err());
+        assert!(Config::try_parse_from(["stream-rust-test", "--rate", "0"]).is_err());
+        assert!(Config::try_parse_from(["stream-rust-test", "-i", "0"]).is_err());
+        assert!(Config::try_parse_from(["stream-rust-test", "--burst-size", "1001"]).is_err());
+    }
+
+    #[test]
+    fn test_sensor_interval() {
+        let mut cfg = Config::parse_from(["stream-rust-test", "--sensor-intervals", "100,60000"]);
+
+        assert_eq!(
+            cfg.stream.sensor_interval(0, "test"),
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            cfg.stream.sensor_interval(1, "test-1"),
+            Duration::from_millis(60000)
+        );
+
+        cfg.stream
+            .intervals_by_sensor
+            .insert("test".to_string(), 500);
+        assert_eq!(
+            cfg.stream.sensor_interval(0, "test"),
+            Duration::from_millis(500)
+        );
+
+        cfg.stream.sensor_intervals.clear();
+        assert_eq!(
+            cfg.stream.sensor_interval(1, "test-1"),
+            Duration::from_millis(1000)
+        );
+
+        assert!(
+            Config::try_parse_from(["stream-rust-test", "--sensor-intervals", "100,0"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_human_durations() {
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--interval-btw-samples",
+            "1m30s",
+            "--sensor-intervals",
+            "500ms,2s",
+            "--group-intervals",
+            "hvac.*=1s",
+            "--mqtt-keepalive",
+            "1m",
+            "--ramp-up",
+            "5m",
+            "--timestamp-offset",
+            "-1d",
+        ]);
+
+        assert_eq!(cfg.stream.interval_btw_samples, 90_000);
+        assert_eq!(cfg.stream.sensor_intervals, [500, 2000]);
+        assert_eq!(cfg.stream.group_intervals, [("hvac.*".to_string(), 1000)]);
+        assert_eq!(cfg.connection.mqtt.keepalive, Some(Secs(60)));
+        assert_eq!(cfg.stream.ramp_up, 300);
+        assert_eq!(cfg.stream.timestamp.timestamp_offset, -86_400);
+
+        assert!(Config::try_parse_from(["stream-rust-test", "--ramp-up", "1500ms"]).is_err());
+    }
+
+    #[test]
+    fn test_zero_intervals() {
+        // the intervals of the periodic tasks must be positive, to create their tickers
+        for option in [
+            "--heartbeat-interval",
+            "--property-cycle-interval",
+            "--reliability-interval",
+            "--churn-interval",
+            "--status-interval",
+            "--diagnostics-interval",
+            "--snapshot-interval",
+        ] {
+            assert!(
+                Config::try_parse_from(["stream-rust-test", option, "0"]).is_err(),
+                "{option}"
+            );
+            assert!(
+                Config::try_parse_from(["stream-rust-test", option, "1s"]).is_ok(),
+                "{option}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_runtime_limit() {
+        let cfg = Config::parse_from(["stream-rust-test", "--duration", "10m"]);
+        assert_eq!(cfg.stream.runtime_limit(), Some(Duration::from_secs(600)));
+
+        let cfg = Config::parse_from(["stream-rust-test", "--max-runtime", "60"]);
+        assert_eq!(cfg.stream.runtime_limit(), Some(Duration::from_secs(60)));
+
+        assert_eq!(
+            Config::parse_from(["stream-rust-test"])
+                .stream
+                .runtime_limit(),
+            None
+        );
+        assert!(Config::try_parse_from([
+            "stream-rust-test",
+            "--duration",
+            "10m",
+            "--max-runtime",
+            "60"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_sensor_groups() {
+        let mut cfg = Config::parse_from([
+            "stream-rust-test",
+            "--sensor-functions",
+            "sin",
+            "--group-functions",
+            "hvac.*=saw,hvac.floor1.*=random",
+            "--group-intervals",
+            "hvac.*=500",
+        ]);
+
+        assert_eq!(*cfg.stream.sensor_function(0, "pump.1"), MathFunction::Sin);
+        assert_eq!(*cfg.stream.sensor_function(0, "hvac.2"), MathFunction::Saw);
+        assert_eq!(
+            *cfg.stream.sensor_function(0, "hvac.floor1.2"),
+            MathFunction::Random
+        );
+        assert_eq!(
+            cfg.stream.sensor_interval(0, "hvac.2"),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            cfg.stream.sensor_interval(0, "pump.1"),
+            Duration::from_millis(1000)
+        );
+
+        // per-sensor overrides
+        cfg.stream
+            .functions_by_sensor
+            .insert("hvac.3".to_string(), MathFunction::Const);
+        assert_eq!(
+            *cfg.stream.sensor_function(0, "hvac.3"),
+            MathFunction::Const
+        );
+
+        for group in ["hvac=saw", "hvac.*=unknown", "hvac.*"] {
+            assert!(
+                Config::try_parse_from(["stream-rust-test", "--group-functions", group]).is_err(),
+                "{group}"
+            );
+        }
+        assert!(
+            Config::try_parse_from(["stream-rust-test", "--group-intervals", "hvac.*=0"]).is_err()
+        );
+    }
 }
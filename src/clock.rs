@@ -0,0 +1,96 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Simulated clock, advancing faster than the wall clock to accelerate soak tests.
+
+use astarte_device_sdk::chrono::{DateTime, TimeDelta, Utc};
+use std::time::{Duration, Instant};
+
+/// Clock advancing `speedup` times faster than the wall clock
+#[derive(Debug, Clone, Copy)]
+pub struct SimClock {
+    start: Instant,
+    start_utc: DateTime<Utc>,
+    speedup: u32,
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl SimClock {
+    /// Start the clock now, a speedup of 0 is treated as 1
+    pub fn new(speedup: u32) -> Self {
+        Self {
+            start: Instant::now(),
+            start_utc: Utc::now(),
+            speedup: speedup.max(1),
+        }
+    }
+
+    /// Number of simulated seconds elapsing in a wall clock second
+    pub fn speedup(&self) -> u32 {
+        self.speedup
+    }
+
+    /// Check if the clock is faster than the wall clock
+    pub fn is_accelerated(&self) -> bool {
+        self.speedup > 1
+    }
+
+    /// Wall clock time elapsed since the start
+    pub fn real_elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Simulated time elapsed since the start
+    pub fn elapsed(&self) -> Duration {
+        self.simulated(self.real_elapsed())
+    }
+
+    /// Current simulated time, equal to the wall clock one if the clock isn't accelerated
+    pub fn now(&self) -> DateTime<Utc> {
+        if !self.is_accelerated() {
+            return Utc::now();
+        }
+
+        TimeDelta::from_std(self.elapsed())
+            .ok()
+            .and_then(|elapsed| self.start_utc.checked_add_signed(elapsed))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    /// Convert a wall clock duration to the simulated one, saturating on overflow
+    pub fn simulated(&self, real: Duration) -> Duration {
+        real.checked_mul(self.speedup).unwrap_or(Duration::MAX)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_simulated() {
+        let clock = SimClock::new(60);
+        assert!(clock.is_accelerated());
+        assert_eq!(
+            clock.simulated(Duration::from_secs(60)),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(clock.simulated(Duration::MAX), Duration::MAX);
+
+        let clock = SimClock::new(0);
+        assert!(!clock.is_accelerated());
+        assert_eq!(clock.speedup(), 1);
+        assert_eq!(
+            clock.simulated(Duration::from_secs(5)),
+            Duration::from_secs(5)
+        );
+    }
+}
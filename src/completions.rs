@@ -0,0 +1,38 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Shell completions of the CLI options and subcommands.
+
+use crate::cli::Config;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::io::Write;
+
+/// Name of the completed binary
+const BIN_NAME: &str = "stream-rust-test";
+
+/// Write the completion script of the CLI for the given shell
+pub fn completions(shell: Shell, out: &mut impl Write) {
+    clap_complete::generate(shell, &mut Config::command(), BIN_NAME, out);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_completions() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut out = Vec::new();
+            completions(shell, &mut out);
+
+            let script = String::from_utf8(out).unwrap();
+
+            assert!(script.contains("interval-btw-samples"), "{shell}");
+            assert!(script.contains("generate-config"), "{shell}");
+        }
+    }
+}
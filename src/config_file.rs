@@ -0,0 +1,542 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Stream options declared in the `config.toml` file, next to the Astarte connection configuration.
+//!
+//! The options set through the CLI or the environment variables take precedence over the ones of
+//! the file. The file can declare named profiles in `[profile.<name>]` tables, whose values override
+//! the top level ones once selected.
+//!
+//! The file can also be written in JSON or YAML, as `config.json` or `config.yaml`, with the same
+//! structure as the TOML one. The format is detected from the extension.
+
+use crate::cli::Config;
+use crate::duration::Millis;
+use crate::duty_cycle::DutyCycle;
+use crate::env_prefix::EnvPrefix;
+use crate::extra_stream::{ExtraStreamConfig, StreamDefinition};
+use crate::math::MathFunction;
+use crate::sensor_id::SensorIdTemplate;
+use color_eyre::eyre;
+use color_eyre::eyre::{bail, ensure, WrapErr};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+use tracing::debug;
+
+/// Table of the named profiles in the `config.toml` file
+const PROFILES: &str = "profile";
+
+/// Names of the configuration file in the configuration directory, one per supported format
+pub const CONFIG_FILE_NAMES: [&str; 4] =
+    ["config.toml", "config.json", "config.yaml", "config.yml"];
+
+/// Format of the configuration file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// TOML, the default
+    Toml,
+    /// JSON
+    Json,
+    /// YAML
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Detect the format from the extension of the file
+    pub fn from_path(path: &Path) -> eyre::Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+
+        match extension.to_ascii_lowercase().as_str() {
+            "toml" => Ok(Self::Toml),
+            "json" => Ok(Self::Json),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            _ => bail!(
+                "unsupported configuration file {}, expected a toml, json or yaml extension",
+                path.display()
+            ),
+        }
+    }
+
+    /// Parse the content of the file to the table of its values
+    pub fn parse(self, content: &str) -> eyre::Result<Table> {
+        match self {
+            Self::Toml => content.parse().map_err(eyre::Report::new),
+            Self::Json => serde_json::from_str(content).map_err(eyre::Report::new),
+            Self::Yaml => serde_yaml::from_str(content).map_err(eyre::Report::new),
+        }
+    }
+}
+
+impl Display for ConfigFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Toml => write!(f, "TOML"),
+            Self::Json => write!(f, "JSON"),
+            Self::Yaml => write!(f, "YAML"),
+        }
+    }
+}
+
+/// Find the configuration file in the directory, in any of the supported formats
+///
+/// If there is none, the missing `config.toml` is returned, while multiple files are ambiguous.
+pub async fn find_config_file(directory: &Path) -> eyre::Result<PathBuf> {
+    let mut found = Vec::new();
+
+    for name in CONFIG_FILE_NAMES {
+        let path = directory.join(name);
+
+        let exists = tokio::fs::try_exists(&path)
+            .await
+            .wrap_err_with(|| format!("couldn't access {}", path.display()))?;
+
+        if exists {
+            found.push(path);
+        }
+    }
+
+    match found.len() {
+        0 => Ok(directory.join(CONFIG_FILE_NAMES[0])),
+        1 => Ok(found.remove(0)),
+        _ => bail!(
+            "multiple configuration files in {}, keep only one of: {}",
+            directory.display(),
+            CONFIG_FILE_NAMES.join(", ")
+        ),
+    }
+}
+
+/// Where the configuration is read from: the `config.toml` file, the profile selected in it and the
+/// prefix of the environment variables
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLocation {
+    /// Path of the file
+    pub path: PathBuf,
+    /// Profile whose values override the top level ones
+    pub profile: Option<String>,
+    /// Prefix of the environment variables
+    pub env_prefix: EnvPrefix,
+}
+
+impl ConfigLocation {
+    /// File without a selected profile, with the default environment variables
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            profile: None,
+            env_prefix: EnvPrefix::default(),
+        }
+    }
+
+    /// Select a profile of the file
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+
+        self
+    }
+
+    /// Read the environment variables with the prefix
+    pub fn with_env_prefix(mut self, env_prefix: EnvPrefix) -> Self {
+        self.env_prefix = env_prefix;
+
+        self
+    }
+
+    /// Read the values of the file, overridden by the ones of the selected profile
+    ///
+    /// A missing file has no values, unless a profile is selected.
+    pub async fn read(&self) -> eyre::Result<Table> {
+        let format = ConfigFormat::from_path(&self.path)?;
+
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                ensure!(
+                    self.profile.is_none(),
+                    "couldn't select the profile, {} not found",
+                    self.path.display()
+                );
+
+                debug!("file {} not found", self.path.display());
+
+                return Ok(Table::new());
+            }
+            Err(err) => {
+                return Err(err).wrap_err_with(|| format!("couldn't read {}", self.path.display()));
+            }
+        };
+
+        let table = format
+            .parse(&content)
+            .wrap_err_with(|| format!("invalid {format} in {}", self.path.display()))?;
+
+        select_profile(table, self.profile.as_deref())
+            .wrap_err_with(|| format!("invalid profiles in {}", self.path.display()))
+    }
+}
+
+impl Display for ConfigLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+
+        if let Some(profile) = &self.profile {
+            write!(f, " (profile {profile})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Override the top level values with the ones of the profile, dropping the profiles
+fn select_profile(mut table: Table, profile: Option<&str>) -> eyre::Result<Table> {
+    let mut profiles = match table.remove(PROFILES) {
+        Some(Value::Table(profiles)) => profiles,
+        Some(_) => bail!("[{PROFILES}] must be a table of profiles"),
+        None => Table::new(),
+    };
+
+    let Some(name) = profile else {
+        return Ok(table);
+    };
+
+    let overrides = match profiles.remove(name) {
+        Some(Value::Table(overrides)) => overrides,
+        Some(_) => bail!("[{PROFILES}.{name}] must be a table"),
+        None => bail!(
+            "profile {name} not found, expected one of: {}",
+            profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+        ),
+    };
+
+    merge_tables(&mut table, overrides);
+
+    Ok(table)
+}
+
+/// Merge the tables recursively, the values of the other one taking precedence
+fn merge_tables(table: &mut Table, other: Table) {
+    for (key, value) in other {
+        match (table.get_mut(&key), value) {
+            (Some(Value::Table(table)), Value::Table(other)) => merge_tables(table, other),
+            (Some(current), value) => *current = value,
+            (None, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Stream options of the `config.toml` file
+#[derive(Debug, Default, Deserialize)]
+pub struct ConfigFile {
+    /// Id of the primary sensor
+    pub sensor_id: Option<String>,
+    /// Number of simulated sensors
+    pub sensors: Option<u32>,
+    /// Math functions of the sensors by id or group, e.g. `hvac.*`
+    #[serde(default)]
+    pub functions: BTreeMap<String, MathFunction>,
+    /// Milliseconds between two samples of the sensors by id or group, or human friendly durations
+    #[serde(default)]
+    pub intervals: BTreeMap<String, Millis>,
+    /// Scales of the sensors by id or group
+    #[serde(default)]
+    pub scales: BTreeMap<String, f64>,
+    /// Priorities of the sensors by id or group
+    #[serde(default)]
+    pub priorities: BTreeMap<String, u8>,
+    /// Duty cycles of the sensors by id or group
+    #[serde(default)]
+    pub duty_cycles: BTreeMap<String, DutyCycle>,
+    /// Additional streams, one `[[stream]]` entry per stream
+    #[serde(default)]
+    pub stream: Vec<StreamDefinition>,
+}
+
+impl ConfigFile {
+    /// Load the stream options of a `config.toml` file
+    ///
+    /// A missing file declares no options.
+    pub async fn load(location: &ConfigLocation) -> eyre::Result<Self> {
+        Value::Table(location.read().await?)
+            .try_into()
+            .wrap_err_with(|| format!("invalid stream options in {location}"))
+    }
+
+    /// Merge the options into the CLI configuration, without overriding the ones already set
+    ///
+    /// The streams are added to the ones of the CLI.
+    pub fn merge_into(self, cfg: &mut Config) -> eyre::Result<()> {
+        if cfg.stream.sensor_id.is_none() {
+            cfg.stream.sensor_id = self
+                .sensor_id
+                .as_deref()
+                .map(SensorIdTemplate::parse)
+                .transpose()?;
+        }
+
+        if cfg.stream.sensors.is_none() {
+            ensure!(
+                self.sensors != Some(0),
+                "the number of sensors must be positive"
+            );
+
+            cfg.stream.sensors = self.sensors;
+        }
+
+        cfg.stream.functions_by_sensor.extend(self.functions);
+
+        for (sensor_id, Millis(interval)) in self.intervals {
+            ensure!(
+                interval > 0,
+                "the interval of sensor {sensor_id} must be positive"
+            );
+
+            cfg.stream.intervals_by_sensor.insert(sensor_id, interval);
+        }
+
+        cfg.stream.scales_by_sensor.extend(self.scales);
+        cfg.stream.priorities_by_sensor.extend(self.priorities);
+        cfg.stream.duty_cycles_by_sensor.extend(self.duty_cycles);
+
+        for (i, definition) in self.stream.into_iter().enumerate() {
+            let stream = ExtraStreamConfig::try_from(definition)
+                .wrap_err_with(|| format!("invalid stream {i}"))?;
+
+            cfg.stream.extra_streams.push(stream);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_config_file() {
+        let file: ConfigFile = toml::from_str(
+            r#"
+            sensor_id = "boiler"
+            sensors = 2
+
+            [astarte]
+            connection = "mqtt"
+
+            [functions]
+            boiler-1 = "saw"
+            "hvac.*" = "random"
+
+            [intervals]
+            boiler = 100
+
+            [scales]
+            boiler-1 = 3.5
+
+            [duty_cycles]
+            "hvac.*" = "30:90"
+
+            [priorities]
+            boiler = 10
+
+            [[stream]]
+            interface = "org.astarte-platform.stream-rust-test.IntegerValues"
+            path = "/other/value"
+            function = "saw"
+            interval = 200
+            type = "integer"
+            duty_cycle = "10:50"
+
+            [[stream]]
+            interface = "org.astarte-platform.genericsensors.Values"
+            "#,
+        )
+        .unwrap();
+
+        let mut cfg = Config::parse_from(["stream-rust-test"]);
+        file.merge_into(&mut cfg).unwrap();
+
+        assert_eq!(cfg.stream.sensor_id().render(0, ""), "boiler");
+        assert_eq!(
+            *cfg.stream.sensor_function(1, "boiler-1"),
+            MathFunction::Saw
+        );
+        assert_eq!(
+            *cfg.stream.sensor_function(1, "hvac.1"),
+            MathFunction::Random
+        );
+        assert_eq!(
+            cfg.stream.sensor_interval(0, "boiler"),
+            std::time::Duration::from_millis(100)
+        );
+        assert_eq!(cfg.stream.sensors(), 2);
+        assert_eq!(cfg.stream.sensor_scale(0, "boiler"), 1.0);
+        assert_eq!(cfg.stream.sensor_scale(1, "boiler-1"), 3.5);
+        assert_eq!(
+            cfg.stream.sensor_duty_cycle(0, "hvac.1"),
+            Some(DutyCycle::parse("30:90").unwrap())
+        );
+        assert_eq!(cfg.stream.sensor_duty_cycle(0, "boiler"), None);
+        assert_eq!(cfg.stream.sensor_priority(0, "boiler"), 10);
+        assert_eq!(cfg.stream.sensor_priority(1, "boiler-1"), 0);
+        assert_eq!(
+            cfg.stream.extra_streams,
+            [
+                ExtraStreamConfig::parse(
+                    "interface=org.astarte-platform.stream-rust-test.IntegerValues,type=integer,\
+                     function=saw,interval=200,path=/other/value,duty_cycle=10:50"
+                )
+                .unwrap(),
+                ExtraStreamConfig::parse("interface=org.astarte-platform.genericsensors.Values")
+                    .unwrap(),
+            ]
+        );
+
+        // the CLI takes precedence
+        let file: ConfigFile = toml::from_str("sensor_id = \"boiler\"").unwrap();
+        let mut cfg = Config::parse_from(["stream-rust-test", "--sensor-id", "pump"]);
+        file.merge_into(&mut cfg).unwrap();
+
+        assert_eq!(cfg.stream.sensor_id().render(0, ""), "pump");
+
+        assert!(toml::from_str::<ConfigFile>("[[stream]]\nunknown = 1").is_err());
+
+        // the intervals reach the tickers of the sensors
+        let file: ConfigFile = toml::from_str("[intervals]\nboiler = 0").unwrap();
+        let mut cfg = Config::parse_from(["stream-rust-test"]);
+        assert!(file.merge_into(&mut cfg).is_err());
+    }
+
+    #[test]
+    fn test_select_profile() {
+        let table: Table = toml::from_str(
+            r#"
+            sensor_id = "boiler"
+
+            [astarte]
+            connection = "mqtt"
+
+            [astarte.mqtt]
+            realm = "production"
+            device_id = "device"
+
+            [profile.staging.astarte.mqtt]
+            realm = "staging"
+
+            [profile.local]
+            sensor_id = "pump"
+
+            [profile.local.astarte]
+            connection = "grpc"
+            "#,
+        )
+        .unwrap();
+
+        let staging = select_profile(table.clone(), Some("staging")).unwrap();
+        assert_eq!(
+            staging["astarte"]["mqtt"]["realm"].as_str(),
+            Some("staging")
+        );
+        assert_eq!(
+            staging["astarte"]["mqtt"]["device_id"].as_str(),
+            Some("device")
+        );
+        assert!(!staging.contains_key(PROFILES));
+
+        let local = select_profile(table.clone(), Some("local")).unwrap();
+        assert_eq!(local["sensor_id"].as_str(), Some("pump"));
+        assert_eq!(local["astarte"]["connection"].as_str(), Some("grpc"));
+        assert_eq!(
+            local["astarte"]["mqtt"]["realm"].as_str(),
+            Some("production")
+        );
+
+        let default = select_profile(table.clone(), None).unwrap();
+        assert_eq!(
+            default["astarte"]["mqtt"]["realm"].as_str(),
+            Some("production")
+        );
+        assert!(!default.contains_key(PROFILES));
+
+        let err = select_profile(table, Some("missing")).unwrap_err();
+        assert!(format!("{err}").contains("local, staging"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_load_profile() {
+        let dir = std::env::temp_dir().join(format!("profile-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("config.toml");
+
+        // a profile can't be selected without the file
+        let location = ConfigLocation::new(&path).with_profile(Some("staging".to_string()));
+        assert!(ConfigFile::load(&location).await.is_err());
+        ConfigFile::load(&ConfigLocation::new(&path)).await.unwrap();
+
+        tokio::fs::write(
+            &path,
+            "sensor_id = \"boiler\"\n[profile.staging]\nsensor_id = \"pump\"\n",
+        )
+        .await
+        .unwrap();
+        let file = ConfigFile::load(&location).await.unwrap();
+        assert_eq!(file.sensor_id.as_deref(), Some("pump"));
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_config_formats() {
+        let dir = std::env::temp_dir().join(format!("formats-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        // the missing file defaults to the TOML one
+        assert_eq!(
+            find_config_file(&dir).await.unwrap(),
+            dir.join("config.toml")
+        );
+
+        let json = dir.join("config.json");
+        tokio::fs::write(
+            &json,
+            r#"{"sensor_id": "boiler", "intervals": {"boiler": "2s"}, "profile": {"staging": {"sensor_id": "pump"}}}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(find_config_file(&dir).await.unwrap(), json);
+
+        let location = ConfigLocation::new(&json).with_profile(Some("staging".to_string()));
+        let file = ConfigFile::load(&location).await.unwrap();
+        assert_eq!(file.sensor_id.as_deref(), Some("pump"));
+        assert_eq!(file.intervals["boiler"], Millis(2000));
+
+        let yaml = dir.join("config.yaml");
+        tokio::fs::write(&yaml, "sensor_id: boiler\nintervals:\n  boiler: 500\n")
+            .await
+            .unwrap();
+
+        // the format can't be chosen among multiple files
+        assert!(find_config_file(&dir).await.is_err());
+
+        let file = ConfigFile::load(&ConfigLocation::new(&yaml)).await.unwrap();
+        assert_eq!(file.sensor_id.as_deref(), Some("boiler"));
+        assert_eq!(file.intervals["boiler"], Millis(500));
+
+        assert!(ConfigFormat::from_path(Path::new("config.ini")).is_err());
+        assert!(ConfigFormat::Json.parse("[1, 2]").is_err());
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+}
@@ -0,0 +1,397 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! State of the connection to Astarte, observed from the events of the connection tasks of the
+//! Astarte Device SDK.
+//!
+//! The SDK reconnects internally without exposing its state, so the transitions are inferred from
+//! the tracing events it emits while reconnecting and once reconnected, together with the errors
+//! causing the disconnections. Each connection has its own [`ConnectionObserver`], so that the
+//! devices of a fleet don't share their state.
+//!
+//! The SDK spawns the tasks of a connection without propagating the tracing spans, so its events
+//! can't be attributed to a connection when more than one is observed. In that case only the end of
+//! the event loop of each connection is observed, marking it as disconnected.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::watch;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// Target of the events of the Astarte Device SDK
+const SDK_TARGET: &str = "astarte_device_sdk";
+
+/// State of the connection to Astarte
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Connected and sending data
+    #[default]
+    Connected,
+    /// Connection lost, the SDK is trying to reconnect
+    Reconnecting,
+    /// Connection closed
+    Disconnected,
+}
+
+impl ConnectionState {
+    /// State reached after an event of the SDK with the given message, if any
+    fn after(message: &str) -> Option<Self> {
+        match message {
+            "reconnecting" => Some(ConnectionState::Reconnecting),
+            // the stored data is sent again once reconnected
+            "start sending store publishes" => Some(ConnectionState::Connected),
+            "connection closed" | "connection closed successfully" | "task errored" => {
+                Some(ConnectionState::Disconnected)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionState::Connected => write!(f, "connected"),
+            ConnectionState::Reconnecting => write!(f, "reconnecting"),
+            ConnectionState::Disconnected => write!(f, "disconnected"),
+        }
+    }
+}
+
+/// Diagnostics of the connection, accumulated from the events of the SDK
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionDiagnostics {
+    /// Times the SDK started reconnecting
+    pub reconnects: u64,
+    /// Error of the last disconnection, if any
+    pub last_disconnect_reason: Option<String>,
+}
+
+/// Channels publishing the state, the diagnostics and the activity of a single connection
+#[derive(Debug, Clone)]
+pub struct ConnectionObserver {
+    state: watch::Sender<ConnectionState>,
+    diagnostics: watch::Sender<ConnectionDiagnostics>,
+    activity: watch::Sender<u64>,
+}
+
+impl Default for ConnectionObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionObserver {
+    /// Create the channels of a connection, initially connected
+    pub fn new() -> Self {
+        Self {
+            state: watch::channel(ConnectionState::default()).0,
+            diagnostics: watch::channel(ConnectionDiagnostics::default()).0,
+            activity: watch::channel(0).0,
+        }
+    }
+
+    /// Receiver of the state changes of the connection
+    pub fn state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    /// Receiver of the diagnostics of the connection
+    pub fn diagnostics(&self) -> watch::Receiver<ConnectionDiagnostics> {
+        self.diagnostics.subscribe()
+    }
+
+    /// Receiver of the count of the outcomes showing that the connection is alive
+    pub fn activity(&self) -> watch::Receiver<u64> {
+        self.activity.subscribe()
+    }
+
+    /// Record an outcome of the client of the connection showing that it's alive, like data sent
+    /// or received
+    pub fn record_activity(&self) {
+        self.activity.send_modify(|count| *count += 1);
+    }
+
+    /// Set the state of the connection, notifying the receivers only if it changed
+    fn set_state(&self, state: ConnectionState) {
+        self.state.send_if_modified(|current| {
+            let changed = *current != state;
+            *current = state;
+
+            changed
+        });
+    }
+
+    /// Update the channels after an event of the SDK
+    fn observe(&self, message: &MessageVisitor, level: Level) {
+        if let Some(error) = message.error.as_ref().filter(|_| level == Level::ERROR) {
+            self.diagnostics.send_modify(|diagnostics| {
+                diagnostics.last_disconnect_reason = Some(error.clone());
+            });
+        }
+
+        let Some(state) = ConnectionState::after(&message.message) else {
+            return;
+        };
+
+        if state == ConnectionState::Reconnecting {
+            self.diagnostics
+                .send_modify(|diagnostics| diagnostics.reconnects += 1);
+        }
+
+        self.set_state(state);
+    }
+}
+
+/// Tracing layer publishing the state of the connections on the channels of their observers
+///
+/// The connections are observed while running [`ConnectionStateLayer::run_observed`]. The events of
+/// the SDK are published on the observer of the only observed connection, and ignored while none or
+/// more than one are observed, since they can't be told apart.
+///
+/// The layer must be filtered with [`ConnectionStateLayer::observes`], to receive the debug events
+/// of the SDK regardless of the log level.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionStateLayer {
+    observers: Arc<Mutex<HashMap<u64, ConnectionObserver>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl ConnectionStateLayer {
+    /// Create the layer, without observed connections
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if the layer observes the events with the given metadata
+    pub fn observes(metadata: &Metadata<'_>) -> bool {
+        metadata.target().starts_with(SDK_TARGET) && *metadata.level() <= Level::DEBUG
+    }
+
+    /// Run a future handling the events of a connection, publishing the events of the SDK emitted
+    /// meanwhile on the given observer
+    ///
+    /// The connection is marked as disconnected once the future completes. If the future is
+    /// dropped, e.g. aborting its task, the connection is no longer observed.
+    pub async fn run_observed<F>(&self, observer: ConnectionObserver, future: F) -> F::Output
+    where
+        F: Future,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let guard = ObservedConnection::register(Arc::clone(&self.observers), id, observer);
+
+        let output = future.await;
+
+        guard.observer().set_state(ConnectionState::Disconnected);
+
+        output
+    }
+
+    /// Observer of the only observed connection, if any
+    fn current_observer(&self) -> Option<ConnectionObserver> {
+        let observers = self
+            .observers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        match observers.len() {
+            1 => observers.values().next().cloned(),
+            _ => None,
+        }
+    }
+}
+
+/// Registration of the observer of a connection, removed once dropped
+struct ObservedConnection {
+    observers: Arc<Mutex<HashMap<u64, ConnectionObserver>>>,
+    id: u64,
+    observer: ConnectionObserver,
+}
+
+impl ObservedConnection {
+    fn register(
+        observers: Arc<Mutex<HashMap<u64, ConnectionObserver>>>,
+        id: u64,
+        observer: ConnectionObserver,
+    ) -> Self {
+        observers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id, observer.clone());
+
+        Self {
+            observers,
+            id,
+            observer,
+        }
+    }
+
+    fn observer(&self) -> &ConnectionObserver {
+        &self.observer
+    }
+}
+
+impl Drop for ObservedConnection {
+    fn drop(&mut self) {
+        self.observers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.id);
+    }
+}
+
+impl<S> Layer<S> for ConnectionStateLayer
+where
+    S: Subscriber,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut message = MessageVisitor::default();
+        event.record(&mut message);
+
+        // most of the events don't change the state
+        let relevant =
+            message.error.is_some() || ConnectionState::after(&message.message).is_some();
+
+        if !relevant {
+            return;
+        }
+
+        if let Some(observer) = self.current_observer() {
+            observer.observe(&message, *event.metadata().level());
+        }
+    }
+}
+
+/// Extract the message and the error of an event
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    error: Option<String>,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            "error" => self.error = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        match field.name() {
+            "message" => self.message = format!("{value:?}"),
+            "error" => self.error = Some(format!("{value:?}")),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tracing_subscriber::filter::filter_fn;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_connection_state() {
+        let layer = ConnectionStateLayer::new();
+        let observer = ConnectionObserver::new();
+        let other = ConnectionObserver::new();
+        let mut rx = observer.state();
+        let diagnostics = observer.diagnostics();
+        let activity = observer.activity();
+        let observer_activity = observer.clone();
+        let guard = ObservedConnection::register(Arc::clone(&layer.observers), 0, observer);
+        let subscriber = tracing_subscriber::registry().with(
+            layer
+                .clone()
+                .with_filter(filter_fn(ConnectionStateLayer::observes)),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(target: "astarte_device_sdk::connection", "reconnecting");
+            assert_eq!(*rx.borrow_and_update(), ConnectionState::Reconnecting);
+
+            // only the events of the SDK are observed
+            tracing::debug!("start sending store publishes");
+            assert!(!rx.has_changed().unwrap());
+
+            tracing::debug!(
+                target: "astarte_device_sdk::connection",
+                "start sending store publishes"
+            );
+            assert_eq!(*rx.borrow_and_update(), ConnectionState::Connected);
+
+            tracing::info!(target: "astarte_device_sdk::connection", "connection closed");
+            assert_eq!(*rx.borrow_and_update(), ConnectionState::Disconnected);
+
+            tracing::error!(
+                target: "astarte_device_sdk::transport::mqtt",
+                error = %"connection refused",
+                "error received from mqtt connection"
+            );
+
+            // the events can't be attributed while more connections are observed
+            let _other =
+                ObservedConnection::register(Arc::clone(&layer.observers), 1, other.clone());
+            tracing::debug!(target: "astarte_device_sdk::connection", "reconnecting");
+            assert!(!rx.has_changed().unwrap());
+        });
+
+        drop(guard);
+
+        // the activity is only recorded from the outcomes of the client
+        assert_eq!(*activity.borrow(), 0);
+        observer_activity.record_activity();
+        assert_eq!(*activity.borrow(), 1);
+
+        assert_eq!(
+            *diagnostics.borrow(),
+            ConnectionDiagnostics {
+                reconnects: 1,
+                last_disconnect_reason: Some("connection refused".to_string()),
+            }
+        );
+
+        assert_eq!(*other.state().borrow(), ConnectionState::Connected);
+        assert_eq!(*other.activity().borrow(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_observed() {
+        let layer = ConnectionStateLayer::new();
+        let first = ConnectionObserver::new();
+        let second = ConnectionObserver::new();
+
+        layer
+            .run_observed(first.clone(), async {
+                assert_eq!(layer.observers.lock().unwrap().len(), 1);
+            })
+            .await;
+
+        // the end of the event loop disconnects
+        assert_eq!(*first.state().borrow(), ConnectionState::Disconnected);
+        assert_eq!(*second.state().borrow(), ConnectionState::Connected);
+        assert!(layer.observers.lock().unwrap().is_empty());
+
+        // an aborted connection is no longer observed
+        let pending = layer.run_observed(second.clone(), std::future::pending::<()>());
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(10), pending)
+                .await
+                .is_err()
+        );
+        assert!(layer.observers.lock().unwrap().is_empty());
+        assert_eq!(*second.state().borrow(), ConnectionState::Connected);
+    }
+}
@@ -0,0 +1,222 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Watchdog of the connection to Astarte, rebuilding it inside the process when it's stuck.
+//!
+//! The SDK reconnects by itself once the connection errors, but a half-open connection can stay
+//! silent for long without erroring. When the client neither sends data nor receives events for
+//! too long, the connection is torn down and a new client and connection pair replaces it, instead
+//! of requiring a restart of the container.
+//!
+//! The connection is rebuilt in the same way when a rotation of the credentials is requested, once
+//! the credential is re-read from its source.
+
+use crate::astarte::ConnectionConfigBuilder;
+use crate::config_file::ConfigLocation;
+use crate::jitter::Jitter;
+use crate::store::DeviceStore;
+use crate::supervisor::RestartPolicy;
+use astarte_device_sdk::DeviceClient;
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, error, info, warn};
+
+/// Watchdog rebuilding the connection once stuck
+#[derive(Debug, Clone)]
+pub struct ConnectionWatchdog {
+    builder: ConnectionConfigBuilder,
+    /// Time without activity after which the connection is stuck
+    timeout: Option<Duration>,
+    /// Requests of rotating the credentials, re-read from the config file
+    rotation: Option<(watch::Receiver<u64>, ConfigLocation)>,
+    policy: RestartPolicy,
+    jitter: Option<Jitter>,
+}
+
+/// Reason of a rebuild of the connection
+#[derive(Debug, Clone, Copy)]
+enum Rebuild {
+    Stuck,
+    Rotation,
+}
+
+impl ConnectionWatchdog {
+    /// Rebuild the connection of the given config, retried with the given policy
+    pub fn new(
+        builder: ConnectionConfigBuilder,
+        policy: RestartPolicy,
+        jitter: Option<Jitter>,
+    ) -> Self {
+        Self {
+            builder,
+            timeout: None,
+            rotation: None,
+            policy,
+            jitter,
+        }
+    }
+
+    /// Rebuild the connection when no activity is observed for longer than the timeout
+    pub fn stuck_after(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Rebuild the connection with the credential re-read from the config file on each request
+    pub fn rotate_credentials(
+        mut self,
+        requests: watch::Receiver<u64>,
+        location: ConfigLocation,
+    ) -> Self {
+        self.rotation = Some((requests, location));
+
+        self
+    }
+
+    /// Handle the events of the connection, rebuilding it when no activity is observed or the
+    /// credentials are rotated
+    ///
+    /// The clients of the rebuilt connections are sent on the channel.
+    pub async fn handle<S, F>(
+        self,
+        events: F,
+        mut activity: watch::Receiver<u64>,
+        clients: mpsc::Sender<DeviceClient<S>>,
+    ) -> eyre::Result<()>
+    where
+        S: DeviceStore,
+        F: Future<Output = eyre::Result<()>> + Send + 'static,
+    {
+        let Self {
+            mut builder,
+            timeout,
+            mut rotation,
+            policy,
+            jitter,
+        } = self;
+
+        let mut events: BoxFuture<'static, eyre::Result<()>> = events.boxed();
+        let mut restarts: u64 = 0;
+
+        loop {
+            let stuck = async {
+                match timeout {
+                    Some(timeout) => stuck_for(&mut activity, timeout).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let rotated = async {
+                match &mut rotation {
+                    Some((requests, location)) => {
+                        if requests.changed().await.is_err() {
+                            return std::future::pending().await;
+                        }
+
+                        location.clone()
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+
+            let rebuild = tokio::select! {
+                res = &mut events => return res,
+                _ = stuck => Rebuild::Stuck,
+                location = rotated => {
+                    if let Err(err) = builder.reload_credential(&location).await {
+                        error!("couldn't rotate the credentials: {err:#}");
+
+                        continue;
+                    }
+
+                    Rebuild::Rotation
+                }
+            };
+
+            match rebuild {
+                Rebuild::Stuck => warn!(
+                    "no activity on the connection for more than {:?}, rebuilding it",
+                    timeout.unwrap_or_default()
+                ),
+                Rebuild::Rotation => info!("credentials re-read, re-establishing the connection"),
+            }
+
+            // tear down the connection before connecting again with the same device
+            drop(events);
+
+            let (client, connection) = builder
+                .clone()
+                .build_with_retry::<S>(policy, jitter)
+                .await
+                .wrap_err("couldn't rebuild the connection")?;
+
+            if clients.send(client).await.is_err() {
+                debug!("stream stopped, closing the rebuilt connection");
+
+                return Ok(());
+            }
+
+            restarts += 1;
+
+            info!("rebuilt the connection, {restarts} times so far");
+
+            events = connection.handle_events().boxed();
+        }
+    }
+}
+
+/// Wait until no activity is observed for longer than the timeout
+pub async fn stuck_for(activity: &mut watch::Receiver<u64>, timeout: Duration) {
+    activity.borrow_and_update();
+
+    loop {
+        match tokio::time::timeout(timeout, activity.changed()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(_)) => return std::future::pending().await,
+            Err(_) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stuck_for() {
+        let timeout = Duration::from_millis(50);
+        let (tx, mut rx) = watch::channel(0);
+
+        // the activity keeps the connection alive
+        let alive = async {
+            for _ in 0..5 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                tx.send_modify(|count| *count += 1);
+            }
+        };
+        tokio::select! {
+            _ = stuck_for(&mut rx, timeout) => panic!("stuck while active"),
+            _ = alive => {}
+        }
+
+        tokio::time::timeout(Duration::from_secs(1), stuck_for(&mut rx, timeout))
+            .await
+            .unwrap();
+
+        // never stuck once the activity isn't observed anymore
+        drop(tx);
+        assert!(
+            tokio::time::timeout(timeout * 2, stuck_for(&mut rx, timeout))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,134 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sensors whose values are a function of the values of another sensor, producing correlated
+//! datasets, e.g. the humidity inversely correlated with the temperature.
+//!
+//! The value of a correlated sensor is `factor * source + offset`, plus a random noise uniformly
+//! distributed in `[-noise, noise]`.
+
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, eyre, WrapErr};
+use rand::Rng;
+use std::fmt::Display;
+
+/// Correlation of a sensor to a source sensor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correlation {
+    /// Id of the correlated sensor
+    pub sensor: String,
+    /// Id of the sensor the values are derived from
+    pub source: String,
+    factor: f64,
+    offset: f64,
+    noise: f64,
+}
+
+impl Correlation {
+    /// Parse a `<sensor>=<source>:<factor>:<offset>[:<noise>]` correlation
+    pub fn parse(s: &str) -> eyre::Result<Self> {
+        let (sensor, rest) = s
+            .split_once('=')
+            .ok_or_else(|| eyre!("expected <sensor>=<source>:<factor>:<offset>, got {s}"))?;
+
+        let mut parts = rest.split(':');
+        let source = parts.next().unwrap_or_default();
+
+        ensure!(
+            !sensor.is_empty() && !source.is_empty(),
+            "missing sensor id in correlation {s}"
+        );
+        ensure!(
+            sensor != source,
+            "sensor {sensor} can't be correlated to itself"
+        );
+
+        let mut number = |name: &str, default: Option<f64>| -> eyre::Result<f64> {
+            match (parts.next(), default) {
+                (Some(value), _) => value
+                    .trim()
+                    .parse()
+                    .wrap_err_with(|| format!("invalid {name} in correlation {s}")),
+                (None, Some(default)) => Ok(default),
+                (None, None) => Err(eyre!("missing {name} in correlation {s}")),
+            }
+        };
+
+        let factor = number("factor", None)?;
+        let offset = number("offset", None)?;
+        let noise = number("noise", Some(0.0))?;
+
+        ensure!(parts.next().is_none(), "too many fields in correlation {s}");
+        ensure!(
+            noise.is_finite() && noise >= 0.0,
+            "the noise of correlation {s} must be a non negative number"
+        );
+
+        Ok(Self {
+            sensor: sensor.to_string(),
+            source: source.to_string(),
+            factor,
+            offset,
+            noise,
+        })
+    }
+
+    /// Derive a value from the one of the source sensor
+    pub fn apply(&self, source: f64) -> f64 {
+        let noise = if self.noise > 0.0 {
+            rand::thread_rng().gen_range(-self.noise..=self.noise)
+        } else {
+            0.0
+        };
+
+        self.factor * source + self.offset + noise
+    }
+}
+
+impl Display for Correlation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={}:{}:{}:{}",
+            self.sensor, self.source, self.factor, self.offset, self.noise
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_correlation() {
+        let correlation = Correlation::parse("humidity=temperature:-0.5:80").unwrap();
+
+        assert_eq!(correlation.sensor, "humidity");
+        assert_eq!(correlation.source, "temperature");
+        assert_eq!(correlation.apply(20.0), 70.0);
+        assert_eq!(correlation.to_string(), "humidity=temperature:-0.5:80:0");
+
+        let noisy = Correlation::parse("b=a:2:0:1").unwrap();
+
+        for _ in 0..100 {
+            let value = noisy.apply(10.0);
+
+            assert!((19.0..=21.0).contains(&value), "{value}");
+        }
+
+        for invalid in [
+            "humidity",
+            "humidity=temperature",
+            "humidity=temperature:x:0",
+            "a=a:1:0",
+            "=a:1:0",
+            "b=a:1:0:-1",
+            "b=a:1:0:1:2",
+        ] {
+            assert!(Correlation::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+}
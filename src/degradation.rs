@@ -0,0 +1,222 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Graceful degradation of the stream under sustained send errors.
+//!
+//! The stream steps down an ordered ladder after a number of consecutive errors, and steps back up
+//! after a number of consecutive successes:
+//! 1. normal: every sample is sent;
+//! 2. reduced rate: only one sample every few is sent;
+//! 3. heartbeat only: the samples are replaced by periodic probes, while the heartbeats are sent;
+//! 4. paused: the heartbeats are suspended too, only the periodic probes are sent.
+
+use std::fmt::Display;
+
+/// Step of the degradation ladder
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DegradationLevel {
+    /// Every sample is sent
+    #[default]
+    Normal,
+    /// One sample every few is sent
+    ReducedRate,
+    /// Only the heartbeats and the probes are sent
+    HeartbeatOnly,
+    /// Only the probes are sent
+    Paused,
+}
+
+impl Display for DegradationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DegradationLevel::Normal => write!(f, "normal"),
+            DegradationLevel::ReducedRate => write!(f, "reduced-rate"),
+            DegradationLevel::HeartbeatOnly => write!(f, "heartbeat-only"),
+            DegradationLevel::Paused => write!(f, "paused"),
+        }
+    }
+}
+
+impl DegradationLevel {
+    fn worse(self) -> Self {
+        match self {
+            DegradationLevel::Normal => DegradationLevel::ReducedRate,
+            DegradationLevel::ReducedRate => DegradationLevel::HeartbeatOnly,
+            DegradationLevel::HeartbeatOnly | DegradationLevel::Paused => DegradationLevel::Paused,
+        }
+    }
+
+    fn better(self) -> Self {
+        match self {
+            DegradationLevel::Normal | DegradationLevel::ReducedRate => DegradationLevel::Normal,
+            DegradationLevel::HeartbeatOnly => DegradationLevel::ReducedRate,
+            DegradationLevel::Paused => DegradationLevel::HeartbeatOnly,
+        }
+    }
+}
+
+/// Options of the degradation ladder
+#[derive(Debug, Clone, clap::Args)]
+pub struct DegradationConfig {
+    /// Degrade the stream under sustained send errors instead of stopping
+    #[clap(long, env = "DEGRADATION")]
+    pub degradation: bool,
+    /// Consecutive send errors stepping the stream down the degradation ladder
+    #[clap(long, default_value = "5", env = "DEGRADATION_ERRORS")]
+    pub degradation_errors: u32,
+    /// Consecutive successful sends stepping the stream back up the degradation ladder
+    #[clap(long, default_value = "3", env = "DEGRADATION_RECOVERY")]
+    pub degradation_recovery: u32,
+    /// With a reduced rate, send one sample every this many
+    #[clap(long, default_value = "4", env = "DEGRADATION_RATE_DIVISOR")]
+    pub degradation_rate_divisor: u32,
+    /// Without samples, send a probe every this many samples
+    #[clap(long, default_value = "10", env = "DEGRADATION_PROBE")]
+    pub degradation_probe: u32,
+}
+
+/// State of the degradation ladder
+#[derive(Debug, Clone)]
+pub struct Degradation {
+    errors_threshold: u32,
+    recovery_threshold: u32,
+    rate_divisor: u32,
+    probe: u32,
+    level: DegradationLevel,
+    errors: u32,
+    successes: u32,
+    ticks: u64,
+}
+
+impl Degradation {
+    /// Create the ladder, returning [`None`] if the degradation is disabled
+    pub fn new(cfg: &DegradationConfig) -> Option<Self> {
+        cfg.degradation.then(|| Self {
+            errors_threshold: cfg.degradation_errors.max(1),
+            recovery_threshold: cfg.degradation_recovery.max(1),
+            rate_divisor: cfg.degradation_rate_divisor.max(1),
+            probe: cfg.degradation_probe.max(1),
+            level: DegradationLevel::Normal,
+            errors: 0,
+            successes: 0,
+            ticks: 0,
+        })
+    }
+
+    /// Current step of the ladder
+    pub fn level(&self) -> DegradationLevel {
+        self.level
+    }
+
+    /// Check if the sample of the current tick must be sent, either as data or as a probe
+    pub fn sample(&mut self) -> bool {
+        let every = match self.level {
+            DegradationLevel::Normal => 1,
+            DegradationLevel::ReducedRate => self.rate_divisor,
+            DegradationLevel::HeartbeatOnly | DegradationLevel::Paused => self.probe,
+        };
+
+        let send = self.ticks % u64::from(every) == 0;
+        self.ticks = self.ticks.wrapping_add(1);
+
+        send
+    }
+
+    /// Check if the heartbeats must be sent
+    pub fn heartbeats(&self) -> bool {
+        self.level < DegradationLevel::Paused
+    }
+
+    /// Record the outcome of a send, returning the new level if it changed
+    pub fn record(&mut self, success: bool) -> Option<DegradationLevel> {
+        let level = if success {
+            self.errors = 0;
+            self.successes = self.successes.saturating_add(1);
+
+            if self.successes < self.recovery_threshold {
+                return None;
+            }
+
+            self.level.better()
+        } else {
+            self.successes = 0;
+            self.errors = self.errors.saturating_add(1);
+
+            if self.errors < self.errors_threshold {
+                return None;
+            }
+
+            self.level.worse()
+        };
+
+        self.errors = 0;
+        self.successes = 0;
+
+        if level == self.level {
+            return None;
+        }
+
+        self.level = level;
+        // wait a full period before the next sample of the new level
+        self.ticks = 1;
+
+        Some(level)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_degradation_ladder() {
+        let mut degradation = Degradation::new(&DegradationConfig {
+            degradation: true,
+            degradation_errors: 2,
+            degradation_recovery: 2,
+            degradation_rate_divisor: 3,
+            degradation_probe: 5,
+        })
+        .unwrap();
+
+        let sampled =
+            |degradation: &mut Degradation| (0..15).filter(|_| degradation.sample()).count();
+
+        assert_eq!(sampled(&mut degradation), 15);
+
+        let mut levels = Vec::new();
+        for _ in 0..8 {
+            levels.extend(degradation.record(false));
+        }
+        assert_eq!(
+            levels,
+            [
+                DegradationLevel::ReducedRate,
+                DegradationLevel::HeartbeatOnly,
+                DegradationLevel::Paused
+            ]
+        );
+        assert!(!degradation.heartbeats());
+        assert_eq!(sampled(&mut degradation), 3);
+
+        // a success resets the errors count
+        assert_eq!(degradation.record(true), None);
+        assert_eq!(degradation.record(false), None);
+        assert_eq!(degradation.record(true), None);
+        assert_eq!(
+            degradation.record(true),
+            Some(DegradationLevel::HeartbeatOnly)
+        );
+        assert!(degradation.heartbeats());
+
+        degradation.record(true);
+        assert_eq!(
+            degradation.record(true),
+            Some(DegradationLevel::ReducedRate)
+        );
+        assert_eq!(sampled(&mut degradation), 5);
+    }
+}
@@ -0,0 +1,159 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Catalogue of the supported behaviors, exported as JSON to build configuration forms.
+
+use crate::aggregate::Aggregate;
+use crate::astarte::SERVER_COMMANDS;
+use crate::cli::Config;
+use crate::math::MathFunction;
+use crate::output::{BlobContent, DateTimeSource, OutputType, StringFormat};
+use crate::timestamp::SkewDistribution;
+use clap::{CommandFactory, ValueEnum};
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use serde_json::{json, Value};
+
+/// Describe the values of an enum, with the CLI options configuring them
+fn variants<T>(parameters: impl Fn(&T) -> &'static [&'static str]) -> Vec<Value>
+where
+    T: ValueEnum,
+{
+    T::value_variants()
+        .iter()
+        .filter_map(|variant| {
+            let value = variant.to_possible_value()?;
+
+            Some(json!({
+                "name": value.get_name(),
+                "description": value.get_help().map(ToString::to_string),
+                "parameters": parameters(variant),
+            }))
+        })
+        .collect()
+}
+
+/// CLI options configuring a math function
+fn function_parameters(function: &MathFunction) -> &'static [&'static str] {
+    match function {
+        MathFunction::Exec => &["exec-command", "exec-timeout"],
+        _ => &["scale"],
+    }
+}
+
+/// CLI options configuring an output type
+fn output_parameters(output_type: &OutputType) -> &'static [&'static str] {
+    match output_type {
+        OutputType::Double | OutputType::Integer => &[],
+        OutputType::Boolean => &["boolean-threshold"],
+        OutputType::String => &["string-format", "string-template", "string-file"],
+        OutputType::LongInteger => &["longinteger-boundaries"],
+        OutputType::BinaryBlob => &["blob-size", "blob-content"],
+        OutputType::DateTime => &[
+            "datetime-source",
+            "datetime-offset",
+            "datetime-start",
+            "datetime-step",
+        ],
+    }
+}
+
+/// Describe every CLI option
+fn options() -> Vec<Value> {
+    Config::command()
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set())
+        .filter_map(|arg| {
+            let name = arg.get_long()?;
+
+            let possible_values: Vec<String> = arg
+                .get_possible_values()
+                .iter()
+                .map(|value| value.get_name().to_string())
+                .collect();
+
+            let default: Vec<String> = arg
+                .get_default_values()
+                .iter()
+                .map(|value| value.to_string_lossy().to_string())
+                .collect();
+
+            Some(json!({
+                "name": name,
+                "env": arg.get_env().map(|env| env.to_string_lossy().to_string()),
+                "description": arg.get_help().map(ToString::to_string),
+                "default": default.first(),
+                "possible_values": possible_values,
+            }))
+        })
+        .collect()
+}
+
+/// Describe the parameters updatable from Astarte, reading them from the commands interface
+fn remote_parameters() -> eyre::Result<Vec<Value>> {
+    let interface: Value =
+        serde_json::from_str(SERVER_COMMANDS).wrap_err("invalid commands interface")?;
+
+    let parameters = interface
+        .get("mappings")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|mapping| {
+            let endpoint = mapping.get("endpoint")?.as_str()?;
+            let name = endpoint.rsplit('/').next()?;
+
+            Some(json!({
+                "name": name,
+                "endpoint": endpoint,
+                "type": mapping.get("type"),
+                "description": mapping.get("description"),
+            }))
+        })
+        .collect();
+
+    Ok(parameters)
+}
+
+/// Build the catalogue of the supported behaviors
+pub fn describe() -> eyre::Result<Value> {
+    Ok(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "generators": variants::<MathFunction>(function_parameters),
+        "output_types": variants::<OutputType>(output_parameters),
+        "string_formats": variants::<StringFormat>(|_| &[]),
+        "blob_contents": variants::<BlobContent>(|_| &[]),
+        "datetime_sources": variants::<DateTimeSource>(|_| &[]),
+        "aggregates": variants::<Aggregate>(|_| &[]),
+        "timestamp_skews": variants::<SkewDistribution>(|_| &[]),
+        "remote_parameters": remote_parameters()?,
+        "options": options(),
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let catalogue = describe().unwrap();
+
+        let names = |key: &str| -> Vec<String> {
+            catalogue[key]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        assert!(names("generators").contains(&"noise-sin".to_string()));
+        assert!(names("output_types").contains(&"binaryblob".to_string()));
+        assert!(names("remote_parameters").contains(&"interval_btw_samples".to_string()));
+        assert!(names("options").contains(&"math-function".to_string()));
+    }
+}
@@ -0,0 +1,117 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Diagnostics of the connection to Astarte, periodically published so that the connectivity of
+//! the test devices can be analyzed during the chaos tests.
+
+use crate::astarte::AstarteConnection;
+use crate::connection_state::ConnectionDiagnostics;
+use crate::offset::stable_hash;
+use astarte_device_sdk::AstarteType;
+use std::collections::HashMap;
+
+/// Device owned object interface the diagnostics are published on
+pub const DIAGNOSTICS_INTERFACE: &str = "org.astarte-platform.stream-rust-test.Diagnostics";
+
+/// Transport connecting the device to Astarte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transport {
+    /// Type of the connection
+    pub connection: AstarteConnection,
+    /// Hash of the URL of the broker, so that the URL itself isn't published
+    pub broker_url_hash: String,
+}
+
+impl Transport {
+    /// Transport connecting through the given URL
+    pub fn new(connection: AstarteConnection, broker_url: &str) -> Self {
+        Self {
+            connection,
+            broker_url_hash: format!("{:016x}", stable_hash(&[broker_url.as_bytes()])),
+        }
+    }
+}
+
+/// Snapshot of the diagnostics of the connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Transport connecting the device
+    pub transport: Transport,
+    /// Reconnections and last disconnection of the transport
+    pub connection: ConnectionDiagnostics,
+}
+
+impl Diagnostics {
+    /// Fields of the object sent on the [`DIAGNOSTICS_INTERFACE`]
+    pub fn fields(&self) -> HashMap<String, AstarteType> {
+        let transport = match self.transport.connection {
+            AstarteConnection::Mqtt => "mqtt",
+            AstarteConnection::Grpc => "grpc",
+        };
+
+        HashMap::from([
+            (
+                "transport".to_string(),
+                AstarteType::String(transport.to_string()),
+            ),
+            (
+                "brokerUrlHash".to_string(),
+                AstarteType::String(self.transport.broker_url_hash.clone()),
+            ),
+            (
+                "reconnects".to_string(),
+                AstarteType::LongInteger(
+                    i64::try_from(self.connection.reconnects).unwrap_or(i64::MAX),
+                ),
+            ),
+            (
+                "lastDisconnectReason".to_string(),
+                AstarteType::String(
+                    self.connection
+                        .last_disconnect_reason
+                        .clone()
+                        .unwrap_or_default(),
+                ),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_fields() {
+        let transport = Transport::new(AstarteConnection::Mqtt, "https://api.astarte/pairing");
+        assert_eq!(transport.broker_url_hash.len(), 16);
+        assert_ne!(
+            Transport::new(AstarteConnection::Grpc, "http://[::1]:50051").broker_url_hash,
+            transport.broker_url_hash
+        );
+
+        let diagnostics = Diagnostics {
+            transport: transport.clone(),
+            connection: ConnectionDiagnostics {
+                reconnects: 2,
+                last_disconnect_reason: Some("connection refused".to_string()),
+            },
+        };
+
+        let fields = diagnostics.fields();
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields["transport"], AstarteType::String("mqtt".to_string()));
+        assert_eq!(
+            fields["brokerUrlHash"],
+            AstarteType::String(transport.broker_url_hash)
+        );
+        assert_eq!(fields["reconnects"], AstarteType::LongInteger(2));
+        assert_eq!(
+            fields["lastDisconnectReason"],
+            AstarteType::String("connection refused".to_string())
+        );
+    }
+}
@@ -0,0 +1,116 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dry run of the streams, logging the values instead of sending them to Astarte.
+//!
+//! The client doesn't connect to Astarte, so the settings of the generators can be verified without
+//! a realm. No data is ever received and no property is stored.
+
+use crate::loopback::{Loopback, LoopbackClient};
+use astarte_device_sdk::chrono::{DateTime, SecondsFormat, Utc};
+use astarte_device_sdk::client::RecvError;
+use astarte_device_sdk::{DeviceEvent, Value};
+use async_trait::async_trait;
+use tracing::info;
+
+/// Client logging the data that would be sent to the endpoint
+pub type DryRunClient = LoopbackClient<DryRun>;
+
+/// Loopback of the dry run client, logging the sent data
+#[derive(Debug, Clone)]
+pub struct DryRun {
+    endpoint: String,
+    quiet: bool,
+}
+
+impl DryRunClient {
+    /// Client of the given endpoint, the pairing URL or the Message Hub one
+    pub fn dry_run(endpoint: impl Into<String>) -> Self {
+        LoopbackClient::new(DryRun {
+            endpoint: endpoint.into(),
+            quiet: false,
+        })
+    }
+
+    /// Discard the data without logging it, e.g. to measure the throughput of the streams
+    pub fn quiet(mut self) -> Self {
+        self.loopback_mut().quiet = true;
+
+        self
+    }
+}
+
+#[async_trait]
+impl Loopback for DryRun {
+    /// Log the data, with the current time if it has no explicit timestamp
+    fn send(&self, interface: &str, path: &str, data: Value, timestamp: Option<DateTime<Utc>>) {
+        if self.quiet {
+            return;
+        }
+
+        let timestamp = timestamp
+            .unwrap_or_else(Utc::now)
+            .to_rfc3339_opts(SecondsFormat::Millis, true);
+
+        match data {
+            Value::Individual(data) => info!(
+                "dry run, {} at {timestamp}: {interface}{path} {data:?}",
+                self.endpoint
+            ),
+            Value::Object(data) => info!(
+                "dry run, {} at {timestamp}: {interface}{path} {data:?}",
+                self.endpoint
+            ),
+            Value::Unset => info!(
+                "dry run, {} at {timestamp}: {interface}{path} unset",
+                self.endpoint
+            ),
+        }
+    }
+
+    /// No data is ever received
+    async fn recv(&self) -> Result<DeviceEvent, RecvError> {
+        std::future::pending().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use astarte_device_sdk::properties::PropAccess;
+    use astarte_device_sdk::{AstarteType, Client};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_dry_run_client() {
+        let client = DryRunClient::dry_run("http://localhost/pairing");
+
+        client
+            .send(
+                "org.astarte-platform.genericsensors.Values",
+                "/1/value",
+                1.5,
+            )
+            .await
+            .unwrap();
+        client
+            .send_object_with_timestamp(
+                "org.astarte-platform.stream-rust-test.Diagnostics",
+                "/1",
+                HashMap::from([("reconnects".to_string(), AstarteType::LongInteger(0))]),
+                Utc::now(),
+            )
+            .await
+            .unwrap();
+
+        assert!(client.all_props().await.unwrap().is_empty());
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(10), client.recv())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,285 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human friendly durations, e.g. `500ms`, `45s`, `10m` or `1h30m`.
+//!
+//! A duration is a sequence of numbers followed by a unit among `ms`, `s`, `m`, `h` and `d`. A
+//! bare number, without unit, is interpreted in the unit chosen by the option.
+//!
+//! The options are parsed with the functions of this module, while the [`Millis`] and [`Secs`]
+//! types accept both a number and a human friendly string in the configuration files.
+
+use color_eyre::eyre;
+use color_eyre::eyre::{bail, ensure, eyre, WrapErr};
+use serde::{Deserialize, Deserializer, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Parse a duration, interpreting a bare number as seconds
+pub fn parse_secs(s: &str) -> eyre::Result<Duration> {
+    parse(s, Duration::from_secs(1))
+}
+
+/// Parse a duration in milliseconds, interpreting a bare number as milliseconds
+pub fn parse_millis(s: &str) -> eyre::Result<u64> {
+    let duration = parse(s, Duration::from_millis(1))?;
+
+    ensure!(
+        duration.subsec_nanos() % 1_000_000 == 0,
+        "duration {s} is not a whole number of milliseconds"
+    );
+
+    u64::try_from(duration.as_millis()).map_err(|_| eyre!("duration {s} too long"))
+}
+
+/// Parse a positive duration in milliseconds, interpreting a bare number as milliseconds
+pub fn parse_positive_millis(s: &str) -> eyre::Result<u64> {
+    let millis = parse_millis(s)?;
+
+    ensure!(millis > 0, "duration {s} must be positive");
+
+    Ok(millis)
+}
+
+/// Parse a duration in seconds, interpreting a bare number as seconds
+pub fn parse_whole_secs(s: &str) -> eyre::Result<u64> {
+    let duration = parse_secs(s)?;
+
+    ensure!(
+        duration.subsec_nanos() == 0,
+        "duration {s} is not a whole number of seconds"
+    );
+
+    Ok(duration.as_secs())
+}
+
+/// Parse a duration in seconds that can be negative, e.g. `-1h`
+pub fn parse_signed_secs(s: &str) -> eyre::Result<i64> {
+    let s = s.trim();
+    let (negative, magnitude) = match s.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, s),
+    };
+
+    let secs =
+        i64::try_from(parse_whole_secs(magnitude)?).map_err(|_| eyre!("duration {s} too long"))?;
+
+    Ok(if negative { -secs } else { secs })
+}
+
+/// Parse a duration, interpreting a bare number in the given unit
+pub fn parse(s: &str, bare_unit: Duration) -> eyre::Result<Duration> {
+    let s = s.trim();
+
+    ensure!(!s.is_empty(), "empty duration");
+
+    if let Ok(bare) = s.parse::<u64>() {
+        return scale(bare_unit, bare).ok_or_else(|| eyre!("duration {s} too long"));
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| eyre!("missing unit at the end of duration {s}"))?;
+
+        ensure!(digits > 0, "expected a number in duration {s}");
+
+        let value: u64 = rest[..digits]
+            .parse()
+            .wrap_err_with(|| format!("invalid number in duration {s}"))?;
+
+        rest = &rest[digits..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        let unit = match &rest[..unit_len] {
+            "ms" => Duration::from_millis(1),
+            "s" => Duration::from_secs(1),
+            "m" => Duration::from_secs(60),
+            "h" => Duration::from_secs(60 * 60),
+            "d" => Duration::from_secs(24 * 60 * 60),
+            unit => bail!("unknown unit {unit} in duration {s}, expected ms, s, m, h or d"),
+        };
+
+        rest = &rest[unit_len..];
+
+        let value = scale(unit, value).ok_or_else(|| eyre!("duration {s} too long"))?;
+
+        total = total
+            .checked_add(value)
+            .ok_or_else(|| eyre!("duration {s} too long"))?;
+    }
+
+    Ok(total)
+}
+
+/// Multiply the unit by a value, returning [`None`] on overflow
+fn scale(unit: Duration, value: u64) -> Option<Duration> {
+    let nanos = unit.as_nanos().checked_mul(u128::from(value))?;
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    let subsec_nanos = u32::try_from(nanos % 1_000_000_000).ok()?;
+
+    Some(Duration::new(secs, subsec_nanos))
+}
+
+/// Number or human friendly string, as written in the configuration files
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Repr {
+    Number(u64),
+    Human(String),
+}
+
+/// Milliseconds read from a number, e.g. `500`, or a human friendly duration, e.g. `2s`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "Repr")]
+pub struct Millis(pub u64);
+
+impl FromStr for Millis {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_millis(s).map(Self)
+    }
+}
+
+impl TryFrom<Repr> for Millis {
+    type Error = eyre::Report;
+
+    fn try_from(value: Repr) -> Result<Self, Self::Error> {
+        match value {
+            Repr::Number(millis) => Ok(Self(millis)),
+            Repr::Human(s) => s.parse(),
+        }
+    }
+}
+
+impl Display for Millis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}ms", self.0)
+    }
+}
+
+/// Seconds read from a number, e.g. `90`, or a human friendly duration, e.g. `1m30s`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "Repr")]
+pub struct Secs(pub u64);
+
+impl FromStr for Secs {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_whole_secs(s).map(Self)
+    }
+}
+
+impl TryFrom<Repr> for Secs {
+    type Error = eyre::Report;
+
+    fn try_from(value: Repr) -> Result<Self, Self::Error> {
+        match value {
+            Repr::Number(secs) => Ok(Self(secs)),
+            Repr::Human(s) => s.parse(),
+        }
+    }
+}
+
+impl Display for Secs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}s", self.0)
+    }
+}
+
+/// (De)serialize a [`Duration`] as a number of milliseconds, for `#[serde(with = "...")]`
+///
+/// The sub-millisecond part of the duration is truncated.
+pub mod as_millis {
+    use super::*;
+
+    /// Serialize the duration as whole milliseconds
+    pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+    }
+
+    /// Deserialize the duration from a number of milliseconds
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_secs("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_secs("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_secs("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_secs("1s500ms").unwrap(), Duration::from_millis(1500));
+        assert_eq!(parse_secs("2d").unwrap(), Duration::from_secs(172_800));
+        assert_eq!(
+            parse("250", Duration::from_millis(1)).unwrap(),
+            Duration::from_millis(250)
+        );
+
+        for invalid in ["", "m", "10x", "1h30", "-5s", "1.5s"] {
+            assert!(parse_secs(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(parse_millis("500").unwrap(), 500);
+        assert_eq!(parse_millis("2s").unwrap(), 2000);
+        assert_eq!(parse_millis("1m30s").unwrap(), 90_000);
+        assert_eq!(parse_millis("5000000000").unwrap(), 5_000_000_000);
+        assert!(parse_positive_millis("0ms").is_err());
+
+        assert_eq!(parse_whole_secs("90").unwrap(), 90);
+        assert_eq!(parse_whole_secs("1h").unwrap(), 3600);
+        assert!(parse_whole_secs("1500ms").is_err());
+
+        assert_eq!(parse_signed_secs("-1d").unwrap(), -86_400);
+        assert_eq!(parse_signed_secs("-30").unwrap(), -30);
+        assert_eq!(parse_signed_secs("2m").unwrap(), 120);
+        assert!(parse_signed_secs("--1").is_err());
+    }
+
+    #[test]
+    fn test_deserialize_durations() {
+        #[derive(Deserialize)]
+        struct Durations {
+            millis: Vec<Millis>,
+            secs: Vec<Secs>,
+        }
+
+        let durations: Durations = toml::from_str(
+            r#"
+            millis = [250, "2s"]
+            secs = [30, "1m30s"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(durations.millis, [Millis(250), Millis(2000)]);
+        assert_eq!(durations.secs, [Secs(30), Secs(90)]);
+
+        assert!(toml::from_str::<Durations>("millis = [\"2x\"]\nsecs = []").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Duty cycle of a stream, alternating periods sending data and silent periods to emulate devices
+//! waking up periodically.
+
+use crate::duration;
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, eyre, WrapErr};
+use serde::Deserialize;
+use std::fmt::Display;
+use std::time::Duration;
+
+/// Stream sending data for `on` seconds, then silent for `off` seconds, repeatedly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct DutyCycle {
+    on: Duration,
+    off: Duration,
+}
+
+impl DutyCycle {
+    /// Parse an `<on>:<off>` duty cycle, in seconds or human friendly durations, e.g. `1m:5m`
+    pub fn parse(s: &str) -> eyre::Result<Self> {
+        let (on, off) = s
+            .split_once(':')
+            .ok_or_else(|| eyre!("expected <on>:<off> seconds, e.g. 30:90, got {s}"))?;
+
+        let secs = |secs: &str| -> eyre::Result<u64> {
+            duration::parse_whole_secs(secs)
+                .wrap_err_with(|| format!("invalid seconds {secs} in duty cycle {s}"))
+        };
+
+        let on = secs(on)?;
+        let off = secs(off)?;
+
+        ensure!(
+            on > 0,
+            "the on period of the duty cycle {s} must be positive"
+        );
+
+        Ok(Self {
+            on: Duration::from_secs(on),
+            off: Duration::from_secs(off),
+        })
+    }
+
+    /// Check if the stream sends data after the given time from the start
+    pub fn is_on(&self, elapsed: Duration) -> bool {
+        let period = (self.on + self.off).as_millis();
+        let phase = elapsed.as_millis() % period;
+
+        phase < self.on.as_millis()
+    }
+}
+
+impl TryFrom<String> for DutyCycle {
+    type Error = eyre::Report;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl Display for DutyCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.on.as_secs(), self.off.as_secs())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_duty_cycle() {
+        let cycle = DutyCycle::parse("30:90").unwrap();
+        let at = Duration::from_secs;
+
+        assert!(cycle.is_on(at(0)));
+        assert!(cycle.is_on(at(29)));
+        assert!(!cycle.is_on(at(30)));
+        assert!(!cycle.is_on(at(119)));
+        assert!(cycle.is_on(at(120)));
+        assert_eq!(cycle.to_string(), "30:90");
+
+        // never silent
+        assert!(DutyCycle::parse("10:0").unwrap().is_on(at(15)));
+
+        for cycle in ["30", "0:10", "a:10", "10:-1"] {
+            assert!(DutyCycle::parse(cycle).is_err(), "{cycle}");
+        }
+    }
+}
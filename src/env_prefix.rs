@@ -0,0 +1,116 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prefix of the environment variables, so that multiple instances sharing the same environment
+//! can be configured side by side.
+//!
+//! The configured prefix replaces the `ASTARTE_` one of the connection variables, e.g.
+//! `MYTEST_REALM` instead of `ASTARTE_REALM`, and is prepended to the ones of the stream options,
+//! e.g. `MYTEST_MATH_FUNCTION` instead of `MATH_FUNCTION`.
+
+use clap::Command;
+use std::borrow::Cow;
+
+/// Prefix of the Astarte environment variables, replaced by the configured one
+pub const ASTARTE_PREFIX: &str = "ASTARTE_";
+
+/// Name of the CLI option setting the prefix
+pub const ENV_PREFIX_ARG: &str = "env_prefix";
+
+/// Prefix of the environment variables, the default names if not set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvPrefix(Option<String>);
+
+impl EnvPrefix {
+    /// Prefix the environment variables with the given one, if any
+    pub fn new(prefix: Option<String>) -> Self {
+        Self(prefix.filter(|prefix| !prefix.is_empty()))
+    }
+
+    /// Name of the environment variable with the prefix
+    pub fn var<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        let Some(prefix) = &self.0 else {
+            return Cow::Borrowed(name);
+        };
+
+        let name = name.strip_prefix(ASTARTE_PREFIX).unwrap_or(name);
+
+        Cow::Owned(format!("{prefix}{name}"))
+    }
+
+    /// Prefix the environment variables read by the options of the command and of its subcommands
+    pub fn command(&self, command: Command) -> Command {
+        if self.0.is_none() {
+            return command;
+        }
+
+        let subcommands: Vec<String> = command
+            .get_subcommands()
+            .map(|subcommand| subcommand.get_name().to_string())
+            .collect();
+
+        let command = subcommands.into_iter().fold(command, |command, name| {
+            command.mut_subcommand(name, |subcommand| self.command(subcommand))
+        });
+
+        command.mut_args(|arg| {
+            let Some(name) = arg.get_env().and_then(|name| name.to_str()) else {
+                return arg;
+            };
+
+            let name = self.var(name).into_owned();
+
+            arg.env(name)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::Config;
+    use clap::CommandFactory;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_env_prefix() {
+        let prefix = EnvPrefix::new(Some("MYTEST_".to_string()));
+
+        assert_eq!(prefix.var("ASTARTE_REALM"), "MYTEST_REALM");
+        assert_eq!(prefix.var("MATH_FUNCTION"), "MYTEST_MATH_FUNCTION");
+
+        let default = EnvPrefix::new(Some(String::new()));
+        assert_eq!(default, EnvPrefix::default());
+        assert_eq!(default.var("ASTARTE_REALM"), "ASTARTE_REALM");
+
+        let command = prefix.command(Config::command());
+        let arg = command
+            .get_arguments()
+            .find(|arg| arg.get_id() == "math_function")
+            .unwrap();
+        assert_eq!(arg.get_env().unwrap(), "MYTEST_MATH_FUNCTION");
+
+        let arg = command
+            .find_subcommand("cleanup")
+            .and_then(|cleanup| cleanup.get_arguments().find(|arg| arg.get_id() == "store"))
+            .unwrap();
+        assert_eq!(arg.get_env().unwrap(), "MYTEST_STORE");
+    }
+
+    #[test]
+    fn test_no_prefixed_collisions() {
+        let prefix = EnvPrefix::new(Some("MYTEST_".to_string()));
+        let command = prefix.command(Config::command());
+
+        let mut names = HashSet::new();
+
+        for arg in command.get_arguments() {
+            if let Some(name) = arg.get_env() {
+                assert!(names.insert(name.to_os_string()), "duplicated {name:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! External math function computed by a user-provided subprocess.
+//!
+//! The base value is written on the subprocess standard input as a line of text, then a single
+//! line containing the computed value is read back from its standard output. The process is kept
+//! alive between the samples and it is restarted if it exits, misbehaves or doesn't answer in time.
+
+use color_eyre::eyre;
+use color_eyre::eyre::{eyre, OptionExt, WrapErr};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tracing::{debug, warn};
+
+/// Math function computed by an external process
+#[derive(Debug)]
+pub struct ExecFunction {
+    /// Program and arguments of the command to spawn
+    command: Vec<String>,
+    /// Maximum time to wait for the process to answer
+    timeout: Duration,
+    /// Running process, if any
+    process: Option<ExecProcess>,
+}
+
+#[derive(Debug)]
+struct ExecProcess {
+    // keep the child so that the process is killed when dropped
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl ExecFunction {
+    /// Create an external function from a command line
+    ///
+    /// The command is split into words following the quoting rules of a POSIX shell, e.g.
+    /// `python3 -c 'print(1)'`, the first word being the program to execute. The command is not
+    /// run by a shell, so variables and globs are not expanded.
+    pub fn new(command: &str, timeout: Duration) -> eyre::Result<Self> {
+        let command = shell_words::split(command)
+            .wrap_err_with(|| format!("invalid exec command {command}"))?;
+
+        if command.is_empty() {
+            return Err(eyre!("empty exec command"));
+        }
+
+        Ok(Self {
+            command,
+            timeout,
+            process: None,
+        })
+    }
+
+    fn spawn(&self) -> eyre::Result<ExecProcess> {
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or_eyre("empty exec command")?;
+
+        debug!("spawning exec math function {}", self.command.join(" "));
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .wrap_err_with(|| format!("couldn't spawn {program}"))?;
+
+        let stdin = child.stdin.take().ok_or_eyre("missing process stdin")?;
+        let stdout = child.stdout.take().ok_or_eyre("missing process stdout")?;
+
+        Ok(ExecProcess {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        })
+    }
+
+    /// Compute the output value by sending the input to the external process
+    ///
+    /// On error the process is terminated and it will be spawned again on the next call.
+    pub async fn compute(&mut self, value: f64) -> eyre::Result<f64> {
+        let mut process = match self.process.take() {
+            Some(process) => process,
+            None => self.spawn()?,
+        };
+
+        let res = tokio::time::timeout(self.timeout, process.exchange(value))
+            .await
+            .map_err(|_| eyre!("exec math function timed out after {:?}", self.timeout))
+            .and_then(|res| res);
+
+        match res {
+            Ok(value) => {
+                self.process = Some(process);
+
+                Ok(value)
+            }
+            Err(err) => {
+                warn!("restarting exec math function");
+
+                Err(err)
+            }
+        }
+    }
+}
+
+impl ExecProcess {
+    async fn exchange(&mut self, value: f64) -> eyre::Result<f64> {
+        self.stdin
+            .write_all(format!("{value}\n").as_bytes())
+            .await
+            .wrap_err("couldn't write to the process stdin")?;
+        self.stdin.flush().await?;
+
+        let line = self
+            .stdout
+            .next_line()
+            .await
+            .wrap_err("couldn't read from the process stdout")?
+            .ok_or_eyre("the process closed its stdout")?;
+
+        line.trim()
+            .parse::<f64>()
+            .wrap_err_with(|| format!("invalid value returned by the process: {line}"))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_exec_function_compute() {
+        // echo back the received value
+        let mut exec = ExecFunction::new("cat", Duration::from_secs(1)).unwrap();
+
+        assert_eq!(exec.compute(4.2).await.unwrap(), 4.2);
+        assert_eq!(exec.compute(-1.0).await.unwrap(), -1.0);
+    }
+
+    #[tokio::test]
+    async fn test_exec_function_timeout() {
+        // never answers
+        let mut exec = ExecFunction::new("sleep 10", Duration::from_millis(50)).unwrap();
+
+        assert!(exec.compute(1.0).await.is_err());
+        assert!(exec.process.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_exec_function_quoted_args() {
+        let mut exec = ExecFunction::new(
+            r#"sh -c 'while read v; do echo "$v"; done'"#,
+            Duration::from_secs(1),
+        )
+        .unwrap();
+
+        assert_eq!(
+            exec.command,
+            ["sh", "-c", r#"while read v; do echo "$v"; done"#]
+        );
+        assert_eq!(exec.compute(2.5).await.unwrap(), 2.5);
+
+        assert!(ExecFunction::new("sh -c 'unterminated", Duration::from_secs(1)).is_err());
+        assert!(ExecFunction::new("  ", Duration::from_secs(1)).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Additional streams, sending data on other interfaces concurrently to the main one.
+//!
+//! Each stream is configured with a comma separated list of `key=value` options:
+//! - `interface`: name of the interface, required;
+//! - `path`: endpoint the data is sent to (default `/test/value`);
+//! - `function`: math function generating the data (default `default`);
+//! - `interval`: milliseconds between two samples, or a duration such as `2s` (default `1000`);
+//! - `type`: Astarte type of the sent values (default `double`);
+//! - `duty_cycle`: `<on>:<off>` seconds sending data and then silent (default always sending).
+//!
+//! For instance `interface=org.astarte-platform.stream-rust-test.IntegerValues,type=integer,
+//! function=saw,interval=200`.
+//!
+//! The same options can be declared in the `[[stream]]` array of the `config.toml` file, one entry
+//! per stream.
+
+use crate::duration::Millis;
+use crate::duty_cycle::DutyCycle;
+use crate::math::{BaseValue, MathFunction};
+use crate::output::{OutputConfig, OutputType};
+use crate::path::EndpointPath;
+use crate::stream::periodic_ticker;
+use astarte_device_sdk::AstarteType;
+use clap::ValueEnum;
+use color_eyre::eyre;
+use color_eyre::eyre::{bail, ensure, eyre, OptionExt, WrapErr};
+use serde::Deserialize;
+use std::time::{Duration, SystemTime};
+use tokio::time::Interval;
+use tracing::info;
+
+/// Options of an additional stream, as declared in a `[[stream]]` entry of the `config.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StreamDefinition {
+    /// Interface the data is sent to
+    pub interface: Option<String>,
+    /// Endpoint the data is sent to
+    pub path: Option<String>,
+    /// Math function generating the data
+    pub function: Option<MathFunction>,
+    /// Milliseconds between two samples, or a human friendly duration
+    pub interval: Option<Millis>,
+    /// Astarte type of the sent values
+    #[serde(rename = "type")]
+    pub output_type: Option<OutputType>,
+    /// Periods sending data and silent
+    pub duty_cycle: Option<DutyCycle>,
+}
+
+/// Configuration of an additional stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtraStreamConfig {
+    /// Interface the data is sent to
+    pub interface: String,
+    /// Endpoint the data is sent to
+    pub path: String,
+    /// Math function generating the data
+    pub math_function: MathFunction,
+    /// Time to wait between two samples
+    pub interval: Duration,
+    /// Astarte type of the sent values
+    pub output_type: OutputType,
+    /// Periods sending data and silent, always sending if missing
+    pub duty_cycle: Option<DutyCycle>,
+}
+
+impl ExtraStreamConfig {
+    /// Parse the configuration from a comma separated list of `key=value` options
+    pub fn parse(spec: &str) -> eyre::Result<Self> {
+        let mut definition = StreamDefinition::default();
+
+        for option in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or_else(|| eyre!("expected key=value, found {option}"))?;
+
+            let value = value.trim();
+
+            match key.trim() {
+                "interface" => definition.interface = Some(value.to_string()),
+                "path" => definition.path = Some(value.to_string()),
+                "function" => {
+                    let function = MathFunction::from_str(value, true)
+                        .map_err(|_| eyre!("unknown math function {value}"))?;
+
+                    definition.function = Some(function);
+                }
+                "interval" => {
+                    let millis = value
+                        .parse()
+                        .wrap_err_with(|| format!("invalid interval {value}"))?;
+
+                    definition.interval = Some(millis);
+                }
+                "type" => {
+                    let output_type = OutputType::from_str(value, true)
+                        .map_err(|_| eyre!("unknown output type {value}"))?;
+
+                    definition.output_type = Some(output_type);
+                }
+                "duty_cycle" => definition.duty_cycle = Some(DutyCycle::parse(value)?),
+                _ => bail!("unknown option {key}"),
+            }
+        }
+
+        Self::try_from(definition)
+    }
+}
+
+impl TryFrom<StreamDefinition> for ExtraStreamConfig {
+    type Error = eyre::Report;
+
+    fn try_from(value: StreamDefinition) -> Result<Self, Self::Error> {
+        let path = value.path.unwrap_or_else(|| "/test/value".to_string());
+
+        EndpointPath::parse(&path).wrap_err_with(|| format!("invalid path {path}"))?;
+
+        let math_function = value.function.unwrap_or_default();
+
+        ensure!(
+            math_function != MathFunction::Exec,
+            "the exec math function is only supported by the main stream"
+        );
+
+        let Millis(millis) = value.interval.unwrap_or(Millis(1000));
+
+        ensure!(millis > 0, "the interval must be positive");
+
+        Ok(Self {
+            interface: value.interface.ok_or_eyre("missing interface")?,
+            path,
+            math_function,
+            interval: Duration::from_millis(millis),
+            output_type: value.output_type.unwrap_or_default(),
+            duty_cycle: value.duty_cycle,
+        })
+    }
+}
+
+/// Additional stream, with its own generator state
+#[derive(Debug)]
+pub struct ExtraStream {
+    cfg: ExtraStreamConfig,
+    base_value: BaseValue,
+    output: OutputConfig,
+    ticker: Interval,
+    /// Consecutive samples that failed to be sent
+    failures: u32,
+}
+
+impl ExtraStream {
+    /// Build the stream, converting the values with the options of the main stream
+    pub fn new(
+        cfg: ExtraStreamConfig,
+        output: &OutputConfig,
+        now: SystemTime,
+    ) -> eyre::Result<Self> {
+        let mut output = output.clone();
+        output.output_type = cfg.output_type;
+        output.array_length = None;
+        output.aggregate = None;
+
+        let base_value = BaseValue::try_from_system_time(now, 1.0)?;
+        let ticker = periodic_ticker(cfg.interval);
+
+        Ok(Self {
+            cfg,
+            base_value,
+            output,
+            ticker,
+            failures: 0,
+        })
+    }
+
+    /// Interface the data is sent to
+    pub fn interface(&self) -> &str {
+        &self.cfg.interface
+    }
+
+    /// Endpoint the data is sent to
+    pub fn path(&self) -> &str {
+        &self.cfg.path
+    }
+
+    /// Name of the sensor, describing the generated data
+    pub fn name(&self) -> String {
+        format!("{} {}", self.cfg.math_function, self.cfg.output_type)
+    }
+
+    /// Check if the stream sends data after the given time from the start
+    pub fn is_on_duty(&self, elapsed: Duration) -> bool {
+        self.cfg
+            .duty_cycle
+            .map_or(true, |duty_cycle| duty_cycle.is_on(elapsed))
+    }
+
+    /// Ticker of the samples, to reschedule the next one
+    pub fn ticker(&mut self) -> &mut Interval {
+        &mut self.ticker
+    }
+
+    /// Record a sample that failed to be sent, returning the number of consecutive failures
+    pub fn failed(&mut self) -> u32 {
+        self.failures += 1;
+
+        self.failures
+    }
+
+    /// Reset the failures after a successful sample
+    pub fn recovered(&mut self) {
+        if self.failures > 0 {
+            info!(
+                "additional stream {} recovered after {} failures",
+                self.name(),
+                self.failures
+            );
+
+            self.failures = 0;
+        }
+    }
+
+    /// Wait for the next sample
+    pub async fn tick(&mut self) {
+        self.ticker.tick().await;
+    }
+
+    /// Generate the next sample, skipping the given number of steps afterwards
+    pub fn sample(&mut self, steps: u32) -> eyre::Result<AstarteType> {
+        let value = self
+            .cfg
+            .math_function
+            .compute(self.base_value.value())
+            .ok_or_eyre("math function not computable locally")?;
+
+        self.base_value.advance(steps);
+
+        self.output.convert(value)
+    }
+}
+
+/// Wait for the next sample of any of the streams, returning its index
+///
+/// Never completes if there are no streams.
+pub async fn next_tick(streams: &mut [ExtraStream]) -> usize {
+    if streams.is_empty() {
+        return std::future::pending().await;
+    }
+
+    let ticks = streams.iter_mut().map(|stream| Box::pin(stream.tick()));
+
+    let ((), idx, _) = futures::future::select_all(ticks).await;
+
+    idx
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_extra_stream_config_parse() {
+        let cfg = ExtraStreamConfig::parse(
+            "interface=org.astarte-platform.stream-rust-test.IntegerValues, type=integer, \
+             function=saw, interval=200, path=/other/value, duty_cycle=30:90",
+        )
+        .unwrap();
+
+        assert_eq!(
+            cfg,
+            ExtraStreamConfig {
+                interface: "org.astarte-platform.stream-rust-test.IntegerValues".to_string(),
+                path: "/other/value".to_string(),
+                math_function: MathFunction::Saw,
+                interval: Duration::from_millis(200),
+                output_type: OutputType::Integer,
+                duty_cycle: Some(DutyCycle::parse("30:90").unwrap()),
+            }
+        );
+
+        let cfg = ExtraStreamConfig::parse("interface=org.astarte-platform.genericsensors.Values")
+            .unwrap();
+        assert_eq!(cfg.path, "/test/value");
+        assert_eq!(cfg.math_function, MathFunction::Default);
+
+        let invalid = [
+            "",
+            "function=sin",
+            "interface=a,function=exec",
+            "interface=a,interval=0",
+            "interface=a,duty_cycle=0:10",
+            "interface=a,path=value",
+            "interface=a,unknown=1",
+            "interface",
+        ];
+
+        for spec in invalid {
+            assert!(ExtraStreamConfig::parse(spec).is_err(), "{spec:?}");
+        }
+    }
+}
@@ -0,0 +1,157 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Failover to a fallback transport, e.g. from the Message Hub to a direct MQTT connection to
+//! Astarte, when the primary connection fails or stays down for too long.
+
+use crate::astarte::ConnectionConfigBuilder;
+use crate::connection_state::ConnectionState;
+use crate::jitter::Jitter;
+use crate::store::DeviceStore;
+use crate::supervisor::RestartPolicy;
+use astarte_device_sdk::DeviceClient;
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+/// Failover from the primary connection to the fallback one
+#[derive(Debug, Clone)]
+pub struct Failover {
+    fallback: ConnectionConfigBuilder,
+    /// Time the primary connection can stay down before failing over, never if not set
+    after: Option<Duration>,
+    policy: RestartPolicy,
+    jitter: Option<Jitter>,
+}
+
+impl Failover {
+    /// Fail over to the fallback connection, retried with the given policy
+    pub fn new(
+        fallback: ConnectionConfigBuilder,
+        after: Option<Duration>,
+        policy: RestartPolicy,
+        jitter: Option<Jitter>,
+    ) -> Self {
+        Self {
+            fallback,
+            after,
+            policy,
+            jitter,
+        }
+    }
+
+    /// Handle the events of the primary connection, failing over when it fails or stays down
+    ///
+    /// The client of the fallback connection is sent on the channel.
+    pub async fn handle<S, F>(
+        self,
+        primary: F,
+        mut state: watch::Receiver<ConnectionState>,
+        clients: mpsc::Sender<DeviceClient<S>>,
+    ) -> eyre::Result<()>
+    where
+        S: DeviceStore,
+        F: Future<Output = eyre::Result<()>>,
+    {
+        let down = async {
+            match self.after {
+                Some(after) => down_for(&mut state, after).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            res = primary => match res {
+                Ok(()) => return Ok(()),
+                Err(err) => warn!("primary connection failed, failing over: {err:#}"),
+            },
+            _ = down => {
+                let after = self.after.unwrap_or_default();
+
+                warn!("primary connection down for more than {after:?}, failing over");
+            }
+        }
+
+        let (client, connection) = self
+            .fallback
+            .build_with_retry(self.policy, self.jitter)
+            .await
+            .wrap_err("couldn't fail over to the fallback connection")?;
+
+        if clients.send(client).await.is_err() {
+            debug!("stream stopped, closing the fallback connection");
+
+            return Ok(());
+        }
+
+        info!("failed over to the fallback connection");
+
+        connection.handle_events().await
+    }
+}
+
+/// Wait until the connection stays down for longer than the threshold
+pub async fn down_for(state: &mut watch::Receiver<ConnectionState>, threshold: Duration) {
+    loop {
+        let connected = *state.borrow_and_update() == ConnectionState::Connected;
+
+        if connected {
+            if state.changed().await.is_err() {
+                return std::future::pending().await;
+            }
+
+            continue;
+        }
+
+        let reconnected = async {
+            state
+                .wait_for(|state| *state == ConnectionState::Connected)
+                .await
+                .is_ok()
+        };
+
+        match tokio::time::timeout(threshold, reconnected).await {
+            Ok(true) => {}
+            Ok(false) => return std::future::pending().await,
+            Err(_) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::FutureExt;
+
+    #[tokio::test]
+    async fn test_down_for() {
+        let threshold = Duration::from_millis(50);
+        let (tx, mut rx) = watch::channel(ConnectionState::Connected);
+
+        // never down while connected
+        assert!(down_for(&mut rx, threshold).now_or_never().is_none());
+
+        // a short outage doesn't count
+        tx.send(ConnectionState::Reconnecting).unwrap();
+        let outage = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            tx.send(ConnectionState::Connected).unwrap();
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        };
+        tokio::select! {
+            _ = down_for(&mut rx, threshold) => panic!("failed over after a short outage"),
+            _ = outage => {}
+        }
+
+        tx.send(ConnectionState::Disconnected).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), down_for(&mut rx, threshold))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,287 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fleet of devices simulated by a single process, each one with its own connection and streams.
+//!
+//! The device ids are either read from a file, one device per line followed by its optional
+//! credentials secret, or generated by a template. The `{n}` placeholder of the template is replaced
+//! by the index of the device, starting from 0, and `{id}` by a device id derived from the
+//! configured one and the index. Without a template, the first device uses the configured id, while
+//! the others derive theirs from it.
+
+use crate::offset::stable_hash;
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, eyre, WrapErr};
+use std::path::Path;
+use uuid::{Builder, Uuid};
+
+/// Placeholder replaced by the index of the device
+const INDEX: &str = "{n}";
+/// Placeholder replaced by a device id derived from the configured one and the index
+const ID: &str = "{id}";
+
+/// Alphabet of the base64 url encoding of the device ids
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Device of the fleet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FleetDevice {
+    /// Index of the device in the fleet
+    pub index: usize,
+    /// Astarte device id
+    pub device_id: String,
+    /// Credentials secret of the device, the configured credential if not set
+    pub credentials_secret: Option<String>,
+}
+
+/// Template of the ids of the devices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceIdTemplate(String);
+
+impl DeviceIdTemplate {
+    /// Parse a template, which must generate a different id for each device
+    pub fn parse(template: &str) -> eyre::Result<Self> {
+        ensure!(
+            template.contains(INDEX) || template.contains(ID),
+            "the device id template {template} must contain {INDEX} or {ID}"
+        );
+
+        let rest = template.replace(INDEX, "").replace(ID, "");
+
+        ensure!(
+            !rest.contains(['{', '}']),
+            "unknown placeholder in device id template {template}, expected {INDEX} or {ID}"
+        );
+
+        Ok(Self(template.to_string()))
+    }
+
+    /// Id of the n-th device
+    pub fn render(&self, n: usize, device_id: &str) -> String {
+        self.0
+            .replace(INDEX, &n.to_string())
+            .replace(ID, &derive_device_id(n, device_id))
+    }
+}
+
+/// Devices of the fleet
+///
+/// The devices read from a file take precedence over the template, while their number defaults to
+/// the number of lines of the file, or to one device.
+pub fn fleet(
+    devices: Option<usize>,
+    template: Option<&DeviceIdTemplate>,
+    file: Option<Vec<FleetDevice>>,
+    device_id: &str,
+) -> eyre::Result<Vec<FleetDevice>> {
+    if let Some(mut listed) = file {
+        let count = devices.unwrap_or(listed.len());
+
+        ensure!(
+            listed.len() >= count,
+            "{count} devices requested, but only {} listed",
+            listed.len()
+        );
+
+        listed.truncate(count);
+
+        return Ok(listed);
+    }
+
+    let devices = (0..devices.unwrap_or(1))
+        .map(|n| {
+            let id = match (template, n) {
+                (Some(template), n) => template.render(n, device_id),
+                (None, 0) => device_id.to_string(),
+                (None, n) => derive_device_id(n, device_id),
+            };
+
+            FleetDevice {
+                index: n,
+                device_id: id,
+                credentials_secret: None,
+            }
+        })
+        .collect();
+
+    Ok(devices)
+}
+
+/// Read the devices listed in a file
+pub async fn read_devices(path: &Path) -> eyre::Result<Vec<FleetDevice>> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .wrap_err_with(|| format!("couldn't read the device ids from {}", path.display()))?;
+
+    parse_devices(&content).wrap_err_with(|| format!("invalid device ids in {}", path.display()))
+}
+
+/// Parse one device per line, with its optional credentials secret, skipping the empty lines and
+/// the `#` comments
+fn parse_devices(content: &str) -> eyre::Result<Vec<FleetDevice>> {
+    let mut devices: Vec<FleetDevice> = Vec::new();
+
+    let lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    for line in lines {
+        let mut fields = line.split_whitespace();
+        let device_id = fields.next().unwrap_or_default().to_string();
+        let credentials_secret = fields.next().map(str::to_string);
+
+        ensure!(fields.next().is_none(), "unexpected fields in line {line}");
+        ensure!(
+            devices.iter().all(|device| device.device_id != device_id),
+            "duplicated device id {device_id}"
+        );
+
+        devices.push(FleetDevice {
+            index: devices.len(),
+            device_id,
+            credentials_secret,
+        });
+    }
+
+    ensure!(!devices.is_empty(), "no device ids listed");
+
+    Ok(devices)
+}
+
+/// Device id derived from the configured one and the index, a base64 url encoded 128 bit id like
+/// the ones generated by `astartectl`
+fn derive_device_id(n: usize, device_id: &str) -> String {
+    let index = n.to_le_bytes();
+    let high = stable_hash(&[b"device-high", device_id.as_bytes(), &index]);
+    let low = stable_hash(&[b"device-low", device_id.as_bytes(), &index]);
+
+    let mut bytes = [0; 16];
+    bytes[..8].copy_from_slice(&high.to_be_bytes());
+    bytes[8..].copy_from_slice(&low.to_be_bytes());
+
+    base64_url(&bytes)
+}
+
+/// Check that the device id is a base64 url encoded 128 bit id, the format required by Astarte
+pub fn check_device_id(device_id: &str) -> eyre::Result<()> {
+    let sextets = device_id
+        .bytes()
+        .map(|c| BASE64_URL.iter().position(|&b| b == c))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| eyre!("the device id {device_id} isn't base64 url encoded"))?;
+
+    // 128 bits are encoded by 22 characters, the last one with 4 unused bits
+    ensure!(
+        sextets.len() == 22 && sextets.last().is_some_and(|last| last & 0x0f == 0),
+        "the device id {device_id} doesn't encode 128 bits"
+    );
+
+    Ok(())
+}
+
+/// Node id of the n-th device connected to the Message Hub, the configured one for the first device
+pub fn derive_node_id(n: usize, node_id: Uuid) -> Uuid {
+    if n == 0 {
+        return node_id;
+    }
+
+    let index = n.to_le_bytes();
+    let high = stable_hash(&[b"node-high", node_id.as_bytes(), &index]);
+    let low = stable_hash(&[b"node-low", node_id.as_bytes(), &index]);
+
+    let mut bytes = [0; 16];
+    bytes[..8].copy_from_slice(&high.to_be_bytes());
+    bytes[8..].copy_from_slice(&low.to_be_bytes());
+
+    Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+/// Encode the bytes in base64 url, without padding
+fn base64_url(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let mut block = [0; 3];
+        block[..chunk.len()].copy_from_slice(chunk);
+
+        let bits = u32::from_be_bytes([0, block[0], block[1], block[2]]);
+
+        // a chunk of n bytes is encoded by n + 1 characters
+        for i in 0..=chunk.len() {
+            let sextet = (bits >> (18 - 6 * i)) & 0x3f;
+
+            encoded.push(char::from(BASE64_URL[sextet as usize]));
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fleet() {
+        let base = "2TBn-jNESuuHamE2Zo1anA";
+
+        let devices = fleet(Some(3), None, None, base).unwrap();
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[0].device_id, base);
+        assert_eq!(devices[1].device_id.len(), 22);
+        assert_ne!(devices[1].device_id, devices[2].device_id);
+        // the same across restarts
+        assert_eq!(fleet(Some(3), None, None, base).unwrap(), devices);
+
+        let template = DeviceIdTemplate::parse("device-{n}").unwrap();
+        let devices = fleet(Some(2), Some(&template), None, base).unwrap();
+        assert_eq!(devices[0].device_id, "device-0");
+        assert_eq!(devices[1].device_id, "device-1");
+
+        assert!(DeviceIdTemplate::parse("device").is_err());
+        assert!(DeviceIdTemplate::parse("{n}-{index}").is_err());
+
+        let listed = parse_devices("# fleet\nfirst secret-1\n\nsecond\n").unwrap();
+        assert_eq!(listed[0].credentials_secret.as_deref(), Some("secret-1"));
+        assert_eq!(listed[1].credentials_secret, None);
+        assert_eq!(
+            fleet(None, None, Some(listed.clone()), base).unwrap(),
+            listed
+        );
+        assert_eq!(
+            fleet(Some(1), None, Some(listed.clone()), base)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(fleet(Some(3), None, Some(listed), base).is_err());
+
+        assert!(parse_devices("first\nfirst\n").is_err());
+        assert!(parse_devices("first secret extra\n").is_err());
+        assert!(parse_devices("# empty\n").is_err());
+    }
+
+    #[test]
+    fn test_check_device_id() {
+        check_device_id("2TBn-jNESuuHamE2Zo1anA").unwrap();
+        check_device_id(&derive_device_id(1, "2TBn-jNESuuHamE2Zo1anA")).unwrap();
+
+        assert!(check_device_id("device").is_err());
+        assert!(check_device_id("2TBn+jNESuuHamE2Zo1anA").is_err());
+        assert!(check_device_id("2TBn-jNESuuHamE2Zo1anA==").is_err());
+        // the unused bits must be zero
+        assert!(check_device_id("2TBn-jNESuuHamE2Zo1anB").is_err());
+    }
+
+    #[test]
+    fn test_base64_url() {
+        assert_eq!(base64_url(b"f"), "Zg");
+        assert_eq!(base64_url(b"fo"), "Zm8");
+        assert_eq!(base64_url(b"foo"), "Zm9v");
+        assert_eq!(base64_url(&[0xfb, 0xff]), "-_8");
+    }
+}
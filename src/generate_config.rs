@@ -0,0 +1,68 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commented example of the `config.toml` file, with the connection and stream options.
+
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, WrapErr};
+use std::path::Path;
+
+/// Example of the `config.toml` file, with a comment for each option
+pub const EXAMPLE_CONFIG: &str = include_str!("../astarte-device-conf/config.toml");
+
+/// Write the example `config.toml` file, or print it if no output is given
+///
+/// An existing file is overwritten only if forced.
+pub async fn generate_config(output: Option<&Path>, force: bool) -> eyre::Result<()> {
+    let Some(path) = output else {
+        print!("{EXAMPLE_CONFIG}");
+
+        return Ok(());
+    };
+
+    let exists = tokio::fs::try_exists(path)
+        .await
+        .wrap_err_with(|| format!("couldn't access {}", path.display()))?;
+
+    ensure!(
+        force || !exists,
+        "{} already exists, use --force to overwrite it",
+        path.display()
+    );
+
+    tokio::fs::write(path, EXAMPLE_CONFIG)
+        .await
+        .wrap_err_with(|| format!("couldn't write {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::astarte::ConnectionConfigBuilder;
+    use crate::config_file::{ConfigFile, ConfigLocation};
+
+    #[tokio::test]
+    async fn test_generate_config() {
+        let dir = std::env::temp_dir().join(format!("generate-config-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("config.toml");
+
+        generate_config(Some(&path), false).await.unwrap();
+        assert!(generate_config(Some(&path), false).await.is_err());
+        generate_config(Some(&path), true).await.unwrap();
+
+        // the example follows the schema of both the connection and the stream options
+        let location = ConfigLocation::new(&path);
+        ConfigFile::load(&location).await.unwrap();
+        let astarte_cfg_builder = ConnectionConfigBuilder::load(&location).await.unwrap();
+        assert_eq!(
+            astarte_cfg_builder.device_id().as_deref(),
+            Some("DEVICE_ID_HERE")
+        );
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+}
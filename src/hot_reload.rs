@@ -0,0 +1,150 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hot-reload of the stream options of the `config.toml` file.
+//!
+//! The modification time of the file is polled, and once changed the options are re-read and
+//! merged again into the ones of the CLI, which keep taking precedence. The reloaded configuration
+//! is then applied by the streams without dropping the connection to Astarte.
+//!
+//! The SIGHUP is not used, since it already rotates the credentials.
+
+use crate::cli::Config;
+use crate::config_file::{ConfigFile, ConfigLocation};
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+/// Interval between two checks of the modification time of the file
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Watcher reloading the configuration file once modified
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    /// Configuration of the CLI, before merging the options of the file
+    cli_cfg: Config,
+    location: ConfigLocation,
+    /// Last modification time of the file, missing if the file doesn't exist
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    /// Watch the file from its current state
+    pub async fn new(cli_cfg: Config, location: ConfigLocation) -> Self {
+        let modified = modified(&location).await;
+
+        Self {
+            cli_cfg,
+            location,
+            modified,
+        }
+    }
+
+    /// Read the file again, merging its options into the ones of the CLI
+    pub async fn reload(&self) -> eyre::Result<Config> {
+        let mut cfg = self.cli_cfg.clone();
+
+        ConfigFile::load(&self.location)
+            .await?
+            .merge_into(&mut cfg)
+            .wrap_err_with(|| format!("invalid stream options in {}", self.location))?;
+
+        Ok(cfg)
+    }
+
+    /// Check if the file was modified since the last check
+    async fn is_modified(&mut self) -> bool {
+        let modified = modified(&self.location).await;
+
+        if modified == self.modified {
+            return false;
+        }
+
+        self.modified = modified;
+
+        true
+    }
+
+    /// Send the reloaded configuration every time the file is modified
+    ///
+    /// An invalid file is reported and ignored, keeping the current configuration. It stops once
+    /// every receiver is dropped.
+    pub async fn watch(mut self, configs: watch::Sender<Arc<Config>>) {
+        let mut ticker = tokio::time::interval(WATCH_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        debug!("watching {} for changes", self.location);
+
+        loop {
+            tokio::select! {
+                _ = configs.closed() => break,
+                _ = ticker.tick() => {}
+            }
+
+            if !self.is_modified().await {
+                continue;
+            }
+
+            match self.reload().await {
+                Ok(cfg) => {
+                    info!("{} changed, reloading the configuration", self.location);
+
+                    if configs.send(Arc::new(cfg)).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    warn!("couldn't reload the configuration, keeping the current one: {err:#}")
+                }
+            }
+        }
+    }
+}
+
+/// Modification time of the file, if it exists
+async fn modified(location: &ConfigLocation) -> Option<SystemTime> {
+    tokio::fs::metadata(&location.path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[tokio::test]
+    async fn test_reload() {
+        let dir = std::env::temp_dir().join(format!("hot-reload-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("config.toml");
+
+        let cli_cfg = Config::parse_from(["stream-rust-test", "--scale", "2"]);
+        let mut watcher = ConfigWatcher::new(cli_cfg, ConfigLocation::new(&path)).await;
+        assert!(!watcher.is_modified().await);
+
+        tokio::fs::write(&path, "sensors = 3\n[scales]\ntest-1 = 5.0")
+            .await
+            .unwrap();
+        assert!(watcher.is_modified().await);
+        assert!(!watcher.is_modified().await);
+
+        let cfg = watcher.reload().await.unwrap();
+        assert_eq!(cfg.stream.sensors(), 3);
+        assert_eq!(cfg.stream.sensor_scale(0, "test"), 2.0);
+        assert_eq!(cfg.stream.sensor_scale(1, "test-1"), 5.0);
+
+        tokio::fs::write(&path, "sensors = 0").await.unwrap();
+        assert!(watcher.reload().await.is_err());
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runtime update of the device introspection.
+//!
+//! Interfaces can be added or removed while the device is connected, either through these functions
+//! or remotely with the `org.astarte-platform.stream-rust-test.IntrospectionCommands` interface.
+
+use crate::astarte::is_bundled_interface;
+use astarte_device_sdk::introspection::DynamicIntrospection;
+use astarte_device_sdk::{AstarteType, Interface};
+use color_eyre::eyre;
+use color_eyre::eyre::{bail, ensure, WrapErr};
+use std::str::FromStr;
+use tracing::info;
+
+/// Server owned interface of the commands updating the introspection
+pub const INTROSPECTION_COMMANDS_INTERFACE: &str =
+    "org.astarte-platform.stream-rust-test.IntrospectionCommands";
+
+/// Update of the device introspection
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntrospectionCommand {
+    /// Add the interface with the given JSON definition
+    Add(String),
+    /// Remove the interface with the given name
+    Remove(String),
+}
+
+impl IntrospectionCommand {
+    /// Parse a command received on the [`INTROSPECTION_COMMANDS_INTERFACE`]
+    pub fn try_from_value(path: &str, value: AstarteType) -> eyre::Result<Self> {
+        let AstarteType::String(value) = value else {
+            bail!("the introspection commands must be strings");
+        };
+
+        match path {
+            "/add" => Ok(Self::Add(value)),
+            "/remove" => Ok(Self::Remove(value)),
+            _ => bail!("unknown introspection command {path}"),
+        }
+    }
+
+    /// Apply the command to the introspection of the device
+    pub async fn apply<C>(&self, client: &C) -> eyre::Result<()>
+    where
+        C: DynamicIntrospection + Sync,
+    {
+        match self {
+            IntrospectionCommand::Add(json) => add_interface(client, json).await,
+            IntrospectionCommand::Remove(name) => remove_interface(client, name).await,
+        }
+    }
+}
+
+/// Add an interface, given its JSON definition, to the introspection of the device
+pub async fn add_interface<C>(client: &C, json: &str) -> eyre::Result<()>
+where
+    C: DynamicIntrospection + Sync,
+{
+    let interface = Interface::from_str(json).wrap_err("invalid interface")?;
+    let name = interface.interface_name().to_string();
+
+    let added = client
+        .add_interface(interface)
+        .await
+        .wrap_err_with(|| format!("couldn't add interface {name}"))?;
+
+    if added {
+        info!("interface {name} added");
+    } else {
+        info!("interface {name} already in the introspection");
+    }
+
+    Ok(())
+}
+
+/// Remove an interface from the introspection of the device
+///
+/// The bundled interfaces cannot be removed, since the device relies on them and the interface of
+/// the introspection commands couldn't be added back.
+pub async fn remove_interface<C>(client: &C, name: &str) -> eyre::Result<()>
+where
+    C: DynamicIntrospection + Sync,
+{
+    ensure!(
+        !is_bundled_interface(name),
+        "interface {name} is bundled with the device and cannot be removed"
+    );
+
+    let removed = client
+        .remove_interface(name)
+        .await
+        .wrap_err_with(|| format!("couldn't remove interface {name}"))?;
+
+    if removed {
+        info!("interface {name} removed");
+    } else {
+        info!("interface {name} not in the introspection");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_introspection_command_try_from_value() {
+        assert_eq!(
+            IntrospectionCommand::try_from_value(
+                "/remove",
+                AstarteType::String("org.astarte-platform.genericsensors.Values".to_string())
+            )
+            .unwrap(),
+            IntrospectionCommand::Remove("org.astarte-platform.genericsensors.Values".to_string())
+        );
+        assert_eq!(
+            IntrospectionCommand::try_from_value("/add", AstarteType::String("{}".to_string()))
+                .unwrap(),
+            IntrospectionCommand::Add("{}".to_string())
+        );
+
+        assert!(IntrospectionCommand::try_from_value("/add", AstarteType::Boolean(true)).is_err());
+        assert!(
+            IntrospectionCommand::try_from_value("/other", AstarteType::String(String::new()))
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_bundled_interface() {
+        let (client, _connection) = crate::mock::mock();
+
+        assert!(remove_interface(&client, INTROSPECTION_COMMANDS_INTERFACE)
+            .await
+            .is_err());
+        assert!(
+            remove_interface(&client, "org.astarte-platform.genericsensors.Values")
+                .await
+                .is_err()
+        );
+        assert!(
+            remove_interface(&client, "org.astarte-platform.stream-rust-test.Custom")
+                .await
+                .is_ok()
+        );
+    }
+}
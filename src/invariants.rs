@@ -0,0 +1,126 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Invariants of the math functions, checked with property based tests.
+//!
+//! The module is exported with the `test-utils` feature, so that forks adding new math functions
+//! can check them against the same invariants.
+
+use crate::math::MathFunction;
+use clap::ValueEnum;
+use proptest::prelude::*;
+use std::f64::consts::PI;
+
+/// Maximum base value used to check the invariants
+///
+/// Base values derive from the seconds elapsed since the start, so they are never negative.
+pub const MAX_INPUT: f64 = 1e9;
+
+/// Strategy generating the base values given to the math functions
+pub fn input() -> impl Strategy<Value = f64> {
+    0.0..MAX_INPUT
+}
+
+/// Strategy generating every math function computed locally
+pub fn function() -> impl Strategy<Value = MathFunction> {
+    let functions: Vec<MathFunction> = MathFunction::value_variants()
+        .iter()
+        .filter(|function| **function != MathFunction::Exec)
+        .cloned()
+        .collect();
+
+    proptest::sample::select(functions)
+}
+
+/// Inclusive range containing every output of the function, if bounded
+pub fn bounds(function: &MathFunction) -> Option<(f64, f64)> {
+    let bounds = match function {
+        MathFunction::Sin => (-1.0, 1.0),
+        MathFunction::NoiseSin => (-1.0, 2.0),
+        MathFunction::RandomSpikesSin => (-1.0, 102.0),
+        MathFunction::Const | MathFunction::Exec => return None,
+        MathFunction::Saw => (-1.0, 1.0),
+        MathFunction::Rect => (0.0, 1.0),
+        MathFunction::Sinc => (-1.0, 1.0),
+        MathFunction::Random => (0.0, 1.0),
+        MathFunction::Default => {
+            let max = 4.0 / PI + 4.0 * PI * (1.0 / 3.0 + 1.0 / 5.0 + 1.0 / 7.0);
+
+            (-max, max)
+        }
+    };
+
+    Some(bounds)
+}
+
+/// Check the invariants of a function on a base value
+pub fn check(function: &MathFunction, input: f64) -> Result<(), TestCaseError> {
+    let Some(value) = function.compute(input) else {
+        prop_assert_eq!(function, &MathFunction::Exec);
+
+        return Ok(());
+    };
+
+    prop_assert!(value.is_finite(), "{function}({input}) = {value}");
+
+    if let Some((min, max)) = bounds(function) {
+        prop_assert!(
+            (min..=max).contains(&value),
+            "{function}({input}) = {value} outside [{min}, {max}]"
+        );
+    }
+
+    match function {
+        MathFunction::Const => prop_assert_eq!(value, input),
+        MathFunction::Rect => prop_assert!(value == 0.0 || value == 1.0),
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Check that the saw function ramps up within a period
+pub fn check_saw_ramp(input: f64, step: f64) -> Result<(), TestCaseError> {
+    // only compare values in the same period, as computed by the function, with a margin for the
+    // rounding of the sum
+    if input % (2.0 * PI) + step >= 2.0 * PI - 1e-6 {
+        return Ok(());
+    }
+
+    let next = input + step;
+    let saw = |value| MathFunction::Saw.compute(value).unwrap_or_default();
+
+    prop_assert!(saw(input) <= saw(next) + 1e-6, "saw({input}) > saw({next})");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_math_function_invariants(function in function(), input in input()) {
+            check(&function, input)?;
+        }
+
+        #[test]
+        fn test_saw_ramp(input in input(), step in 0.0..1.0) {
+            check_saw_ramp(input, step)?;
+        }
+    }
+
+    #[test]
+    fn test_sinc_peak() {
+        // the sinc is centered in the middle of each period of 20 PI
+        for period in 0..10 {
+            let peak = 10.0 * PI + 20.0 * PI * f64::from(period);
+
+            assert_eq!(MathFunction::Sinc.compute(peak), Some(1.0));
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Random jitter of the intervals between two samples, since perfectly periodic traffic hides the
+//! timing-related bugs of the downstream pipelines.
+
+use rand::Rng;
+use std::time::Duration;
+
+/// Jitter delaying each sample by a random time around the nominal interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Jitter {
+    /// Maximum deviation, as a fraction of the interval
+    fraction: f64,
+}
+
+impl Jitter {
+    /// Deviate up to the given percentage of the interval, no jitter if zero
+    pub fn new(percent: u8) -> Option<Self> {
+        (percent > 0).then(|| Self {
+            fraction: f64::from(percent.min(100)) / 100.0,
+        })
+    }
+
+    /// Random interval, uniformly distributed around the nominal one
+    pub fn apply(&self, interval: Duration) -> Duration {
+        let factor = rand::thread_rng().gen_range(1.0 - self.fraction..=1.0 + self.fraction);
+
+        interval.mul_f64(factor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_jitter() {
+        assert_eq!(Jitter::new(0), None);
+
+        let jitter = Jitter::new(20).unwrap();
+        let interval = Duration::from_millis(1000);
+
+        for _ in 0..100 {
+            let jittered = jitter.apply(interval);
+
+            assert!(jittered >= Duration::from_millis(800), "{jittered:?}");
+            assert!(jittered <= Duration::from_millis(1200), "{jittered:?}");
+        }
+
+        // a full jitter never makes the interval negative
+        assert!(Jitter::new(100).unwrap().apply(interval) <= Duration::from_millis(2000));
+    }
+}
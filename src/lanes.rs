@@ -0,0 +1,93 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Send lanes separating the normal telemetry from the critical events.
+//!
+//! Messages on the critical lane (alarms and heartbeats) are sent immediately, even when the
+//! telemetry lane is paused or throttled.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Lane a message is sent on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    /// Normal telemetry, subject to the stream state and limits
+    Telemetry,
+    /// Critical events, always sent immediately
+    Critical,
+}
+
+impl Display for Lane {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Lane::Telemetry => write!(f, "telemetry"),
+            Lane::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+/// Counters of the messages of a single lane
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaneCounter {
+    /// Messages sent successfully
+    pub sent: u64,
+    /// Messages that couldn't be sent
+    pub failed: u64,
+}
+
+/// Counters of the messages of each lane
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaneCounters {
+    telemetry: LaneCounter,
+    critical: LaneCounter,
+}
+
+impl LaneCounters {
+    /// Get the counter of a lane
+    pub fn get(&self, lane: Lane) -> LaneCounter {
+        match lane {
+            Lane::Telemetry => self.telemetry,
+            Lane::Critical => self.critical,
+        }
+    }
+
+    fn get_mut(&mut self, lane: Lane) -> &mut LaneCounter {
+        match lane {
+            Lane::Telemetry => &mut self.telemetry,
+            Lane::Critical => &mut self.critical,
+        }
+    }
+
+    /// Sum of the counters of every lane
+    pub fn total(&self) -> LaneCounter {
+        LaneCounter {
+            sent: self.telemetry.sent + self.critical.sent,
+            failed: self.telemetry.failed + self.critical.failed,
+        }
+    }
+
+    /// Record the outcome of a message sent on a lane
+    pub fn record(&mut self, lane: Lane, success: bool) {
+        let counter = self.get_mut(lane);
+
+        if success {
+            counter.sent += 1;
+        } else {
+            counter.failed += 1;
+        }
+    }
+}
+
+impl Display for LaneCounters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "telemetry sent {} failed {}, critical sent {} failed {}",
+            self.telemetry.sent, self.telemetry.failed, self.critical.sent, self.critical.failed
+        )
+    }
+}
@@ -0,0 +1,135 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Distribution of the downlink latency measured on the received commands.
+
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::time::Duration;
+
+/// Maximum number of latency samples used to compute the distribution
+const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Latency samples of the most recent commands
+#[derive(Debug, Clone)]
+pub struct LatencyStats {
+    samples: VecDeque<Duration>,
+    capacity: usize,
+    count: u64,
+}
+
+impl Default for LatencyStats {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl LatencyStats {
+    /// Keep at most `capacity` samples, discarding the oldest ones
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            count: 0,
+        }
+    }
+
+    /// Record the latency of a command
+    pub fn record(&mut self, latency: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(latency);
+        self.count += 1;
+    }
+
+    /// Compute the distribution of the recorded samples
+    pub fn summary(&self) -> Option<LatencySummary> {
+        let mut sorted: Vec<Duration> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+
+        let min = *sorted.first()?;
+        let max = *sorted.last()?;
+        let total: Duration = sorted.iter().sum();
+
+        // nearest-rank percentile
+        let percentile = |p: usize| {
+            let rank = (p * sorted.len() + 99) / 100;
+
+            sorted[rank.saturating_sub(1)]
+        };
+
+        Some(LatencySummary {
+            count: self.count,
+            min,
+            mean: total / sorted.len() as u32,
+            p50: percentile(50),
+            p95: percentile(95),
+            p99: percentile(99),
+            max,
+        })
+    }
+}
+
+/// Distribution of the latency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencySummary {
+    /// Number of measured commands
+    pub count: u64,
+    /// Minimum latency
+    pub min: Duration,
+    /// Mean latency
+    pub mean: Duration,
+    /// Median latency
+    pub p50: Duration,
+    /// 95th percentile of the latency
+    pub p95: Duration,
+    /// 99th percentile of the latency
+    pub p99: Duration,
+    /// Maximum latency
+    pub max: Duration,
+}
+
+impl Display for LatencySummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "count {} min {:?} mean {:?} p50 {:?} p95 {:?} p99 {:?} max {:?}",
+            self.count, self.min, self.mean, self.p50, self.p95, self.p99, self.max
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_latency_summary() {
+        let mut stats = LatencyStats::with_capacity(100);
+        assert!(stats.summary().is_none());
+
+        // the first sample is discarded
+        for ms in 0..=100 {
+            stats.record(Duration::from_millis(ms));
+        }
+
+        let summary = stats.summary().unwrap();
+
+        assert_eq!(summary.count, 101);
+        assert_eq!(summary.min, Duration::from_millis(1));
+        assert_eq!(summary.p50, Duration::from_millis(50));
+        assert_eq!(summary.p95, Duration::from_millis(95));
+        assert_eq!(summary.p99, Duration::from_millis(99));
+        assert_eq!(summary.max, Duration::from_millis(100));
+        assert_eq!(summary.mean, Duration::from_micros(50_500));
+    }
+}
@@ -7,7 +7,66 @@
 #![doc = include_str!("../README.md")]
 #![warn(clippy::dbg_macro, missing_docs, rustdoc::missing_crate_level_docs)]
 
+pub mod aggregate;
 pub mod astarte;
+pub mod churn;
+pub mod cleanup;
 pub mod cli;
+pub mod clock;
+pub mod completions;
+pub mod config_file;
+pub mod connection_state;
+pub mod connection_watchdog;
+pub mod correlation;
+pub mod degradation;
+pub mod describe;
+pub mod diagnostics;
+pub mod dry_run;
+pub mod duration;
+pub mod duty_cycle;
+pub mod env_prefix;
+pub mod exec;
+pub mod extra_stream;
+pub mod failover;
+pub mod fleet;
+pub mod generate_config;
+pub mod hot_reload;
+pub mod introspection;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod invariants;
+pub mod jitter;
+pub mod lanes;
+pub mod latency;
+pub mod log_file;
+pub mod logging;
+pub mod loopback;
 pub mod math;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock;
+pub mod offset;
+pub mod output;
+pub mod path;
+pub mod path_template;
+pub mod preflight;
+pub mod privacy;
+pub mod prompt;
+pub mod ramp_up;
+pub mod rate_limit;
+pub mod reliability;
+pub mod remote_config;
+pub mod replay;
+pub mod retention;
+pub mod rotation;
+pub mod sampler;
+pub mod schedule;
+pub mod sensor_id;
 pub mod shutdown;
+pub mod snapshot;
+pub mod status;
+pub mod store;
+pub mod stream;
+pub mod supervisor;
+pub mod timestamp;
+pub mod type_fuzz;
+pub mod validation;
+pub mod window;
@@ -0,0 +1,228 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Logs written to a file, rotated by size and time so that long soak tests keep their history
+//! without filling the disk.
+//!
+//! Once rotated, the file is renamed appending `.1` to its name, while the older ones are shifted
+//! to `.2`, `.3` and so on, deleting the ones exceeding the maximum number of rotated files.
+
+use crate::duration;
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Options of the log file
+#[derive(Debug, Clone, PartialEq, Eq, clap::Args)]
+pub struct LogFileOptions {
+    /// File the logs are written to, in addition to the standard output
+    #[clap(long = "log-file", env = "LOG_FILE", global = true)]
+    pub path: Option<PathBuf>,
+    /// Bytes written to the log file before rotating it
+    #[clap(
+        long = "log-max-size",
+        env = "LOG_MAX_SIZE",
+        default_value = "10485760",
+        value_parser = clap::value_parser!(u64).range(1..),
+        global = true
+    )]
+    pub max_size: u64,
+    /// Time after which the log file is rotated regardless of its size (e.g. `1h`, `1d`; bare
+    /// numbers are seconds)
+    #[clap(
+        long = "log-rotation",
+        env = "LOG_ROTATION",
+        value_parser = duration::parse_whole_secs,
+        global = true
+    )]
+    pub rotation: Option<u64>,
+    /// Rotated log files kept, the older ones are deleted
+    #[clap(
+        long = "log-max-files",
+        env = "LOG_MAX_FILES",
+        default_value = "5",
+        global = true
+    )]
+    pub max_files: usize,
+}
+
+impl LogFileOptions {
+    /// Open the log file, if set
+    pub fn open(&self) -> eyre::Result<Option<RotatingFile>> {
+        let Some(path) = &self.path else {
+            return Ok(None);
+        };
+
+        RotatingFile::open(
+            path,
+            self.max_size,
+            self.rotation.map(Duration::from_secs),
+            self.max_files,
+        )
+        .map(Some)
+        .wrap_err_with(|| format!("couldn't open the log file {}", path.display()))
+    }
+}
+
+/// File rotated once exceeding its maximum size or age
+#[derive(Debug)]
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    /// Bytes written to the current file
+    size: u64,
+    opened: Instant,
+    max_size: u64,
+    max_age: Option<Duration>,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    /// Open the file, appending to it if it exists
+    pub fn open(
+        path: impl Into<PathBuf>,
+        max_size: u64,
+        max_age: Option<Duration>,
+        max_files: usize,
+    ) -> io::Result<Self> {
+        let path = path.into();
+
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            file,
+            size,
+            opened: Instant::now(),
+            max_size,
+            max_age,
+            max_files,
+        })
+    }
+
+    /// Path of the n-th rotated file
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{n}"));
+
+        path.into()
+    }
+
+    /// Check if the file must be rotated before writing the given bytes
+    fn must_rotate(&self, len: usize) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+
+        let too_big = self.size.saturating_add(len as u64) > self.max_size;
+        let too_old = self
+            .max_age
+            .is_some_and(|max_age| self.opened.elapsed() >= max_age);
+
+        too_big || too_old
+    }
+
+    /// Shift the rotated files, deleting the oldest one, and start a new file
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            remove_if_exists(&self.path)?;
+        } else {
+            remove_if_exists(&self.rotated(self.max_files))?;
+
+            for n in (1..self.max_files).rev() {
+                rename_if_exists(&self.rotated(n), &self.rotated(n + 1))?;
+            }
+
+            rename_if_exists(&self.path, &self.rotated(1))?;
+        }
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.opened = Instant::now();
+
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.must_rotate(buf.len()) {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
+
+fn rename_if_exists(from: &Path, to: &Path) -> io::Result<()> {
+    match std::fs::rename(from, to) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = std::env::temp_dir().join(format!("log-file-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("stream.log");
+
+        let mut file = RotatingFile::open(&path, 10, None, 2).unwrap();
+
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+
+        let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), "fourth\n");
+        assert_eq!(read(file.rotated(1)), "third\n");
+        assert_eq!(read(file.rotated(2)), "second\n");
+        assert!(!file.rotated(3).exists());
+
+        // appends to the existing file, rotating it once old
+        let mut file = RotatingFile::open(&path, 1024, Some(Duration::ZERO), 2).unwrap();
+        assert_eq!(file.size, 7);
+        file.write_all(b"fifth\n").unwrap();
+        assert_eq!(read(path.clone()), "fifth\n");
+        assert_eq!(read(file.rotated(1)), "fourth\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Logs of the application: their verbosity, set with the `-v` and `-q` flags instead of the
+//! `RUST_LOG` syntax, and their format.
+
+use clap::ValueEnum;
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use tracing::Subscriber;
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::{DefaultFields, Writer};
+use tracing_subscriber::fmt::{FormatFields, MakeWriter};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+/// Target of the summary logged when the streams stop, shown even in quiet mode
+pub const SUMMARY_TARGET: &str = "stream_rust_test::summary";
+
+/// Format of the logs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, with the fields of the events at the top level
+    Json,
+}
+
+impl LogFormat {
+    /// Layer formatting the logs to the writer, with colors only if `ansi` is set
+    ///
+    /// The JSON objects also have a `span` object with the fields of the current span, e.g. the
+    /// `sensor_id` of the sample being sent.
+    pub fn layer<S, W>(self, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+        W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+    {
+        let layer = fmt::layer().with_writer(writer).with_ansi(ansi);
+
+        match self {
+            Self::Text if ansi => layer.boxed(),
+            Self::Text => layer.fmt_fields(PlainFields::default()).boxed(),
+            Self::Json => layer
+                .json()
+                .flatten_event(true)
+                .with_span_list(false)
+                .boxed(),
+        }
+    }
+}
+
+/// Fields formatted without colors
+///
+/// The formatted fields of the spans are cached by formatter type, so the layers without colors
+/// need their own one to avoid reusing the colored fields of the other layers.
+#[derive(Debug, Default)]
+struct PlainFields(DefaultFields);
+
+impl<'writer> FormatFields<'writer> for PlainFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        writer: Writer<'writer>,
+        fields: R,
+    ) -> std::fmt::Result {
+        self.0.format_fields(writer, fields)
+    }
+}
+
+/// Verbosity of the logs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    /// Only the warnings, the errors and the final summary
+    Quiet,
+    /// Debug logs of the application, or the `RUST_LOG` filter if set
+    #[default]
+    Normal,
+    /// Trace logs of the application and info logs of the dependencies, e.g. the SDK
+    Verbose,
+    /// Trace logs of everything
+    Trace,
+}
+
+impl Verbosity {
+    /// Verbosity selected by the number of `-v` flags or by the `-q` one
+    pub fn from_flags(verbose: u8, quiet: bool) -> Self {
+        match (verbose, quiet) {
+            (_, true) => Self::Quiet,
+            (0, false) => Self::Normal,
+            (1, false) => Self::Verbose,
+            _ => Self::Trace,
+        }
+    }
+
+    /// Filter of the logs, the flags taking precedence over the `RUST_LOG` environment variable
+    pub fn env_filter(self) -> eyre::Result<EnvFilter> {
+        let directives = match self {
+            Self::Quiet => format!("warn,{SUMMARY_TARGET}=info"),
+            Self::Normal => {
+                return EnvFilter::builder()
+                    .with_default_directive("stream_rust_test=debug".parse()?)
+                    .from_env()
+                    .wrap_err("failed to set debug filter");
+            }
+            Self::Verbose => "info,stream_rust_test=trace".to_string(),
+            Self::Trace => "trace".to_string(),
+        };
+
+        EnvFilter::builder()
+            .parse(&directives)
+            .wrap_err_with(|| format!("invalid log filter {directives}"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_verbosity() {
+        assert_eq!(Verbosity::from_flags(0, false), Verbosity::Normal);
+        assert_eq!(Verbosity::from_flags(1, false), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(3, false), Verbosity::Trace);
+        assert_eq!(Verbosity::from_flags(0, true), Verbosity::Quiet);
+
+        let quiet = Verbosity::Quiet.env_filter().unwrap().to_string();
+        assert!(quiet.contains("warn"));
+        assert!(quiet.contains(&format!("{SUMMARY_TARGET}=info")));
+    }
+}
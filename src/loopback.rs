@@ -0,0 +1,240 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Client handling the data locally instead of sending it to Astarte.
+//!
+//! The [`LoopbackClient`] implements the client traits of the Astarte Device SDK, handing the sent
+//! data to its [`Loopback`] and receiving the events from it. The introspection is accepted as is
+//! and no property is stored, so the dry run and the mock connection only define what happens to
+//! the data.
+
+use astarte_device_sdk::chrono::{DateTime, Utc};
+use astarte_device_sdk::client::{ClientDisconnect, RecvError};
+use astarte_device_sdk::introspection::DynamicIntrospection;
+use astarte_device_sdk::properties::PropAccess;
+use astarte_device_sdk::store::StoredProp;
+use astarte_device_sdk::types::TypeError;
+use astarte_device_sdk::{
+    AstarteAggregate, AstarteType, Client, DeviceEvent, Error, Interface, Value,
+};
+use async_trait::async_trait;
+use std::path::Path;
+
+/// Local handling of the data sent and received by a [`LoopbackClient`]
+#[async_trait]
+pub trait Loopback: Send + Sync {
+    /// Handle the data sent on the given interface and path, with its explicit timestamp if any
+    fn send(&self, interface: &str, path: &str, data: Value, timestamp: Option<DateTime<Utc>>);
+
+    /// Wait for the next event received from Astarte
+    async fn recv(&self) -> Result<DeviceEvent, RecvError>;
+}
+
+/// Client handing the data to its [`Loopback`] instead of sending it to Astarte
+#[derive(Debug, Clone)]
+pub struct LoopbackClient<L> {
+    loopback: L,
+}
+
+impl<L> LoopbackClient<L> {
+    /// Client of the given loopback
+    pub fn new(loopback: L) -> Self {
+        Self { loopback }
+    }
+
+    /// Mutable loopback of the client
+    pub fn loopback_mut(&mut self) -> &mut L {
+        &mut self.loopback
+    }
+}
+
+#[async_trait]
+impl<L> Client for LoopbackClient<L>
+where
+    L: Loopback,
+{
+    async fn send_object_with_timestamp<D>(
+        &self,
+        interface_name: &str,
+        interface_path: &str,
+        data: D,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), Error>
+    where
+        D: AstarteAggregate + Send,
+    {
+        let data = Value::Object(data.astarte_aggregate()?);
+
+        self.loopback
+            .send(interface_name, interface_path, data, Some(timestamp));
+
+        Ok(())
+    }
+
+    async fn send_object<D>(
+        &self,
+        interface_name: &str,
+        interface_path: &str,
+        data: D,
+    ) -> Result<(), Error>
+    where
+        D: AstarteAggregate + Send,
+    {
+        let data = Value::Object(data.astarte_aggregate()?);
+
+        self.loopback
+            .send(interface_name, interface_path, data, None);
+
+        Ok(())
+    }
+
+    async fn send_with_timestamp<D>(
+        &self,
+        interface_name: &str,
+        interface_path: &str,
+        data: D,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), Error>
+    where
+        D: TryInto<AstarteType> + Send,
+    {
+        let data = data.try_into().map_err(|_| TypeError::Conversion)?;
+
+        self.loopback.send(
+            interface_name,
+            interface_path,
+            Value::Individual(data),
+            Some(timestamp),
+        );
+
+        Ok(())
+    }
+
+    async fn send<D>(
+        &self,
+        interface_name: &str,
+        interface_path: &str,
+        data: D,
+    ) -> Result<(), Error>
+    where
+        D: TryInto<AstarteType> + Send,
+    {
+        let data = data.try_into().map_err(|_| TypeError::Conversion)?;
+
+        self.loopback.send(
+            interface_name,
+            interface_path,
+            Value::Individual(data),
+            None,
+        );
+
+        Ok(())
+    }
+
+    async fn unset(&self, interface_name: &str, interface_path: &str) -> Result<(), Error> {
+        self.loopback
+            .send(interface_name, interface_path, Value::Unset, None);
+
+        Ok(())
+    }
+
+    async fn recv(&self) -> Result<DeviceEvent, RecvError> {
+        self.loopback.recv().await
+    }
+}
+
+#[async_trait]
+impl<L> ClientDisconnect for LoopbackClient<L>
+where
+    L: Loopback,
+{
+    async fn disconnect(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<L> DynamicIntrospection for LoopbackClient<L>
+where
+    L: Loopback,
+{
+    async fn add_interface(&self, _interface: Interface) -> Result<bool, Error> {
+        Ok(true)
+    }
+
+    async fn extend_interfaces<I>(&self, interfaces: I) -> Result<Vec<String>, Error>
+    where
+        I: IntoIterator<Item = Interface> + Send,
+    {
+        Ok(interfaces
+            .into_iter()
+            .map(|interface| interface.interface_name().to_string())
+            .collect())
+    }
+
+    async fn extend_interfaces_vec(
+        &self,
+        interfaces: Vec<Interface>,
+    ) -> Result<Vec<String>, Error> {
+        self.extend_interfaces(interfaces).await
+    }
+
+    async fn add_interface_from_file<P>(&self, _file_path: P) -> Result<bool, Error>
+    where
+        P: AsRef<Path> + Send + Sync,
+    {
+        Ok(true)
+    }
+
+    async fn add_interface_from_str(&self, _json_str: &str) -> Result<bool, Error> {
+        Ok(true)
+    }
+
+    async fn remove_interface(&self, _interface_name: &str) -> Result<bool, Error> {
+        Ok(true)
+    }
+
+    async fn remove_interfaces<I>(&self, interfaces_name: I) -> Result<Vec<String>, Error>
+    where
+        I: IntoIterator<Item = String> + Send,
+        I::IntoIter: Send,
+    {
+        Ok(interfaces_name.into_iter().collect())
+    }
+
+    async fn remove_interfaces_vec(
+        &self,
+        interfaces_name: Vec<String>,
+    ) -> Result<Vec<String>, Error> {
+        Ok(interfaces_name)
+    }
+}
+
+#[async_trait]
+impl<L> PropAccess for LoopbackClient<L>
+where
+    L: Loopback,
+{
+    async fn property(&self, _interface: &str, _path: &str) -> Result<Option<AstarteType>, Error> {
+        Ok(None)
+    }
+
+    async fn interface_props(&self, _interface: &str) -> Result<Vec<StoredProp>, Error> {
+        Ok(Vec::new())
+    }
+
+    async fn all_props(&self) -> Result<Vec<StoredProp>, Error> {
+        Ok(Vec::new())
+    }
+
+    async fn device_props(&self) -> Result<Vec<StoredProp>, Error> {
+        Ok(Vec::new())
+    }
+
+    async fn server_props(&self) -> Result<Vec<StoredProp>, Error> {
+        Ok(Vec::new())
+    }
+}
@@ -4,97 +4,641 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use astarte_device_sdk::EventLoop;
-use clap::Parser;
+use astarte_device_sdk::client::ClientDisconnect;
+use astarte_device_sdk::store::memory::MemoryStore;
+use astarte_device_sdk::store::SqliteStore;
 use color_eyre::eyre;
-use color_eyre::eyre::WrapErr;
-use std::time::SystemTime;
-use stream_rust_test::astarte::{send_data, ConnectionConfigBuilder, SdkConnection};
-use stream_rust_test::cli::Config;
-use stream_rust_test::shutdown::shutdown;
+use color_eyre::eyre::{bail, ensure, WrapErr};
+use futures::FutureExt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use stream_rust_test::astarte::{
+    handle_grpc_events, redact_url, AstarteConnection, ConnectionConfigBuilder, PairingEnv,
+    SdkConnection,
+};
+use stream_rust_test::cleanup::cleanup;
+use stream_rust_test::cli::{Command, Config};
+use stream_rust_test::completions::completions;
+use stream_rust_test::config_file::{find_config_file, ConfigFile, ConfigLocation};
+use stream_rust_test::connection_state::{ConnectionObserver, ConnectionStateLayer};
+use stream_rust_test::connection_watchdog::ConnectionWatchdog;
+use stream_rust_test::describe::describe;
+use stream_rust_test::dry_run::DryRunClient;
+use stream_rust_test::env_prefix::EnvPrefix;
+use stream_rust_test::failover::Failover;
+use stream_rust_test::fleet::{fleet, read_devices};
+use stream_rust_test::generate_config::generate_config;
+use stream_rust_test::hot_reload::ConfigWatcher;
+use stream_rust_test::jitter::Jitter;
+use stream_rust_test::preflight::{validate_config, validate_interfaces};
+use stream_rust_test::prompt::TerminalPrompt;
+use stream_rust_test::replay::replay;
+use stream_rust_test::shutdown::{pause_signals, rotation_signals, shutdown, watchdog};
+use stream_rust_test::store::{DeviceStore, StoreType};
+use stream_rust_test::stream::StreamManager;
+use stream_rust_test::supervisor::RestartPolicy;
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio::task::JoinSet;
 use tracing::{debug, error, info, warn};
+use tracing_subscriber::filter::filter_fn;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::Layer;
 
-fn env_filter() -> eyre::Result<EnvFilter> {
-    EnvFilter::builder()
-        .with_default_directive("stream_rust_test=debug".parse()?)
-        .from_env()
-        .wrap_err("failed to set debug filter")
+/// Time given to the tasks to shut down once the maximum runtime is exceeded
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Directory of the configuration file
+#[cfg_attr(feature = "docker", allow(unused_variables))]
+fn config_dir(env_prefix: &EnvPrefix) -> eyre::Result<PathBuf> {
+    // default path where to search for the configuration file
+    let path_str = "/etc/stream-rust-test".to_string();
+
+    #[cfg(not(feature = "docker"))]
+    // if we are not using containers, we try to retrieve the config file from a specific location
+    // set by the user in the ASTARTE_CONFIG_PATH environment variable, with the prefix if any
+    let path_str = match std::env::var(&*env_prefix.var("ASTARTE_CONFIG_PATH")) {
+        Ok(path) => {
+            debug!("configuration directory: {path}");
+            path
+        }
+        Err(std::env::VarError::NotPresent) => {
+            debug!("configuration default directory (/etc/stream-rust-test)");
+            path_str
+        }
+        Err(err) => {
+            error!("failed to retrieve the configuration directory: {err}");
+            return Err(color_eyre::Report::new(err));
+        }
+    };
+
+    Ok(PathBuf::from(path_str))
 }
 
-#[tokio::main]
-async fn main() -> eyre::Result<()> {
-    color_eyre::install()?;
+/// Configuration of the connection to Astarte, merging the environment variables and the
+/// `config.toml`, tuned by the CLI options
+///
+/// If `interactive` and started from a terminal, the missing MQTT values are asked.
+async fn connection_config(
+    cli_cfg: &Config,
+    config_location: &ConfigLocation,
+    interactive: bool,
+) -> eyre::Result<ConnectionConfigBuilder> {
+    info!("retrieve Astarte connection config from ENV and {config_location}");
 
-    let filter = env_filter()?;
-    tracing_subscriber::registry()
-        .with(fmt::layer())
-        .with(filter)
-        .init();
+    let prompt = TerminalPrompt::new().filter(|_| interactive);
+    let mut astarte_cfg_builder = match prompt {
+        Some(mut prompt) => {
+            ConnectionConfigBuilder::load_or_prompt(config_location, &mut prompt).await?
+        }
+        None => ConnectionConfigBuilder::load(config_location).await?,
+    };
+    astarte_cfg_builder.cli_overrides(cli_cfg);
+
+    debug!("Astarte connection config:\n{}", astarte_cfg_builder.dump());
+
+    Ok(astarte_cfg_builder)
+}
+
+/// Print the stream options and the Astarte connection configuration, with the secrets redacted
+async fn print_config(cli_cfg: &Config, config_location: &ConfigLocation) -> eyre::Result<()> {
+    let mut stream_cfg = cli_cfg.clone();
+    stream_cfg.connection.proxy = stream_cfg.connection.proxy.as_deref().map(redact_url);
+    // the options given after the command are already the effective ones
+    stream_cfg.command = None;
+
+    println!("# stream options\n{stream_cfg:#?}\n");
+    println!("# Astarte connection from {config_location}");
+
+    match connection_config(cli_cfg, config_location, false).await {
+        Ok(astarte_cfg_builder) => println!("{}", astarte_cfg_builder.dump()),
+        Err(err) => println!("invalid connection configuration: {err:#}"),
+    }
+
+    Ok(())
+}
+
+/// Configurations of the connections of the simulated devices
+///
+/// A single device keeps the configured id and store directory, while the devices of a fleet store
+/// their data in a subdirectory named after their id.
+async fn fleet_config(
+    cli_cfg: &Config,
+    astarte_cfg_builder: &ConnectionConfigBuilder,
+) -> eyre::Result<Vec<ConnectionConfigBuilder>> {
+    let device_ids = match &cli_cfg.connection.device_ids {
+        Some(path) => Some(read_devices(path).await?),
+        None => None,
+    };
+    let configured = cli_cfg.connection.device_id_template.is_none()
+        && device_ids.is_none()
+        && !cli_cfg.connection.random_device_id;
+
+    let fleet = fleet(
+        cli_cfg.connection.devices.map(|devices| devices as usize),
+        cli_cfg.connection.device_id_template.as_ref(),
+        device_ids,
+        &astarte_cfg_builder.device_id().unwrap_or_default(),
+    )?;
+
+    if configured && fleet.len() == 1 {
+        return Ok(vec![astarte_cfg_builder.clone()]);
+    }
+
+    let mut devices = Vec::with_capacity(fleet.len());
+
+    for device in &fleet {
+        let builder = astarte_cfg_builder.fleet_device(device);
+
+        if let Some(store_directory) = builder.store_directory() {
+            tokio::fs::create_dir_all(store_directory)
+                .await
+                .wrap_err_with(|| format!("couldn't create the store of {}", device.device_id))?;
+        }
+
+        devices.push(builder);
+    }
+
+    Ok(devices)
+}
+
+/// Send the values recorded in a file through a volatile connection to Astarte
+async fn replay_file(
+    astarte_cfg_builder: ConnectionConfigBuilder,
+    file: &Path,
+    retry: RestartPolicy,
+    jitter: Option<Jitter>,
+) -> eyre::Result<()> {
+    let (client, connection) = astarte_cfg_builder
+        .build_with_retry::<MemoryStore>(retry, jitter)
+        .await?;
+
+    let events = tokio::spawn(connection.handle_events());
+
+    let sent = replay(&client, file).await;
+
+    client.disconnect().await?;
+    events.await??;
+
+    info!("replayed {} values from {}", sent?, file.display());
+
+    Ok(())
+}
+
+/// Options of the connection to Astarte
+struct ConnectOptions {
+    retry: RestartPolicy,
+    jitter: Option<Jitter>,
+    reattach: Option<RestartPolicy>,
+    failover_after: Option<Duration>,
+    /// Layer observing the connections
+    connection_state: ConnectionStateLayer,
+    /// State, diagnostics and activity of the connection of this device
+    observer: ConnectionObserver,
+    watchdog: Option<Duration>,
+    /// Requests of rotating the credentials, re-read from the config file
+    rotation: watch::Receiver<u64>,
+    config_location: ConfigLocation,
+}
+
+/// Connect to Astarte with the given store, spawning the tasks handling the connection and the
+/// streams
+async fn connect<S, F>(
+    mut stream: StreamManager,
+    mut astarte_cfg_builder: ConnectionConfigBuilder,
+    options: ConnectOptions,
+    tasks: &mut JoinSet<eyre::Result<()>>,
+    stop: F,
+) -> eyre::Result<()>
+where
+    S: DeviceStore,
+    F: Future<Output = ()> + Send + 'static,
+{
+    let mut fallback = astarte_cfg_builder.fallback()?;
+
+    let (client, connection) = match astarte_cfg_builder
+        .clone()
+        .build_with_retry::<S>(options.retry, options.jitter)
+        .await
+    {
+        Ok(connected) => connected,
+        Err(err) => {
+            let Some(fallback) = fallback.take() else {
+                return Err(err);
+            };
+
+            warn!("failing over to the fallback connection: {err:#}");
+
+            astarte_cfg_builder = fallback;
+            astarte_cfg_builder
+                .clone()
+                .build_with_retry(options.retry, options.jitter)
+                .await?
+        }
+    };
+
+    if let Some(transport) = astarte_cfg_builder.transport() {
+        stream.diagnostics_on(transport, options.observer.diagnostics());
+    }
+
+    // only the MQTT sessions are re-established with the rotated credentials
+    let rotates = astarte_cfg_builder.connection() == Some(AstarteConnection::Mqtt);
+
+    // the clients of the new connections, after a re-attachment, a rebuild or a failover
+    let (clients_tx, clients_rx) = mpsc::channel(1);
+    let reattached =
+        (options.reattach.is_some() || fallback.is_some() || options.watchdog.is_some() || rotates)
+            .then_some(clients_rx);
+
+    let watchdog = (options.watchdog.is_some() || rotates).then(|| {
+        let mut watchdog =
+            ConnectionWatchdog::new(astarte_cfg_builder.clone(), options.retry, options.jitter);
+
+        if let Some(timeout) = options.watchdog {
+            watchdog = watchdog.stuck_after(timeout);
+        }
+
+        if rotates {
+            watchdog = watchdog.rotate_credentials(options.rotation, options.config_location);
+        }
+
+        watchdog
+    });
+
+    let events = match (connection, options.reattach) {
+        (SdkConnection::Grpc(grpc_con), Some(policy)) => {
+            // poll updates from the gRPC connection, re-attaching the node
+            handle_grpc_events(
+                astarte_cfg_builder,
+                grpc_con,
+                policy,
+                options.jitter,
+                clients_tx.clone(),
+            )
+            .boxed()
+        }
+        // poll updates from the MQTT connection with Astarte or the gRPC one with the Message Hub
+        (connection, _) => connection.handle_events().boxed(),
+    };
+
+    // rebuild the connection once stuck or rotated, before failing over
+    let events = match watchdog {
+        Some(watchdog) => watchdog
+            .handle(events, options.observer.activity(), clients_tx.clone())
+            .boxed(),
+        None => events,
+    };
+
+    let events = match fallback {
+        Some(fallback) => {
+            let failover = Failover::new(
+                fallback,
+                options.failover_after,
+                options.retry,
+                options.jitter,
+            );
+
+            failover
+                .handle(events, options.observer.state(), clients_tx)
+                .boxed()
+        }
+        None => {
+            // without a failover, only the re-attachments replace the connection
+            drop(clients_tx);
+
+            events
+        }
+    };
+
+    let connection_state = options.connection_state;
+    let observer = options.observer;
+    tasks.spawn(async move { connection_state.run_observed(observer, events).await });
+
+    tasks.spawn(stream.handle(client, reattached, stop));
+
+    Ok(())
+}
+
+/// Location of the configuration file
+async fn config_location(cli_cfg: &Config) -> eyre::Result<ConfigLocation> {
+    let env_prefix = cli_cfg.env_prefix();
+
+    Ok(
+        ConfigLocation::new(find_config_file(&config_dir(&env_prefix)?).await?)
+            .with_profile(cli_cfg.profile.clone())
+            .with_env_prefix(env_prefix),
+    )
+}
+
+/// Root certificates and proxy of the connections to the Pairing API
+async fn pairing_env(cli_cfg: &Config) -> PairingEnv {
+    // an invalid location is reported once looked up again
+    let location = config_location(cli_cfg).await.ok();
+
+    PairingEnv::load(cli_cfg, location.as_ref()).await
+}
+
+fn main() -> eyre::Result<()> {
+    color_eyre::install()?;
 
     // time instant when the program starts its execution
     let now = SystemTime::now();
 
     // initialize CLI configuration options
-    let cli_cfg = Config::parse();
+    let cli_cfg = Config::parse_with_env_prefix();
 
-    debug!("parsed CLI config: {:#?}", cli_cfg);
+    // the environment read by the SDK is set before the runtime spawns its worker threads, since
+    // changing it isn't thread safe
+    let pairing_env = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(pairing_env(&cli_cfg));
+    pairing_env.apply();
 
-    let mut tasks = JoinSet::<eyre::Result<()>>::new();
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(cli_cfg, now))
+}
+
+/// Run the command of the CLI
+async fn run(mut cli_cfg: Config, now: SystemTime) -> eyre::Result<()> {
+    let filter = cli_cfg.logging.verbosity().env_filter()?;
+    let log_file = match cli_cfg.logging.log_file.open()? {
+        Some(file) => Some(
+            cli_cfg
+                .logging
+                .log_format
+                .layer(Mutex::new(file), false)
+                .with_filter(cli_cfg.logging.verbosity().env_filter()?),
+        ),
+        None => None,
+    };
+    let connection_state = ConnectionStateLayer::new();
+    tracing_subscriber::registry()
+        .with(
+            cli_cfg
+                .logging
+                .log_format
+                .layer(std::io::stdout, true)
+                .with_filter(filter),
+        )
+        .with(log_file)
+        .with(
+            connection_state
+                .clone()
+                .with_filter(filter_fn(ConnectionStateLayer::observes)),
+        )
+        .init();
+
+    // the example doesn't depend on the current config
+    if let Some(Command::GenerateConfig { output, force }) = &cli_cfg.command {
+        return generate_config(output.as_deref(), *force).await;
+    }
+
+    if let Some(Command::Completions { shell }) = cli_cfg.command {
+        completions(shell, &mut std::io::stdout());
+
+        return Ok(());
+    }
+
+    let config_location = config_location(&cli_cfg).await?;
+
+    // report the invalid stream options too, instead of failing on the first one
+    if let Some(Command::Validate(_)) = cli_cfg.command {
+        let issues = validate_config(&cli_cfg, &config_location).await;
 
-    // populate the builder using the environment variables (if set)
-    let mut astarte_cfg_builder = ConnectionConfigBuilder::default();
+        for issue in &issues {
+            println!("{issue}");
+        }
+
+        if !issues.is_empty() {
+            bail!("found {} configuration issues", issues.len());
+        }
 
-    info!("retrieve Astarte connection config from ENV");
-    if let Err(err) = astarte_cfg_builder.try_from_env() {
-        warn!("failed to retrieve Astarte connection config from ENV: {err}");
+        println!("the configuration is valid");
 
-        // default path where to search for config.toml
-        let path_str = "/etc/stream-rust-test".to_string();
+        return Ok(());
+    }
+
+    // the reloaded options of the config.toml are merged again into the ones of the CLI
+    let cli_only = cli_cfg.clone();
+
+    // add the options declared in the config.toml to the ones of the CLI
+    let config_file = ConfigFile::load(&config_location).await?;
+    debug!("loaded stream options from {config_location}: {config_file:?}");
+    config_file
+        .merge_into(&mut cli_cfg)
+        .wrap_err_with(|| format!("invalid stream options in {config_location}"))?;
+
+    if cli_cfg.stream.print_config {
+        return print_config(&cli_cfg, &config_location).await;
+    }
 
-        #[cfg(not(feature = "docker"))]
-        // if we are not using containers, we try to retrieve the config file from a specific location
-        // set by the user in the ASTARTE_CONFIG_PATH environment variable
-        let path_str = match std::env::var("ASTARTE_CONFIG_PATH") {
-            Ok(path) => {
-                debug!("retrieve Astarte connection config from path: {path}");
-                path
+    match &cli_cfg.command {
+        Some(Command::Describe) => {
+            println!("{:#}", describe()?);
+
+            return Ok(());
+        }
+        Some(Command::ValidateInterfaces(_)) => {
+            let issues = validate_interfaces(&cli_cfg).await?;
+
+            for issue in &issues {
+                println!("{issue}");
             }
-            Err(std::env::VarError::NotPresent) => {
-                debug!(
-                    "retrieve Astarte connection config from default path (/etc/stream-rust-test)"
-                );
-                path_str
+
+            if !issues.is_empty() {
+                bail!("found {} interface mismatches", issues.len());
             }
-            Err(err) => {
-                error!("failed to retrieve Astarte connection config: {err}");
-                return Err(color_eyre::Report::new(err));
+
+            println!("the interfaces match the configured streams");
+
+            return Ok(());
+        }
+        Some(Command::Cleanup { local_only, .. }) => {
+            let astarte_cfg_builder = connection_config(&cli_cfg, &config_location, true).await?;
+            let fleet = fleet_config(&cli_cfg, &astarte_cfg_builder).await?;
+
+            return cleanup(&fleet, *local_only).await;
+        }
+        Some(Command::Replay { file, .. }) => {
+            let astarte_cfg_builder = connection_config(&cli_cfg, &config_location, true).await?;
+            let retry = cli_cfg.connection.connect_retry_policy();
+            let jitter = Jitter::new(cli_cfg.connection.connect_jitter_percent);
+
+            return replay_file(astarte_cfg_builder, file, retry, jitter).await;
+        }
+        Some(Command::Bench(_)) => {
+            ensure!(
+                cli_cfg.stream.count.is_some() || cli_cfg.stream.runtime_limit().is_some(),
+                "the bench needs a --count, --duration or --max-runtime limit"
+            );
+
+            // the bench never connects to Astarte
+            cli_cfg.connection.dry_run = true;
+        }
+        Some(
+            Command::Run(_)
+            | Command::Validate(_)
+            | Command::GenerateConfig { .. }
+            | Command::Completions { .. },
+        )
+        | None => {}
+    }
+
+    let bench = matches!(cli_cfg.command, Some(Command::Bench(_)));
+
+    debug!("parsed CLI config: {:#?}", cli_cfg);
+
+    let max_runtime = cli_cfg.stream.runtime_limit();
+
+    if let Some(max_runtime) = max_runtime {
+        // terminate the process even if the graceful shutdown hangs
+        watchdog(max_runtime + SHUTDOWN_GRACE_PERIOD);
+    }
+
+    let connect_retry = cli_cfg.connection.connect_retry_policy();
+    let connect_jitter = Jitter::new(cli_cfg.connection.connect_jitter_percent);
+    let reattach_policy = cli_cfg.connection.reattach_policy();
+    let failover_after = cli_cfg.connection.failover_after.map(Duration::from_millis);
+    let connection_watchdog = cli_cfg
+        .connection
+        .connection_watchdog
+        .map(Duration::from_millis);
+    let store = cli_cfg.connection.store;
+    let retention_limits = cli_cfg.connection.retention_limits();
+
+    let mut tasks = JoinSet::<eyre::Result<()>>::new();
+
+    let mut astarte_cfg_builder =
+        match connection_config(&cli_cfg, &config_location, !cli_cfg.connection.dry_run).await {
+            Ok(astarte_cfg_builder) => astarte_cfg_builder,
+            Err(err) if cli_cfg.connection.dry_run => {
+                warn!("dry run without a valid Astarte connection config: {err:#}");
+
+                ConnectionConfigBuilder::default()
             }
+            Err(err) => return Err(err),
         };
 
-        let path = std::path::PathBuf::from(path_str).join("config.toml");
+    if cli_cfg.connection.random_device_id {
+        let device_id = astarte_cfg_builder.register_random_device().await?;
+
+        info!("registered the random device id {device_id}");
+    }
+
+    let fleet = fleet_config(&cli_cfg, &astarte_cfg_builder).await?;
+
+    if fleet.len() > 1 {
+        info!("simulating {} devices", fleet.len());
+    }
+
+    let paused = pause_signals()?;
+    let rotation = rotation_signals()?;
+
+    let config_reload = if cli_cfg.stream.watch_config {
+        let (tx, rx) = watch::channel(Arc::new(cli_cfg.clone()));
+        let watcher = ConfigWatcher::new(cli_only, config_location.clone()).await;
 
-        astarte_cfg_builder.from_toml(path).await;
+        tokio::spawn(watcher.watch(tx));
+
+        Some(rx)
+    } else {
+        None
     };
 
-    let (client, connection) = astarte_cfg_builder.build().await?;
+    // spawn task to send data to Astarte and receive the stream configuration updates
+    let (stop_tx, stop_rx) = oneshot::channel::<()>();
+    let mut stop_tx = Some(stop_tx);
+
+    let stop = async move {
+        // a dropped sender never stops the streams
+        if stop_rx.await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+    .shared();
+
+    for astarte_cfg_builder in fleet {
+        let mut stream = StreamManager::new(cli_cfg.clone(), now).await?;
+
+        if let Some(device_id) = astarte_cfg_builder.device_id() {
+            stream.set_device_id(&device_id);
+        }
+
+        if let Some(store_directory) = astarte_cfg_builder.store_directory() {
+            stream.persist_remote_config(store_directory).await?;
+            stream.persist_snapshot(store_directory);
+        }
+
+        stream.pause_on(paused.clone());
+        stream.rotate_credentials_on(rotation.clone());
 
-    match connection {
-        SdkConnection::Mqtt(mqtt_con) => {
-            // spawn task to poll updates from the MQTT connection with Astarte
-            tasks.spawn(async move { mqtt_con.handle_events().await.map_err(Into::into) });
+        if let Some(config_reload) = &config_reload {
+            stream.reload_config_on(config_reload.clone());
         }
-        SdkConnection::Grpc(grpc_con) => {
-            // spawn task to poll updates from the gRPC connection with the Astarte Message Hub
-            tasks.spawn(async move { grpc_con.handle_events().await.map_err(Into::into) });
+
+        // each device observes its own connection
+        let observer = ConnectionObserver::new();
+        stream.connection_state_on(observer.state());
+        stream.record_activity_on(observer.clone());
+
+        let options = ConnectOptions {
+            retry: connect_retry,
+            jitter: connect_jitter,
+            reattach: reattach_policy,
+            failover_after,
+            connection_state: connection_state.clone(),
+            observer,
+            watchdog: connection_watchdog,
+            rotation: rotation.subscribe(),
+            config_location: config_location.clone(),
+        };
+
+        let stop = stop.clone();
+
+        if cli_cfg.connection.dry_run {
+            let endpoint = astarte_cfg_builder.endpoint().unwrap_or("no endpoint");
+            let mut client = DryRunClient::dry_run(endpoint);
+
+            if bench {
+                client = client.quiet();
+            }
+
+            tasks.spawn(stream.handle(client, None, stop));
+
+            continue;
+        }
+
+        match store {
+            StoreType::Sqlite => {
+                let database = astarte_cfg_builder.sqlite_database()?;
+
+                connect::<SqliteStore, _>(stream, astarte_cfg_builder, options, &mut tasks, stop)
+                    .await?;
+
+                // evict the stored retention exceeding the limits
+                if let (Some(limits), Some(database)) = (retention_limits, database) {
+                    tasks.spawn(limits.enforce(database));
+                }
+            }
+            StoreType::Volatile => {
+                connect::<MemoryStore, _>(stream, astarte_cfg_builder, options, &mut tasks, stop)
+                    .await?
+            }
         }
     }
 
-    // spawn task to send data to Astarte
-    tasks.spawn(send_data(client, now, cli_cfg));
+    let max_runtime_elapsed = async move {
+        match max_runtime {
+            Some(max_runtime) => tokio::time::sleep(max_runtime).await,
+            None => std::future::pending().await,
+        }
+    };
+    let mut max_runtime_elapsed = std::pin::pin!(max_runtime_elapsed);
 
     // handle tasks termination
     loop {
@@ -104,6 +648,13 @@ async fn main() -> eyre::Result<()> {
                 tasks.abort_all();
                 break;
             },
+            _ = &mut max_runtime_elapsed, if stop_tx.is_some() => {
+                info!("maximum runtime exceeded, shutting down");
+
+                if let Some(stop_tx) = stop_tx.take() {
+                    let _ = stop_tx.send(());
+                }
+            },
             opt = tasks.join_next() => {
                 let Some(res) = opt else {
                     break;
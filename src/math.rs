@@ -7,6 +7,7 @@
 //! Math functions used to simulate values sensored by the device to be sent to Astarte
 
 use f64::consts::PI;
+use serde::{Deserialize, Serialize};
 use std::f64;
 use std::fmt::Display;
 use std::time::{SystemTime, SystemTimeError};
@@ -36,14 +37,27 @@ impl BaseValue {
         self.value
     }
 
+    /// Set the scale used to update the inner value
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale = scale;
+    }
+
     /// Update the inner value
     pub fn update(&mut self) {
         self.value += PI * 2.0 * random_interval() * self.scale;
     }
+
+    /// Update the inner value as many times as the given number of steps
+    pub fn advance(&mut self, steps: u32) {
+        for _ in 0..steps {
+            self.update();
+        }
+    }
 }
 
 /// Math functions
-#[derive(Debug, Clone, Default, clap::ValueEnum)]
+#[derive(Debug, Clone, Default, PartialEq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum MathFunction {
     /// Sine
     Sin,
@@ -63,6 +77,10 @@ pub enum MathFunction {
     Sinc,
     /// Random value
     Random,
+    /// Value computed by an external process
+    ///
+    /// See [`ExecFunction`](crate::exec::ExecFunction).
+    Exec,
     /// Default value
     #[default]
     Default,
@@ -79,6 +97,7 @@ impl Display for MathFunction {
             MathFunction::Rect => "rect",
             MathFunction::Sinc => "sinc",
             MathFunction::Random => "random",
+            MathFunction::Exec => "exec",
             MathFunction::Default => "default",
         };
 
@@ -88,8 +107,11 @@ impl Display for MathFunction {
 
 impl MathFunction {
     /// Use math functions to compute an output value given a certain input
-    pub fn compute(&self, value: f64) -> f64 {
-        match self {
+    ///
+    /// Returns [`None`] for [`MathFunction::Exec`], since its value must be computed by an
+    /// [`ExecFunction`](crate::exec::ExecFunction).
+    pub fn compute(&self, value: f64) -> Option<f64> {
+        let value = match self {
             MathFunction::Sin => sin(value),
             MathFunction::NoiseSin => noise_sin(value),
             MathFunction::RandomSpikesSin => random_spikes_sin(value),
@@ -98,8 +120,11 @@ impl MathFunction {
             MathFunction::Rect => rect(value),
             MathFunction::Sinc => sinc(value),
             MathFunction::Random => random(),
+            MathFunction::Exec => return None,
             MathFunction::Default => default(value),
-        }
+        };
+
+        Some(value)
     }
 }
 
@@ -157,6 +182,15 @@ fn random() -> f64 {
     rand::random::<f64>()
 }
 
+/// Sample of the standard normal distribution
+pub(crate) fn standard_normal() -> f64 {
+    // Box-Muller transform, with u1 in (0, 1] to avoid ln(0)
+    let u1 = 1.0 - random();
+    let u2 = random();
+
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
 fn random_interval() -> f64 {
     (random() * 1000.0) % 600.0 + random()
 }
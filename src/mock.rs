@@ -0,0 +1,149 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mock connection to Astarte, to test the streams end to end without a live instance.
+//!
+//! The client records the messages sent by the device, while the connection injects the events
+//! received from Astarte. The module is exported with the `test-utils` feature.
+
+use crate::loopback::{Loopback, LoopbackClient};
+use astarte_device_sdk::chrono::{DateTime, Utc};
+use astarte_device_sdk::client::RecvError;
+use astarte_device_sdk::{DeviceEvent, Value};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, Notify};
+
+/// Message sent through the mock client
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentMessage {
+    /// Interface of the message
+    pub interface: String,
+    /// Path of the message
+    pub path: String,
+    /// Individual or object data, or the unset of a property
+    pub data: Value,
+    /// Explicit timestamp of the message, if any
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Messages sent by the client, notifying the waiting connection
+#[derive(Debug, Default)]
+struct Outbox {
+    messages: Mutex<Vec<SentMessage>>,
+    notify: Notify,
+}
+
+impl Outbox {
+    fn push(&self, message: SentMessage) {
+        self.messages
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(message);
+
+        self.notify.notify_waiters();
+    }
+
+    fn find(&self, predicate: &impl Fn(&SentMessage) -> bool) -> Option<SentMessage> {
+        self.messages
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .iter()
+            .find(|message| predicate(message))
+            .cloned()
+    }
+}
+
+/// Client recording the sent messages and receiving the injected events
+pub type MockClient = LoopbackClient<MockLoopback>;
+
+/// Loopback of the mock client, recording the sent messages and receiving the injected events
+#[derive(Debug, Clone)]
+pub struct MockLoopback {
+    outbox: Arc<Outbox>,
+    events: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<DeviceEvent>>>,
+}
+
+/// Connection of the mock client
+///
+/// Dropping the connection disconnects the client.
+#[derive(Debug)]
+pub struct MockConnection {
+    outbox: Arc<Outbox>,
+    events: mpsc::UnboundedSender<DeviceEvent>,
+}
+
+/// Mock client and its connection
+pub fn mock() -> (MockClient, MockConnection) {
+    let outbox = Arc::new(Outbox::default());
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let client = LoopbackClient::new(MockLoopback {
+        outbox: Arc::clone(&outbox),
+        events: Arc::new(tokio::sync::Mutex::new(rx)),
+    });
+    let connection = MockConnection { outbox, events: tx };
+
+    (client, connection)
+}
+
+impl MockConnection {
+    /// Inject an event received from Astarte
+    pub fn inject(&self, interface: &str, path: &str, data: Value) {
+        let event = DeviceEvent {
+            interface: interface.to_string(),
+            path: path.to_string(),
+            data,
+        };
+
+        // the client is gone only once the test stopped handling the events
+        let _ = self.events.send(event);
+    }
+
+    /// Messages sent by the client so far
+    pub fn sent(&self) -> Vec<SentMessage> {
+        self.outbox
+            .messages
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Wait for the first message sent matching the predicate
+    pub async fn wait_for(&self, predicate: impl Fn(&SentMessage) -> bool) -> SentMessage {
+        loop {
+            // registered before checking, so that no message is missed
+            let notified = self.outbox.notify.notified();
+
+            if let Some(message) = self.outbox.find(&predicate) {
+                return message;
+            }
+
+            notified.await;
+        }
+    }
+}
+
+#[async_trait]
+impl Loopback for MockLoopback {
+    fn send(&self, interface: &str, path: &str, data: Value, timestamp: Option<DateTime<Utc>>) {
+        self.outbox.push(SentMessage {
+            interface: interface.to_string(),
+            path: path.to_string(),
+            data,
+            timestamp,
+        });
+    }
+
+    async fn recv(&self) -> Result<DeviceEvent, RecvError> {
+        self.events
+            .lock()
+            .await
+            .recv()
+            .await
+            .ok_or(RecvError::Disconnected)
+    }
+}
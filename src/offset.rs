@@ -0,0 +1,109 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-sensor phase and amplitude offsets, so that sensors using the same math function don't send
+//! identical data.
+//!
+//! The offsets are derived from a stable hash of the device id, the sensor id and a seed, so they
+//! are the same across restarts and platforms.
+
+use std::f64::consts::PI;
+
+/// Maximum relative deviation of the amplitude from the generated value
+const AMPLITUDE_SPREAD: f64 = 0.2;
+
+/// Phase and amplitude offsets of a sensor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorOffset {
+    /// Radians added to the input of the math function
+    phase: f64,
+    /// Factor multiplied to the output of the math function
+    amplitude: f64,
+}
+
+impl Default for SensorOffset {
+    fn default() -> Self {
+        Self {
+            phase: 0.0,
+            amplitude: 1.0,
+        }
+    }
+}
+
+impl SensorOffset {
+    /// Derive the offsets of a sensor from its identity and a seed
+    pub fn derive(device_id: &str, sensor_id: &str, seed: u64) -> Self {
+        let hash = stable_hash(&[
+            &seed.to_le_bytes(),
+            device_id.as_bytes(),
+            sensor_id.as_bytes(),
+        ]);
+
+        // use the two halves of the hash as independent values in [0, 1)
+        let phase = (hash >> 32) as f64 / (1u64 << 32) as f64;
+        let amplitude = (hash & 0xffff_ffff) as f64 / (1u64 << 32) as f64;
+
+        Self {
+            phase: 2.0 * PI * phase,
+            amplitude: 1.0 + AMPLITUDE_SPREAD * (2.0 * amplitude - 1.0),
+        }
+    }
+
+    /// Shift the input of the math function by the phase offset
+    pub fn input(&self, value: f64) -> f64 {
+        value + self.phase
+    }
+
+    /// Scale the output of the math function by the amplitude offset
+    pub fn output(&self, value: f64) -> f64 {
+        value * self.amplitude
+    }
+}
+
+/// FNV-1a hash of the parts, finalized with the SplitMix64 mixer to spread the bits
+///
+/// The std hasher is not used since its output may change between Rust releases.
+pub(crate) fn stable_hash(parts: &[&[u8]]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+
+    for part in parts {
+        // separate the parts, so that ("ab", "c") and ("a", "bc") differ
+        for byte in part.iter().chain(std::iter::once(&0xff)) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sensor_offset_derive() {
+        let offset = SensorOffset::derive("device", "test", 42);
+
+        assert_eq!(offset, SensorOffset::derive("device", "test", 42));
+        assert_ne!(offset, SensorOffset::derive("device", "test", 43));
+        assert_ne!(offset, SensorOffset::derive("other", "test", 42));
+        assert_ne!(
+            SensorOffset::derive("ab", "c", 42),
+            SensorOffset::derive("a", "bc", 42)
+        );
+
+        assert!((0.0..2.0 * PI).contains(&offset.phase));
+        assert!((1.0 - AMPLITUDE_SPREAD..=1.0 + AMPLITUDE_SPREAD).contains(&offset.amplitude));
+    }
+}
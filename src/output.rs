@@ -0,0 +1,586 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversion of the generated values into the Astarte type of the interface mapping.
+
+use crate::aggregate::Aggregate;
+use crate::duration;
+use astarte_device_sdk::chrono::{DateTime, TimeDelta, Utc};
+use astarte_device_sdk::AstarteType;
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, OptionExt, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Astarte type of the values sent by the stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputType {
+    /// Send the generated values as `double`
+    #[default]
+    Double,
+    /// Send the generated values rounded to the nearest `integer`
+    ///
+    /// Values outside the 32 bit range are saturated to the closest bound.
+    Integer,
+    /// Send `true` if the generated value is above a threshold, `false` otherwise
+    ///
+    /// Random toggles can be obtained by combining the `random` math function with a threshold of
+    /// `0.5`.
+    Boolean,
+    /// Send a `string`, generated according to the string format
+    String,
+    /// Send the generated values rounded to the nearest `longinteger`
+    ///
+    /// Values outside the 64 bit range are saturated to the closest bound. Optionally, 64 bit
+    /// boundary values can be sent instead of the generated values.
+    #[clap(name = "longinteger")]
+    #[serde(rename = "longinteger")]
+    LongInteger,
+    /// Send a `binaryblob` of configurable size, filled according to the blob content
+    #[clap(name = "binaryblob")]
+    #[serde(rename = "binaryblob")]
+    BinaryBlob,
+    /// Send a `datetime`, generated according to the datetime source
+    #[clap(name = "datetime")]
+    #[serde(rename = "datetime")]
+    DateTime,
+}
+
+/// Source of the datetimes sent with the datetime output type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DateTimeSource {
+    /// Current time of the device
+    #[default]
+    Now,
+    /// Current time of the device shifted by a fixed offset
+    Offset,
+    /// Synthetic datetimes, starting from a given datetime and advancing by a fixed step per sample
+    Synthetic,
+}
+
+/// Content of the blobs sent with the binaryblob output type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BlobContent {
+    /// Random bytes
+    #[default]
+    Random,
+    /// Incrementing bytes, wrapping around at 255
+    Pattern,
+}
+
+/// Boundary values sent with the longinteger output type
+///
+/// They include the 64 bit extremes and the values around 2^53, the biggest integer exactly
+/// representable by a double, to verify that the precision is preserved end to end.
+pub const LONGINTEGER_BOUNDARIES: [i64; 10] = [
+    i64::MIN,
+    i64::MIN + 1,
+    -(1 << 53) - 1,
+    -(1 << 53),
+    0,
+    1 << 53,
+    (1 << 53) + 1,
+    (1 << 53) + 2,
+    i64::MAX - 1,
+    i64::MAX,
+];
+
+/// Maximum size in bytes of the blobs sent with the binaryblob output type, also when updated from
+/// Astarte, since each blob is allocated at every sample
+pub const MAX_BLOB_SIZE: usize = 1024 * 1024;
+
+/// Parse the size of the blobs, up to [`MAX_BLOB_SIZE`]
+pub fn parse_blob_size(s: &str) -> eyre::Result<usize> {
+    let size: usize = s
+        .parse()
+        .wrap_err_with(|| format!("invalid blob size {s}"))?;
+
+    ensure!(
+        size <= MAX_BLOB_SIZE,
+        "the blob size {size} exceeds the maximum {MAX_BLOB_SIZE}"
+    );
+
+    Ok(size)
+}
+
+/// Format of the strings sent with the string output type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StringFormat {
+    /// Fill a template with the generated value
+    #[default]
+    Template,
+    /// Send a random UUID
+    Uuid,
+    /// Send the lines of a file, one per sample, restarting from the first one at the end
+    File,
+}
+
+/// Options for the conversion of the generated values
+#[derive(Debug, Clone, clap::Args)]
+pub struct OutputConfig {
+    /// Astarte type of the values sent to the interface
+    #[clap(short, long, default_value = "double", env = "OUTPUT_TYPE")]
+    pub output_type: OutputType,
+    /// Threshold above which a value is sent as `true` with the boolean output type
+    #[clap(long, default_value = "0.0", env = "BOOLEAN_THRESHOLD")]
+    pub boolean_threshold: f64,
+    /// Format of the strings sent with the string output type
+    #[clap(long, default_value = "template", env = "STRING_FORMAT")]
+    pub string_format: StringFormat,
+    /// Template of the strings sent with the string output type
+    ///
+    /// The `{value}` placeholder is replaced with the generated value and the `{count}`
+    /// placeholder with the number of strings generated so far.
+    #[clap(long, default_value = "value={value}", env = "STRING_TEMPLATE")]
+    pub string_template: String,
+    /// File containing the lines sent with the `file` string format
+    #[clap(long, env = "STRING_FILE")]
+    pub string_file: Option<PathBuf>,
+    /// Lines read from the string file
+    #[clap(skip)]
+    lines: Vec<String>,
+    /// Send the longinteger boundary values instead of the generated ones
+    #[clap(long, env = "LONGINTEGER_BOUNDARIES")]
+    pub longinteger_boundaries: bool,
+    /// Size in bytes of the blobs sent with the binaryblob output type, up to 1 MiB
+    #[clap(long, default_value = "16", env = "BLOB_SIZE", value_parser = parse_blob_size)]
+    pub blob_size: usize,
+    /// Content of the blobs sent with the binaryblob output type
+    #[clap(long, default_value = "random", env = "BLOB_CONTENT")]
+    pub blob_content: BlobContent,
+    /// Source of the datetimes sent with the datetime output type
+    #[clap(long, default_value = "now", env = "DATETIME_SOURCE")]
+    pub datetime_source: DateTimeSource,
+    /// Time added to the current time with the `offset` datetime source, can be negative (e.g.
+    /// `-1h`, `30s`; bare numbers are seconds)
+    #[clap(
+        long,
+        default_value = "0",
+        allow_hyphen_values = true,
+        env = "DATETIME_OFFSET",
+        value_parser = duration::parse_signed_secs
+    )]
+    pub datetime_offset: i64,
+    /// First datetime sent with the `synthetic` datetime source, in RFC 3339 format
+    ///
+    /// Defaults to the time of the first sample.
+    #[clap(long, env = "DATETIME_START")]
+    pub datetime_start: Option<DateTime<Utc>>,
+    /// Time between two datetimes sent with the `synthetic` datetime source, can be negative (e.g.
+    /// `1s`, `-1m`; bare numbers are seconds)
+    #[clap(
+        long,
+        default_value = "1",
+        allow_hyphen_values = true,
+        env = "DATETIME_STEP",
+        value_parser = duration::parse_signed_secs
+    )]
+    pub datetime_step: i64,
+    /// Send arrays of the given length instead of single values
+    ///
+    /// The arrays are filled with the values of the math function over a period, starting from
+    /// the current sample.
+    #[clap(long, env = "ARRAY_LENGTH")]
+    pub array_length: Option<usize>,
+    /// Send object aggregated data, with fields derived from the generated value
+    ///
+    /// The output type is ignored, since all the fields are sent as `double`.
+    #[clap(long, conflicts_with = "array_length", env = "AGGREGATE")]
+    pub aggregate: Option<Aggregate>,
+    /// Number of strings, boundary values or synthetic datetimes generated so far
+    #[clap(skip)]
+    count: u64,
+}
+
+impl OutputConfig {
+    /// Read the lines of the string file, if configured
+    pub async fn load_string_file(&mut self) -> eyre::Result<()> {
+        let Some(path) = &self.string_file else {
+            return Ok(());
+        };
+
+        let file = tokio::fs::read_to_string(path)
+            .await
+            .wrap_err_with(|| format!("couldn't read {}", path.display()))?;
+
+        self.lines = file.lines().map(str::to_string).collect();
+
+        ensure!(
+            !self.lines.is_empty(),
+            "empty string file {}",
+            path.display()
+        );
+
+        Ok(())
+    }
+
+    fn string(&mut self, value: f64) -> eyre::Result<String> {
+        let string = match self.string_format {
+            StringFormat::Template => self
+                .string_template
+                .replace("{value}", &value.to_string())
+                .replace("{count}", &self.count.to_string()),
+            StringFormat::Uuid => Uuid::new_v4().to_string(),
+            StringFormat::File => {
+                ensure!(!self.lines.is_empty(), "missing string file");
+
+                let idx = self.count % self.lines.len() as u64;
+
+                self.lines
+                    .get(idx as usize)
+                    .cloned()
+                    .ok_or_eyre("line out of bound")?
+            }
+        };
+
+        self.count = self.count.wrapping_add(1);
+
+        Ok(string)
+    }
+
+    fn blob(&self) -> Vec<u8> {
+        match self.blob_content {
+            BlobContent::Random => (0..self.blob_size).map(|_| rand::random()).collect(),
+            // truncating the index is intended, to wrap around
+            BlobContent::Pattern => (0..self.blob_size).map(|i| i as u8).collect(),
+        }
+    }
+
+    fn datetime(&mut self) -> eyre::Result<DateTime<Utc>> {
+        let datetime = match self.datetime_source {
+            DateTimeSource::Now => Utc::now(),
+            DateTimeSource::Offset => TimeDelta::try_seconds(self.datetime_offset)
+                .and_then(|offset| Utc::now().checked_add_signed(offset))
+                .ok_or_eyre("datetime offset out of range")?,
+            DateTimeSource::Synthetic => {
+                let start = *self.datetime_start.get_or_insert_with(Utc::now);
+
+                let datetime = i64::try_from(self.count)
+                    .ok()
+                    .and_then(|count| count.checked_mul(self.datetime_step))
+                    .and_then(TimeDelta::try_seconds)
+                    .and_then(|elapsed| start.checked_add_signed(elapsed))
+                    .ok_or_eyre("synthetic datetime out of range")?;
+
+                self.count = self.count.wrapping_add(1);
+
+                datetime
+            }
+        };
+
+        Ok(datetime)
+    }
+
+    /// Name of the bundled interface with a mapping of the configured type
+    pub fn default_interface(&self) -> &'static str {
+        if let Some(aggregate) = &self.aggregate {
+            aggregate.default_interface()
+        } else if self.array_length.is_some() {
+            self.output_type.default_array_interface()
+        } else {
+            self.output_type.default_interface()
+        }
+    }
+
+    /// Convert a generated value into the configured output type
+    pub fn convert(&mut self, value: f64) -> eyre::Result<AstarteType> {
+        match self.output_type {
+            OutputType::Double => AstarteType::try_from(value)
+                .wrap_err_with(|| format!("invalid double value {value}")),
+            // the cast saturates on overflow and maps NaN to 0
+            OutputType::Integer => Ok(AstarteType::Integer(value.round() as i32)),
+            OutputType::Boolean => Ok(AstarteType::Boolean(value > self.boolean_threshold)),
+            OutputType::String => self.string(value).map(AstarteType::String),
+            OutputType::LongInteger if self.longinteger_boundaries => {
+                let idx = self.count % LONGINTEGER_BOUNDARIES.len() as u64;
+                self.count = self.count.wrapping_add(1);
+
+                Ok(AstarteType::LongInteger(
+                    LONGINTEGER_BOUNDARIES[idx as usize],
+                ))
+            }
+            // the cast saturates on overflow and maps NaN to 0
+            OutputType::LongInteger => Ok(AstarteType::LongInteger(value.round() as i64)),
+            OutputType::BinaryBlob => Ok(AstarteType::BinaryBlob(self.blob())),
+            OutputType::DateTime => self.datetime().map(AstarteType::DateTime),
+        }
+    }
+
+    /// Convert the generated values into an array of the configured output type
+    pub fn convert_array(&mut self, values: &[f64]) -> eyre::Result<AstarteType> {
+        let items = values
+            .iter()
+            .map(|value| self.convert(*value))
+            .collect::<eyre::Result<Vec<AstarteType>>>()?;
+
+        let array = match self.output_type {
+            OutputType::Double => AstarteType::DoubleArray(array_items(items)?),
+            OutputType::Integer => AstarteType::IntegerArray(array_items(items)?),
+            OutputType::Boolean => AstarteType::BooleanArray(array_items(items)?),
+            OutputType::String => AstarteType::StringArray(array_items(items)?),
+            OutputType::LongInteger => AstarteType::LongIntegerArray(array_items(items)?),
+            OutputType::BinaryBlob => AstarteType::BinaryBlobArray(array_items(items)?),
+            OutputType::DateTime => AstarteType::DateTimeArray(array_items(items)?),
+        };
+
+        Ok(array)
+    }
+}
+
+/// Unwrap the items of an array, all converted into the same type
+fn array_items<T>(items: Vec<AstarteType>) -> eyre::Result<Vec<T>>
+where
+    T: TryFrom<AstarteType>,
+    T::Error: std::error::Error + Send + Sync + 'static,
+{
+    items
+        .into_iter()
+        .map(T::try_from)
+        .collect::<Result<Vec<T>, T::Error>>()
+        .wrap_err("mismatching array item type")
+}
+
+impl Display for OutputType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            OutputType::Double => "double",
+            OutputType::Integer => "integer",
+            OutputType::Boolean => "boolean",
+            OutputType::String => "string",
+            OutputType::LongInteger => "longinteger",
+            OutputType::BinaryBlob => "binaryblob",
+            OutputType::DateTime => "datetime",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+impl OutputType {
+    /// Name of the bundled interface with a mapping of this type
+    pub fn default_interface(&self) -> &'static str {
+        match self {
+            OutputType::Double => "org.astarte-platform.genericsensors.Values",
+            OutputType::Integer => "org.astarte-platform.stream-rust-test.IntegerValues",
+            OutputType::Boolean => "org.astarte-platform.stream-rust-test.BooleanValues",
+            OutputType::String => "org.astarte-platform.stream-rust-test.StringValues",
+            OutputType::LongInteger => "org.astarte-platform.stream-rust-test.LongIntegerValues",
+            OutputType::BinaryBlob => "org.astarte-platform.stream-rust-test.BinaryBlobValues",
+            OutputType::DateTime => "org.astarte-platform.stream-rust-test.DateTimeValues",
+        }
+    }
+
+    /// Name of the bundled interface with an array mapping of this type
+    pub fn default_array_interface(&self) -> &'static str {
+        match self {
+            OutputType::Double => "org.astarte-platform.stream-rust-test.DoubleArrayValues",
+            OutputType::Integer => "org.astarte-platform.stream-rust-test.IntegerArrayValues",
+            OutputType::Boolean => "org.astarte-platform.stream-rust-test.BooleanArrayValues",
+            OutputType::String => "org.astarte-platform.stream-rust-test.StringArrayValues",
+            OutputType::LongInteger => {
+                "org.astarte-platform.stream-rust-test.LongIntegerArrayValues"
+            }
+            OutputType::BinaryBlob => "org.astarte-platform.stream-rust-test.BinaryBlobArrayValues",
+            OutputType::DateTime => "org.astarte-platform.stream-rust-test.DateTimeArrayValues",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn output(output_type: OutputType) -> OutputConfig {
+        OutputConfig {
+            output_type,
+            boolean_threshold: 0.0,
+            string_format: StringFormat::Template,
+            string_template: "value={value}".to_string(),
+            string_file: None,
+            lines: Vec::new(),
+            longinteger_boundaries: false,
+            blob_size: 16,
+            blob_content: BlobContent::Random,
+            datetime_source: DateTimeSource::Now,
+            datetime_offset: 0,
+            datetime_start: None,
+            datetime_step: 1,
+            array_length: None,
+            aggregate: None,
+            count: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_blob_size() {
+        assert_eq!(parse_blob_size("1024").unwrap(), 1024);
+        assert_eq!(parse_blob_size("1048576").unwrap(), MAX_BLOB_SIZE);
+        assert!(parse_blob_size("1048577").is_err());
+        assert!(parse_blob_size("-1").is_err());
+    }
+
+    #[test]
+    fn test_output_convert_integer() {
+        let int = |value| output(OutputType::Integer).convert(value).unwrap();
+
+        assert_eq!(int(0.4), AstarteType::Integer(0));
+        assert_eq!(int(0.5), AstarteType::Integer(1));
+        assert_eq!(int(-1.5), AstarteType::Integer(-2));
+        assert_eq!(int(1e12), AstarteType::Integer(i32::MAX));
+        assert_eq!(int(-1e12), AstarteType::Integer(i32::MIN));
+    }
+
+    #[test]
+    fn test_output_convert_boolean() {
+        let mut cfg = output(OutputType::Boolean);
+
+        assert_eq!(cfg.convert(0.1).unwrap(), AstarteType::Boolean(true));
+        assert_eq!(cfg.convert(-0.1).unwrap(), AstarteType::Boolean(false));
+
+        cfg.boolean_threshold = 0.5;
+        assert_eq!(cfg.convert(0.4).unwrap(), AstarteType::Boolean(false));
+        assert_eq!(cfg.convert(0.6).unwrap(), AstarteType::Boolean(true));
+    }
+
+    #[test]
+    fn test_output_convert_string() {
+        let mut cfg = output(OutputType::String);
+        cfg.string_template = "{count}: value={value}".to_string();
+
+        assert_eq!(
+            cfg.convert(1.5).unwrap(),
+            AstarteType::String("0: value=1.5".to_string())
+        );
+        assert_eq!(
+            cfg.convert(-2.0).unwrap(),
+            AstarteType::String("1: value=-2".to_string())
+        );
+
+        cfg.string_format = StringFormat::File;
+        assert!(cfg.convert(0.0).is_err());
+
+        cfg.lines = vec!["first".to_string(), "second".to_string()];
+        cfg.count = 0;
+
+        let lines: Vec<AstarteType> = (0..3).map(|_| cfg.convert(0.0).unwrap()).collect();
+        assert_eq!(
+            lines,
+            ["first", "second", "first"].map(|s| AstarteType::String(s.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_output_convert_longinteger() {
+        let mut cfg = output(OutputType::LongInteger);
+
+        assert_eq!(
+            cfg.convert(2f64.powi(53)).unwrap(),
+            AstarteType::LongInteger(1 << 53)
+        );
+        assert_eq!(
+            cfg.convert(1e30).unwrap(),
+            AstarteType::LongInteger(i64::MAX)
+        );
+
+        cfg.longinteger_boundaries = true;
+
+        let values: Vec<AstarteType> = (0..=LONGINTEGER_BOUNDARIES.len())
+            .map(|_| cfg.convert(0.0).unwrap())
+            .collect();
+
+        assert_eq!(values.first(), Some(&AstarteType::LongInteger(i64::MIN)));
+        assert_eq!(
+            values.get(LONGINTEGER_BOUNDARIES.len() - 1),
+            Some(&AstarteType::LongInteger(i64::MAX))
+        );
+        assert_eq!(values.last(), Some(&AstarteType::LongInteger(i64::MIN)));
+    }
+
+    #[test]
+    fn test_output_convert_binaryblob() {
+        let mut cfg = output(OutputType::BinaryBlob);
+        cfg.blob_size = 300;
+
+        let AstarteType::BinaryBlob(blob) = cfg.convert(0.0).unwrap() else {
+            panic!("expected a binary blob");
+        };
+        assert_eq!(blob.len(), 300);
+
+        cfg.blob_content = BlobContent::Pattern;
+        let AstarteType::BinaryBlob(blob) = cfg.convert(0.0).unwrap() else {
+            panic!("expected a binary blob");
+        };
+        assert_eq!(blob[..3], [0, 1, 2]);
+        assert_eq!(blob[255..258], [255, 0, 1]);
+    }
+
+    #[test]
+    fn test_output_convert_datetime() {
+        let mut cfg = output(OutputType::DateTime);
+
+        let datetime = |cfg: &mut OutputConfig| {
+            let AstarteType::DateTime(datetime) = cfg.convert(0.0).unwrap() else {
+                panic!("expected a datetime");
+            };
+
+            datetime
+        };
+
+        let before = Utc::now();
+        let now = datetime(&mut cfg);
+        assert!(now >= before && now <= Utc::now());
+
+        cfg.datetime_source = DateTimeSource::Offset;
+        cfg.datetime_offset = -3600;
+        assert!(datetime(&mut cfg) < before);
+
+        cfg.datetime_offset = i64::MAX;
+        assert!(cfg.convert(0.0).is_err());
+
+        cfg.datetime_source = DateTimeSource::Synthetic;
+        cfg.datetime_start = Some("2024-01-01T00:00:00Z".parse().unwrap());
+        cfg.datetime_step = 60;
+
+        let datetimes: Vec<String> = (0..3).map(|_| datetime(&mut cfg).to_rfc3339()).collect();
+        assert_eq!(
+            datetimes,
+            [
+                "2024-01-01T00:00:00+00:00",
+                "2024-01-01T00:01:00+00:00",
+                "2024-01-01T00:02:00+00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_convert_array() {
+        let mut cfg = output(OutputType::Integer);
+
+        assert_eq!(
+            cfg.convert_array(&[0.4, 1.6, -1e12]).unwrap(),
+            AstarteType::IntegerArray(vec![0, 2, i32::MIN])
+        );
+
+        cfg.output_type = OutputType::Double;
+        assert_eq!(
+            cfg.convert_array(&[]).unwrap(),
+            AstarteType::DoubleArray(Vec::new())
+        );
+        assert!(cfg.convert_array(&[1.0, f64::NAN]).is_err());
+
+        cfg.output_type = OutputType::String;
+        assert_eq!(
+            cfg.convert_array(&[1.0, 2.0]).unwrap(),
+            AstarteType::StringArray(vec!["value=1".to_string(), "value=2".to_string()])
+        );
+    }
+}
@@ -0,0 +1,126 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parser of the endpoints of the interfaces updating the stream configuration.
+
+use std::fmt::Display;
+
+/// Endpoint of the form `/<sensor_id>/<parameter>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointPath<'a> {
+    /// Id of the sensor the endpoint refers to
+    pub sensor_id: &'a str,
+    /// Name of the parameter
+    pub parameter: &'a str,
+}
+
+/// Error returned when parsing an invalid endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathError {
+    /// The path doesn't start with a `/`
+    MissingLeadingSlash,
+    /// The sensor id is missing or empty
+    MissingSensorId,
+    /// The parameter is missing or empty
+    MissingParameter,
+    /// The path has more than two components
+    TooDeep,
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::MissingLeadingSlash => write!(f, "the path must start with a '/'"),
+            PathError::MissingSensorId => write!(f, "missing sensor id"),
+            PathError::MissingParameter => write!(f, "missing parameter"),
+            PathError::TooDeep => {
+                write!(f, "the path must be of the form /<sensor_id>/<parameter>")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+impl<'a> EndpointPath<'a> {
+    /// Parse an endpoint, rejecting empty components and any trailing component
+    pub fn parse(path: &'a str) -> Result<Self, PathError> {
+        let path = path
+            .strip_prefix('/')
+            .ok_or(PathError::MissingLeadingSlash)?;
+
+        let mut iter = path.split('/');
+
+        let sensor_id = iter
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or(PathError::MissingSensorId)?;
+        let parameter = iter
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or(PathError::MissingParameter)?;
+
+        if iter.next().is_some() {
+            return Err(PathError::TooDeep);
+        }
+
+        Ok(Self {
+            sensor_id,
+            parameter,
+        })
+    }
+}
+
+impl Display for EndpointPath<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "/{}/{}", self.sensor_id, self.parameter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_path_parse() {
+        assert_eq!(
+            EndpointPath::parse("/test/interval_btw_samples"),
+            Ok(EndpointPath {
+                sensor_id: "test",
+                parameter: "interval_btw_samples"
+            })
+        );
+
+        let invalid = [
+            ("", PathError::MissingLeadingSlash),
+            ("test/toggle", PathError::MissingLeadingSlash),
+            ("/", PathError::MissingSensorId),
+            ("//toggle", PathError::MissingSensorId),
+            ("/test", PathError::MissingParameter),
+            ("/test/", PathError::MissingParameter),
+            ("/test//", PathError::MissingParameter),
+            ("/test/toggle/", PathError::TooDeep),
+            ("/test/toggle/error", PathError::TooDeep),
+            ("/a/b/c/d/e/f/g/h", PathError::TooDeep),
+        ];
+
+        for (path, err) in invalid {
+            assert_eq!(EndpointPath::parse(path), Err(err), "path {path:?}");
+        }
+    }
+
+    #[test]
+    fn test_endpoint_path_round_trip() {
+        for path in [
+            "/test/toggle",
+            "/sensor-1/scale",
+            "/%{sensor_id}/ping",
+            "/ü/ß",
+        ] {
+            assert_eq!(EndpointPath::parse(path).unwrap().to_string(), path);
+        }
+    }
+}
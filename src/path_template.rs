@@ -0,0 +1,117 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Template of the endpoint the stream data is sent to.
+//!
+//! The template is a path containing the `{sensor_id}` and `{mapping}` placeholders, for instance
+//! `/{sensor_id}/{mapping}` (the default) or `/devices/{sensor_id}/readings/{mapping}`.
+
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, eyre};
+use std::fmt::Display;
+
+/// Placeholder replaced by the id of the sensor
+const SENSOR_ID: &str = "{sensor_id}";
+/// Placeholder replaced by the name of the mapping
+const MAPPING: &str = "{mapping}";
+
+/// Default template, sending to `/<sensor_id>/value`
+pub const DEFAULT_PATH_TEMPLATE: &str = "/{sensor_id}/{mapping}";
+
+/// Template of the endpoint the data is sent to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathTemplate(String);
+
+impl Default for PathTemplate {
+    fn default() -> Self {
+        Self(DEFAULT_PATH_TEMPLATE.to_string())
+    }
+}
+
+impl PathTemplate {
+    /// Parse a template, rejecting empty levels and unknown placeholders
+    pub fn parse(template: &str) -> eyre::Result<Self> {
+        let levels = template
+            .strip_prefix('/')
+            .ok_or_else(|| eyre!("the path template must start with a '/'"))?;
+
+        for level in levels.split('/') {
+            ensure!(!level.is_empty(), "empty level in path template {template}");
+
+            let rest = level.replace(SENSOR_ID, "").replace(MAPPING, "");
+
+            ensure!(
+                !rest.contains(['{', '}']),
+                "unknown placeholder in path template level {level}, expected {SENSOR_ID} or {MAPPING}"
+            );
+        }
+
+        Ok(Self(template.to_string()))
+    }
+
+    /// Path of an individual mapping
+    pub fn render(&self, sensor_id: &str, mapping: &str) -> String {
+        self.0
+            .replace(SENSOR_ID, sensor_id)
+            .replace(MAPPING, mapping)
+    }
+
+    /// Path of an object, dropping the `{mapping}` level since the fields are appended to it
+    pub fn render_object(&self, sensor_id: &str) -> String {
+        self.0
+            .split('/')
+            .filter(|level| *level != MAPPING)
+            .collect::<Vec<_>>()
+            .join("/")
+            .replace(SENSOR_ID, sensor_id)
+    }
+}
+
+impl Display for PathTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_path_template_parse() {
+        for template in [
+            DEFAULT_PATH_TEMPLATE,
+            "/{sensor_id}",
+            "/devices/{sensor_id}/readings/{mapping}",
+            "/sensor-{sensor_id}/{mapping}",
+        ] {
+            assert!(PathTemplate::parse(template).is_ok(), "{template}");
+        }
+
+        for template in [
+            "",
+            "{sensor_id}/{mapping}",
+            "/",
+            "/{sensor_id}//{mapping}",
+            "/{sensor_id}/{mapping}/",
+            "/{sensor}/{mapping}",
+            "/{sensor_id/value",
+        ] {
+            assert!(PathTemplate::parse(template).is_err(), "{template}");
+        }
+    }
+
+    #[test]
+    fn test_path_template_render() {
+        let template = PathTemplate::default();
+        assert_eq!(template.render("test", "value"), "/test/value");
+        assert_eq!(template.render_object("test"), "/test");
+
+        let template = PathTemplate::parse("/devices/sensor-{sensor_id}/{mapping}/raw").unwrap();
+        assert_eq!(template.render("1", "value"), "/devices/sensor-1/value/raw");
+        assert_eq!(template.render_object("1"), "/devices/sensor-1/raw");
+    }
+}
@@ -0,0 +1,531 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pre-flight validation of the configuration and of the interfaces against the configured streams.
+//!
+//! The bundled interfaces and the ones in the interfaces directory are parsed, and the endpoints
+//! each stream sends data to are checked to exist and to accept the configured output type, so
+//! that mismatches are reported before connecting to Astarte.
+
+use crate::astarte::{AstarteConnection, ConnectionConfigBuilder, BUNDLED_INTERFACES};
+use crate::cli::Config;
+use crate::config_file::{ConfigFile, ConfigLocation};
+use crate::fleet::{check_device_id, fleet, read_devices};
+use crate::math::MathFunction;
+use crate::output::OutputConfig;
+use crate::type_fuzz::edge_cases;
+use astarte_device_sdk::interface::def::{Aggregation, Ownership};
+use astarte_device_sdk::{AstarteType, Interface};
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Data a stream sends on an endpoint
+#[derive(Debug, Clone)]
+struct Target {
+    /// Stream sending the data
+    stream: String,
+    interface: String,
+    path: String,
+    /// Whether the data is sent as an object
+    object: bool,
+    /// Whether the data is sent with an explicit timestamp
+    timestamp: bool,
+    /// Sample of the sent data
+    sample: AstarteType,
+}
+
+/// Check if a mapping endpoint, possibly containing parameters, matches a path
+fn endpoint_matches(endpoint: &str, path: &str) -> bool {
+    let endpoint: Vec<&str> = endpoint.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+
+    endpoint.len() == path.len()
+        && endpoint.iter().zip(path).all(|(level, segment)| {
+            let parameter = level.starts_with("%{") && level.ends_with('}');
+
+            (parameter && !segment.is_empty()) || *level == segment
+        })
+}
+
+/// Parse the bundled interfaces and the ones in the directory
+///
+/// A custom interface replaces a bundled one only if it has a newer version. The files that
+/// cannot be used are reported as issues.
+async fn load_interfaces(
+    dir: Option<&Path>,
+    issues: &mut Vec<String>,
+) -> eyre::Result<HashMap<String, Interface>> {
+    let mut interfaces = HashMap::new();
+
+    for bundled in BUNDLED_INTERFACES {
+        let interface = Interface::from_str(bundled).wrap_err("invalid bundled interface")?;
+
+        interfaces.insert(interface.interface_name().to_string(), interface);
+    }
+
+    let Some(dir) = dir else {
+        return Ok(interfaces);
+    };
+
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .wrap_err_with(|| format!("couldn't read {}", dir.display()))?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .wrap_err_with(|| format!("couldn't read {}", path.display()))?;
+
+        let interface = match Interface::from_str(&content) {
+            Ok(interface) => interface,
+            Err(err) => {
+                issues.push(format!("{}: invalid interface, {err}", path.display()));
+                continue;
+            }
+        };
+
+        let version = |i: &Interface| (i.version_major(), i.version_minor());
+
+        match interfaces.get(interface.interface_name()) {
+            Some(prev) if version(prev) > version(&interface) => {
+                issues.push(format!(
+                    "{}: interface {} is older than the bundled one",
+                    path.display(),
+                    interface.interface_name()
+                ));
+            }
+            _ => {
+                interfaces.insert(interface.interface_name().to_string(), interface);
+            }
+        }
+    }
+
+    Ok(interfaces)
+}
+
+/// Endpoints the configured streams send data to
+fn targets(cfg: &Config, output: &mut OutputConfig) -> eyre::Result<Vec<Target>> {
+    let mut targets = Vec::new();
+
+    let timestamp = cfg.stream.timestamp.timestamp_source.is_some();
+
+    let interface = cfg
+        .stream
+        .interface_datastream_do
+        .clone()
+        .unwrap_or_else(|| output.default_interface().to_string());
+
+    // the interfaces don't depend on the id of the sensor
+    let sensor_id = &cfg.stream.sensor_id().render(0, "");
+
+    if cfg.stream.fuzz_types {
+        for case in edge_cases() {
+            targets.push(Target {
+                stream: format!("main stream ({} edge case)", case.description),
+                interface: case.interface.to_string(),
+                path: cfg.stream.path_template.render(sensor_id, "value"),
+                object: false,
+                timestamp: false,
+                sample: case.value,
+            });
+        }
+    } else if let Some(aggregate) = output.aggregate {
+        for (field, sample) in aggregate.fields(0.0)? {
+            targets.push(Target {
+                stream: format!("main stream ({aggregate} aggregate)"),
+                interface: interface.clone(),
+                path: format!(
+                    "{}/{field}",
+                    cfg.stream.path_template.render_object(sensor_id)
+                ),
+                object: true,
+                timestamp,
+                sample,
+            });
+        }
+    } else {
+        let (stream, sample) = match output.array_length {
+            Some(len) => (
+                format!("main stream ({} array)", output.output_type),
+                output.convert_array(&vec![0.0; len])?,
+            ),
+            None => (
+                format!("main stream ({})", output.output_type),
+                output.convert(0.0)?,
+            ),
+        };
+
+        for mapping in &cfg.stream.mappings {
+            targets.push(Target {
+                stream: stream.clone(),
+                interface: interface.clone(),
+                path: cfg.stream.path_template.render(sensor_id, mapping),
+                object: false,
+                timestamp,
+                sample: sample.clone(),
+            });
+        }
+    }
+
+    for extra in &cfg.stream.extra_streams {
+        let mut output = output.clone();
+        output.output_type = extra.output_type;
+        output.array_length = None;
+        output.aggregate = None;
+
+        targets.push(Target {
+            stream: format!("additional stream ({})", extra.output_type),
+            interface: extra.interface.clone(),
+            path: extra.path.clone(),
+            object: false,
+            timestamp: false,
+            sample: output.convert(0.0)?,
+        });
+    }
+
+    Ok(targets)
+}
+
+/// Check that an interface accepts the data of a stream
+fn check(interfaces: &HashMap<String, Interface>, target: &Target) -> Option<String> {
+    let Target {
+        stream,
+        interface: name,
+        path,
+        object,
+        timestamp,
+        sample,
+    } = target;
+
+    let Some(interface) = interfaces.get(name) else {
+        return Some(format!("{stream}: interface {name} not found"));
+    };
+
+    if interface.ownership() != Ownership::Device {
+        return Some(format!("{stream}: interface {name} is not device owned"));
+    }
+
+    let aggregation = if *object {
+        Aggregation::Object
+    } else {
+        Aggregation::Individual
+    };
+
+    if interface.aggregation() != aggregation {
+        return Some(format!(
+            "{stream}: interface {name} has {:?} aggregation, expected {aggregation:?}",
+            interface.aggregation()
+        ));
+    }
+
+    let Some(mapping) = interface
+        .iter_mappings()
+        .find(|mapping| endpoint_matches(mapping.endpoint(), path))
+    else {
+        return Some(format!("{stream}: no mapping of {name} matches {path}"));
+    };
+
+    if *sample != mapping.mapping_type() {
+        return Some(format!(
+            "{stream}: mapping {} of {name} has type {}",
+            mapping.endpoint(),
+            mapping.mapping_type()
+        ));
+    }
+
+    if *timestamp && !mapping.explicit_timestamp() {
+        return Some(format!(
+            "{stream}: mapping {} of {name} doesn't accept explicit timestamps",
+            mapping.endpoint()
+        ));
+    }
+
+    None
+}
+
+/// Validate the interfaces against the configured streams, returning the mismatches found
+pub async fn validate_interfaces(cfg: &Config) -> eyre::Result<Vec<String>> {
+    let mut issues = Vec::new();
+
+    if cfg.stream.exec_command.is_none()
+        && cfg
+            .stream
+            .math_functions()
+            .any(|function| *function == MathFunction::Exec)
+    {
+        issues.push("the exec math function requires an exec command".to_string());
+    }
+
+    let interfaces = load_interfaces(cfg.stream.interfaces_dir.as_deref(), &mut issues).await?;
+
+    let mut output = cfg.stream.output.clone();
+    output.load_string_file().await?;
+
+    issues.extend(
+        targets(cfg, &mut output)?
+            .iter()
+            .filter_map(|target| check(&interfaces, target)),
+    );
+
+    Ok(issues)
+}
+
+/// Check the ids of the devices connecting through MQTT, the configured one or the ones of the fleet
+///
+/// A random device id is generated when registering the device, so it isn't checked.
+async fn validate_device_ids(
+    cfg: &Config,
+    astarte_cfg_builder: &ConnectionConfigBuilder,
+) -> eyre::Result<Vec<String>> {
+    if astarte_cfg_builder.connection() != Some(AstarteConnection::Mqtt)
+        || cfg.connection.random_device_id
+    {
+        return Ok(Vec::new());
+    }
+
+    let device_ids = match &cfg.connection.device_ids {
+        Some(path) => Some(read_devices(path).await?),
+        None => None,
+    };
+
+    let devices = fleet(
+        cfg.connection.devices.map(|devices| devices as usize),
+        cfg.connection.device_id_template.as_ref(),
+        device_ids,
+        &astarte_cfg_builder.device_id().unwrap_or_default(),
+    )?;
+
+    let issues = devices
+        .iter()
+        .filter_map(|device| check_device_id(&device.device_id).err())
+        .map(|err| format!("{err:#}"))
+        .collect();
+
+    Ok(issues)
+}
+
+/// Validate the whole configuration, merging the environment variables, the `config.toml` file and
+/// the CLI options, without connecting to Astarte
+///
+/// Every issue found is reported, instead of stopping at the first one.
+pub async fn validate_config(cfg: &Config, location: &ConfigLocation) -> Vec<String> {
+    let mut issues = Vec::new();
+    let mut cfg = cfg.clone();
+
+    let merged = match ConfigFile::load(location).await {
+        Ok(config_file) => config_file
+            .merge_into(&mut cfg)
+            .wrap_err_with(|| format!("invalid stream options in {location}")),
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = merged {
+        issues.push(format!("{err:#}"));
+    }
+
+    match ConnectionConfigBuilder::load(location).await {
+        Ok(mut astarte_cfg_builder) => {
+            astarte_cfg_builder.cli_overrides(&cfg);
+
+            issues.extend(astarte_cfg_builder.validate().await);
+
+            match validate_device_ids(&cfg, &astarte_cfg_builder).await {
+                Ok(id_issues) => issues.extend(id_issues),
+                Err(err) => issues.push(format!("{err:#}")),
+            }
+        }
+        Err(err) => issues.push(format!("{err:#}")),
+    }
+
+    match validate_interfaces(&cfg).await {
+        Ok(interface_issues) => issues.extend(interface_issues),
+        Err(err) => issues.push(format!("{err:#}")),
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_endpoint_matches() {
+        assert!(endpoint_matches("/%{sensor_id}/value", "/test/value"));
+        assert!(endpoint_matches("/sensor/value", "/sensor/value"));
+        assert!(!endpoint_matches("/%{sensor_id}/value", "/test/other"));
+        assert!(!endpoint_matches("/%{sensor_id}/value", "//value"));
+        assert!(!endpoint_matches("/%{sensor_id}/value", "/test/value/more"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_interfaces() {
+        let cfg = Config::parse_from(["stream-rust-test"]);
+        assert!(validate_interfaces(&cfg).await.unwrap().is_empty());
+
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--output-type",
+            "boolean",
+            "--interface-datastream-do",
+            "org.astarte-platform.genericsensors.Values",
+        ]);
+        let issues = validate_interfaces(&cfg).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("has type double"), "{issues:?}");
+
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--aggregate",
+            "environment",
+            "--interface-datastream-do",
+            "org.astarte-platform.stream-rust-test.AccelerometerValues",
+        ]);
+        let issues = validate_interfaces(&cfg).await.unwrap();
+        assert!(!issues.is_empty());
+
+        let cfg = Config::parse_from(["stream-rust-test", "--timestamp-source", "now"]);
+        let issues = validate_interfaces(&cfg).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("explicit timestamps"), "{issues:?}");
+
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--timestamp-source",
+            "synthetic",
+            "--interface-datastream-do",
+            "org.astarte-platform.stream-rust-test.TimestampedValues",
+        ]);
+        assert!(validate_interfaces(&cfg).await.unwrap().is_empty());
+
+        let cfg = Config::parse_from(["stream-rust-test", "--path-template", "/{sensor_id}/raw"]);
+        let issues = validate_interfaces(&cfg).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("matches /test/raw"), "{issues:?}");
+
+        let cfg = Config::parse_from(["stream-rust-test", "--fuzz-types"]);
+        assert!(validate_interfaces(&cfg).await.unwrap().is_empty());
+
+        let cfg = Config::parse_from(["stream-rust-test", "--mappings", "value,raw"]);
+        let issues = validate_interfaces(&cfg).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("matches /test/raw"), "{issues:?}");
+
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--stream",
+            "interface=org.astarte-platform.stream-rust-test.Commands",
+        ]);
+        let issues = validate_interfaces(&cfg).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("not device owned"), "{issues:?}");
+    }
+
+    #[tokio::test]
+    async fn test_validate_config() {
+        let dir = std::env::temp_dir().join(format!("validate-config-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let config_path = dir.join("config.toml");
+        let cfg = Config::parse_from(["stream-rust-test"]);
+
+        let config = |store_directory: &Path, device_id: &str, pairing_url: &str| {
+            format!(
+                r#"
+                [astarte]
+                connection = "mqtt"
+                store_directory = "{}"
+
+                [astarte.mqtt]
+                realm = "realm"
+                device_id = "{device_id}"
+                pairing_url = "{pairing_url}"
+                credentials_secret = "secret"
+                "#,
+                store_directory.display()
+            )
+        };
+        let device_id = "2TBn-jNESuuHamE2Zo1anA";
+
+        tokio::fs::write(
+            &config_path,
+            config(&dir, device_id, "http://localhost/pairing"),
+        )
+        .await
+        .unwrap();
+        let issues = validate_config(&cfg, &ConfigLocation::new(&config_path)).await;
+        assert!(issues.is_empty(), "{issues:?}");
+
+        tokio::fs::write(
+            &config_path,
+            config(&dir.join("missing"), device_id, "localhost/pairing")
+                + "\n[intervals]\nboiler = 0\n",
+        )
+        .await
+        .unwrap();
+        let issues = validate_config(&cfg, &ConfigLocation::new(&config_path)).await;
+        assert_eq!(issues.len(), 3, "{issues:?}");
+        assert!(issues[0].contains("must be positive"), "{issues:?}");
+        assert!(issues[1].contains("invalid store directory"), "{issues:?}");
+        assert!(issues[2].contains("invalid pairing URL"), "{issues:?}");
+
+        tokio::fs::write(
+            &config_path,
+            config(&dir, "device", "http://localhost/pairing"),
+        )
+        .await
+        .unwrap();
+        let issues = validate_config(&cfg, &ConfigLocation::new(&config_path)).await;
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(issues[0].contains("device id device"), "{issues:?}");
+
+        // the devices of the fleet derive valid ids from the configured one
+        let fleet_cfg = Config::parse_from(["stream-rust-test", "--devices", "2"]);
+        tokio::fs::write(
+            &config_path,
+            config(&dir, device_id, "http://localhost/pairing"),
+        )
+        .await
+        .unwrap();
+        let issues = validate_config(&fleet_cfg, &ConfigLocation::new(&config_path)).await;
+        assert!(issues.is_empty(), "{issues:?}");
+
+        tokio::fs::write(
+            &config_path,
+            format!(
+                r#"
+                [astarte]
+                connection = "grpc"
+                store_directory = "{}"
+
+                [astarte.grpc]
+                node_id = "00000000-0000-0000-0000-000000000000"
+                endpoint = "http://localhost:50051"
+                "#,
+                dir.display()
+            ),
+        )
+        .await
+        .unwrap();
+        let issues = validate_config(&cfg, &ConfigLocation::new(&config_path)).await;
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(
+            issues[0].contains("invalid Message Hub node id"),
+            "{issues:?}"
+        );
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Calibrated noise added to the sent values, to generate differentially private streams.
+//!
+//! The noise is calibrated on the privacy budget `epsilon` and on the sensitivity of the values,
+//! that is the maximum change of a value caused by a single individual:
+//! - the Laplace mechanism provides epsilon-differential privacy with scale `sensitivity / epsilon`;
+//! - the Gaussian mechanism provides (epsilon, delta)-differential privacy, for epsilon lower than
+//!   1, with standard deviation `sensitivity * sqrt(2 ln(1.25 / delta)) / epsilon`.
+
+use crate::math::standard_normal;
+use color_eyre::eyre;
+use color_eyre::eyre::ensure;
+use rand::distributions::Open01;
+use rand::Rng;
+use std::fmt::Display;
+
+/// Mechanism generating the noise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum NoiseMechanism {
+    /// Laplace noise, for epsilon-differential privacy
+    Laplace,
+    /// Gaussian noise, for (epsilon, delta)-differential privacy
+    Gaussian,
+}
+
+impl Display for NoiseMechanism {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoiseMechanism::Laplace => write!(f, "laplace"),
+            NoiseMechanism::Gaussian => write!(f, "gaussian"),
+        }
+    }
+}
+
+/// Options of the noise added to the sent values
+#[derive(Debug, Clone, clap::Args)]
+pub struct PrivacyConfig {
+    /// Mechanism of the calibrated noise added to the sent values
+    ///
+    /// If not set, no noise is added.
+    #[clap(long, env = "PRIVACY_NOISE")]
+    pub privacy_noise: Option<NoiseMechanism>,
+    /// Privacy budget, lower values add more noise
+    #[clap(long, default_value = "1.0", env = "PRIVACY_EPSILON")]
+    pub privacy_epsilon: f64,
+    /// Maximum change of a value caused by a single individual
+    #[clap(long, default_value = "1.0", env = "PRIVACY_SENSITIVITY")]
+    pub privacy_sensitivity: f64,
+    /// Probability of exceeding the privacy budget with the Gaussian mechanism
+    #[clap(long, default_value = "0.00001", env = "PRIVACY_DELTA")]
+    pub privacy_delta: f64,
+}
+
+/// Calibrated noise generator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrivacyNoise {
+    mechanism: NoiseMechanism,
+    /// Scale of the Laplace noise or standard deviation of the Gaussian noise
+    scale: f64,
+}
+
+impl PrivacyNoise {
+    /// Calibrate the noise, returning [`None`] if no mechanism is configured
+    pub fn new(cfg: &PrivacyConfig) -> eyre::Result<Option<Self>> {
+        let Some(mechanism) = cfg.privacy_noise else {
+            return Ok(None);
+        };
+
+        let epsilon = cfg.privacy_epsilon;
+        let sensitivity = cfg.privacy_sensitivity;
+
+        ensure!(
+            epsilon.is_finite() && epsilon > 0.0,
+            "privacy epsilon must be positive, received {epsilon}"
+        );
+        ensure!(
+            sensitivity.is_finite() && sensitivity >= 0.0,
+            "privacy sensitivity must not be negative, received {sensitivity}"
+        );
+
+        let scale = match mechanism {
+            NoiseMechanism::Laplace => sensitivity / epsilon,
+            NoiseMechanism::Gaussian => {
+                let delta = cfg.privacy_delta;
+
+                ensure!(
+                    delta > 0.0 && delta < 1.0,
+                    "privacy delta must be between 0 and 1, received {delta}"
+                );
+
+                sensitivity * (2.0 * (1.25 / delta).ln()).sqrt() / epsilon
+            }
+        };
+
+        Ok(Some(Self { mechanism, scale }))
+    }
+
+    /// Scale of the Laplace noise or standard deviation of the Gaussian noise
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Add the noise to a value
+    pub fn apply(&self, value: f64) -> f64 {
+        value + self.sample()
+    }
+
+    fn sample(&self) -> f64 {
+        match self.mechanism {
+            NoiseMechanism::Laplace => laplace(self.scale, rand::thread_rng().sample(Open01)),
+            NoiseMechanism::Gaussian => self.scale * standard_normal(),
+        }
+    }
+}
+
+/// Sample of the Laplace distribution with the given scale, from a uniform sample in (0, 1)
+fn laplace(scale: f64, uniform: f64) -> f64 {
+    // inverse of the cumulative distribution, with u in (-0.5, 0.5) so that ln(0) is never taken
+    let u = 0.5 - uniform;
+
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(mechanism: NoiseMechanism, epsilon: f64) -> PrivacyConfig {
+        PrivacyConfig {
+            privacy_noise: Some(mechanism),
+            privacy_epsilon: epsilon,
+            privacy_sensitivity: 1.0,
+            privacy_delta: 1e-5,
+        }
+    }
+
+    #[test]
+    fn test_privacy_noise_calibration() {
+        const SAMPLES: usize = 100_000;
+
+        let laplace = PrivacyNoise::new(&config(NoiseMechanism::Laplace, 0.5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(laplace.scale(), 2.0);
+
+        // the mean absolute deviation of the Laplace distribution is its scale
+        let mad = (0..SAMPLES).map(|_| laplace.apply(0.0).abs()).sum::<f64>() / SAMPLES as f64;
+        assert!((mad - 2.0).abs() < 0.1, "mean absolute deviation {mad}");
+
+        let gaussian = PrivacyNoise::new(&config(NoiseMechanism::Gaussian, 0.5))
+            .unwrap()
+            .unwrap();
+
+        let variance = (0..SAMPLES)
+            .map(|_| gaussian.apply(0.0).powi(2))
+            .sum::<f64>()
+            / SAMPLES as f64;
+        let std_dev = variance.sqrt();
+        assert!(
+            (std_dev - gaussian.scale()).abs() < gaussian.scale() * 0.05,
+            "standard deviation {std_dev}"
+        );
+
+        assert!(PrivacyNoise::new(&config(NoiseMechanism::Laplace, 0.0)).is_err());
+    }
+
+    #[test]
+    fn test_laplace_bounds() {
+        // the extremes of the open interval sampled by Open01
+        let min = f64::EPSILON / 2.0;
+
+        assert!(laplace(1.0, min).is_finite());
+        assert!(laplace(1.0, 1.0 - min).is_finite());
+        assert!(laplace(1.0, min) > 0.0);
+        assert!(laplace(1.0, 1.0 - min) < 0.0);
+        assert_eq!(laplace(1.0, 0.5), 0.0);
+    }
+}
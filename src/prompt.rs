@@ -0,0 +1,64 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Interactive prompt of the connection settings missing from the configuration, when started from
+//! a terminal.
+
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, WrapErr};
+use std::io::{BufRead, IsTerminal, Write};
+
+/// Source of the answers to the questions about the missing settings
+pub trait Prompt {
+    /// Ask a value, showing the input
+    fn ask(&mut self, question: &str) -> eyre::Result<String>;
+
+    /// Ask a secret value, hiding the input
+    fn ask_secret(&mut self, question: &str) -> eyre::Result<String>;
+
+    /// Ask a value that can't be empty
+    fn ask_required(&mut self, question: &str) -> eyre::Result<String> {
+        let answer = self.ask(question)?;
+
+        ensure!(!answer.is_empty(), "the {question} is required");
+
+        Ok(answer)
+    }
+}
+
+/// Prompt on the terminal the application was started from
+#[derive(Debug)]
+pub struct TerminalPrompt(());
+
+impl TerminalPrompt {
+    /// Prompt on the terminal, none if the standard input isn't a terminal, e.g. in a container
+    pub fn new() -> Option<Self> {
+        std::io::stdin().is_terminal().then_some(Self(()))
+    }
+}
+
+impl Prompt for TerminalPrompt {
+    fn ask(&mut self, question: &str) -> eyre::Result<String> {
+        let mut stderr = std::io::stderr();
+        write!(stderr, "{question}: ")?;
+        stderr.flush()?;
+
+        let mut answer = String::new();
+        std::io::stdin()
+            .lock()
+            .read_line(&mut answer)
+            .wrap_err_with(|| format!("couldn't read the {question}"))?;
+
+        Ok(answer.trim().to_string())
+    }
+
+    fn ask_secret(&mut self, question: &str) -> eyre::Result<String> {
+        let answer = rpassword::prompt_password(format!("{question}: "))
+            .wrap_err_with(|| format!("couldn't read the {question}"))?;
+
+        Ok(answer.trim().to_string())
+    }
+}
@@ -0,0 +1,74 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Gradual ramp-up of the sample rate, so that the broker-side autoscaling and rate limits can be
+//! observed rather than hit instantly.
+//!
+//! The streams start at a fraction of their configured rate, which grows linearly up to the full
+//! rate over the warm-up period.
+
+use std::time::Duration;
+
+/// Linear ramp-up of the sample rate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RampUp {
+    warm_up: Duration,
+    /// Initial rate, as a fraction of the configured one
+    start: f64,
+}
+
+impl RampUp {
+    /// Ramp up over the warm-up period starting from a percentage of the rate, none if the period
+    /// is zero
+    pub fn new(warm_up: Duration, start_percent: u8) -> Option<Self> {
+        (!warm_up.is_zero()).then(|| Self {
+            warm_up,
+            start: f64::from(start_percent.clamp(1, 100)) / 100.0,
+        })
+    }
+
+    /// Duration of the warm-up
+    pub fn warm_up(&self) -> Duration {
+        self.warm_up
+    }
+
+    /// Interval between two samples after the given time from the start
+    pub fn interval(&self, interval: Duration, elapsed: Duration) -> Duration {
+        if elapsed >= self.warm_up {
+            return interval;
+        }
+
+        let progress = elapsed.as_secs_f64() / self.warm_up.as_secs_f64();
+        let rate = self.start + (1.0 - self.start) * progress;
+
+        interval.div_f64(rate)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ramp_up() {
+        assert_eq!(RampUp::new(Duration::ZERO, 10), None);
+
+        let ramp_up = RampUp::new(Duration::from_secs(100), 10).unwrap();
+        let interval = Duration::from_millis(100);
+        let at = Duration::from_secs;
+
+        assert_eq!(
+            ramp_up.interval(interval, at(0)),
+            Duration::from_millis(1000)
+        );
+        assert_eq!(ramp_up.interval(interval, at(100)), interval);
+        assert_eq!(ramp_up.interval(interval, at(200)), interval);
+
+        // half way, at 55% of the rate
+        let half = ramp_up.interval(interval, at(50));
+        assert!(half > Duration::from_millis(181) && half < Duration::from_millis(182));
+    }
+}
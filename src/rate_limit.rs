@@ -0,0 +1,104 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rate limiting of the aggregate telemetry of the device, regardless of the intervals of the
+//! single streams.
+//!
+//! The limiter is a token bucket refilled at the maximum rate: each message consumes a token, and
+//! when the bucket is empty the message waits for the next one.
+
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Token bucket capping the messages per second of the device
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    /// Tokens added every second
+    rate: f64,
+    /// Maximum tokens in the bucket, sent back-to-back after an idle period
+    capacity: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl RateLimiter {
+    /// Limit to the given messages per second, allowing bursts up to the capacity
+    ///
+    /// The bucket starts full.
+    pub fn new(rate: u32, capacity: u32, now: Instant) -> Self {
+        let capacity = f64::from(capacity.max(1));
+
+        Self {
+            rate: f64::from(rate.max(1)),
+            capacity,
+            tokens: capacity,
+            refilled: now,
+        }
+    }
+
+    /// Take a token, or return the time to wait for the next one
+    pub fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.refilled);
+
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity);
+        self.refilled = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+
+            return Ok(());
+        }
+
+        Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+    }
+
+    /// Wait for a token
+    pub async fn acquire(&mut self) {
+        while let Err(wait) = self.try_acquire(Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(10, 2, start);
+
+        // the full bucket allows a burst
+        assert_eq!(limiter.try_acquire(start), Ok(()));
+        assert_eq!(limiter.try_acquire(start), Ok(()));
+
+        let wait = limiter.try_acquire(start).unwrap_err();
+        assert!(wait > Duration::from_millis(99) && wait <= Duration::from_millis(100));
+
+        let later = start + Duration::from_millis(100);
+        assert_eq!(limiter.try_acquire(later), Ok(()));
+        assert!(limiter.try_acquire(later).is_err());
+
+        // the idle time refills at most the capacity
+        let idle = later + Duration::from_secs(60);
+        assert_eq!(limiter.try_acquire(idle), Ok(()));
+        assert_eq!(limiter.try_acquire(idle), Ok(()));
+        assert!(limiter.try_acquire(idle).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(20, 1, start);
+
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+}
@@ -0,0 +1,131 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Probes comparing the delivery of the data sent with different reliability.
+//!
+//! Each probe sends the same sequence number on the mappings of the
+//! `org.astarte-platform.stream-rust-test.ReliabilityValues` interface, one per reliability. The
+//! device counts the messages it could send, while the consumers can count the lost and duplicated
+//! ones from the gaps and repetitions of the received sequence numbers.
+
+use crate::lanes::LaneCounter;
+use std::fmt::Display;
+
+/// Device owned interface with a mapping for each reliability
+pub const RELIABILITY_INTERFACE: &str = "org.astarte-platform.stream-rust-test.ReliabilityValues";
+
+/// Reliability of a mapping of the [`RELIABILITY_INTERFACE`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reliability {
+    /// Delivered at most once
+    Unreliable,
+    /// Delivered at least once
+    Guaranteed,
+    /// Delivered exactly once
+    Unique,
+}
+
+impl Reliability {
+    /// Every reliability, in the order of the probe counters
+    pub const ALL: [Reliability; 3] = [
+        Reliability::Unreliable,
+        Reliability::Guaranteed,
+        Reliability::Unique,
+    ];
+
+    /// Name of the mapping with this reliability
+    pub fn mapping(&self) -> &'static str {
+        match self {
+            Reliability::Unreliable => "unreliable",
+            Reliability::Guaranteed => "guaranteed",
+            Reliability::Unique => "unique",
+        }
+    }
+}
+
+impl Display for Reliability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.mapping())
+    }
+}
+
+/// Sequence number and delivery counters of the probes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReliabilityProbe {
+    sequence: i64,
+    counters: [LaneCounter; 3],
+}
+
+impl ReliabilityProbe {
+    /// Sequence number of the next probe
+    pub fn next_sequence(&mut self) -> i64 {
+        self.sequence = self.sequence.wrapping_add(1);
+
+        self.sequence
+    }
+
+    /// Get the counter of a reliability
+    pub fn get(&self, reliability: Reliability) -> LaneCounter {
+        self.counters[reliability as usize]
+    }
+
+    /// Record the outcome of a probe sent with a reliability
+    pub fn record(&mut self, reliability: Reliability, success: bool) {
+        let counter = &mut self.counters[reliability as usize];
+
+        if success {
+            counter.sent += 1;
+        } else {
+            counter.failed += 1;
+        }
+    }
+}
+
+impl Display for ReliabilityProbe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, reliability) in Reliability::ALL.into_iter().enumerate() {
+            let counter = self.get(reliability);
+
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(
+                f,
+                "{reliability} sent {} failed {}",
+                counter.sent, counter.failed
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reliability_probe() {
+        let mut probe = ReliabilityProbe::default();
+
+        assert_eq!(probe.next_sequence(), 1);
+        assert_eq!(probe.next_sequence(), 2);
+
+        probe.record(Reliability::Unreliable, true);
+        probe.record(Reliability::Unique, false);
+        probe.record(Reliability::Unique, true);
+
+        assert_eq!(probe.get(Reliability::Unreliable).sent, 1);
+        assert_eq!(probe.get(Reliability::Guaranteed), LaneCounter::default());
+        assert_eq!(probe.get(Reliability::Unique).failed, 1);
+
+        assert_eq!(
+            probe.to_string(),
+            "unreliable sent 1 failed 0, guaranteed sent 0 failed 0, unique sent 1 failed 1"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuration received from Astarte, persisted so that the device resumes with its last
+//! commanded configuration after a restart.
+//!
+//! Unlike the properties, the commands are not stored by the Astarte SDK, so the last update of
+//! each parameter of each sensor is saved as JSON in a sidecar file of the store directory.
+
+use crate::stream::StreamConfigUpdate;
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the file, in the store directory, where the configuration is persisted
+pub const REMOTE_CONFIG_FILE: &str = "remote-config.json";
+
+/// Last update of each parameter, by sensor id and parameter name
+type Updates = BTreeMap<String, BTreeMap<String, StreamConfigUpdate>>;
+
+/// Configuration received from Astarte, persisted in a file
+#[derive(Debug)]
+pub struct RemoteConfig {
+    path: PathBuf,
+    updates: Updates,
+}
+
+impl RemoteConfig {
+    /// Load the configuration persisted in a file
+    ///
+    /// A missing file persists no configuration.
+    pub async fn load(path: PathBuf) -> eyre::Result<Self> {
+        let updates = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content)
+                .wrap_err_with(|| format!("invalid remote configuration in {}", path.display()))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Updates::default(),
+            Err(err) => {
+                return Err(err).wrap_err_with(|| format!("couldn't read {}", path.display()));
+            }
+        };
+
+        Ok(Self { path, updates })
+    }
+
+    /// Path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Persisted updates, with the id of the updated sensor
+    pub fn updates(&self) -> impl Iterator<Item = (&str, &StreamConfigUpdate)> {
+        self.updates.iter().flat_map(|(sensor_id, updates)| {
+            updates
+                .values()
+                .map(move |update| (sensor_id.as_str(), update))
+        })
+    }
+
+    /// Record the update of a parameter of a sensor, replacing the previous one
+    pub fn record(&mut self, sensor_id: &str, update: StreamConfigUpdate) {
+        self.updates
+            .entry(sensor_id.to_string())
+            .or_default()
+            .insert(update.parameter().to_string(), update);
+    }
+
+    /// Forget the update of a parameter of a sensor, restored to its initial value
+    pub fn forget(&mut self, sensor_id: &str, parameter: &str) {
+        if let Some(updates) = self.updates.get_mut(sensor_id) {
+            updates.remove(parameter);
+
+            if updates.is_empty() {
+                self.updates.remove(sensor_id);
+            }
+        }
+    }
+
+    /// Forget every update of a deleted sensor
+    pub fn forget_sensor(&mut self, sensor_id: &str) {
+        self.updates.remove(sensor_id);
+    }
+
+    /// Save the configuration, replacing the file atomically
+    pub async fn save(&self) -> eyre::Result<()> {
+        let content = serde_json::to_string_pretty(&self.updates)
+            .wrap_err("couldn't serialize the remote configuration")?;
+
+        let tmp = self.path.with_extension("json.tmp");
+
+        tokio::fs::write(&tmp, content)
+            .await
+            .wrap_err_with(|| format!("couldn't write {}", tmp.display()))?;
+
+        tokio::fs::rename(&tmp, &self.path)
+            .await
+            .wrap_err_with(|| format!("couldn't replace {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math::MathFunction;
+    use crate::stream::StreamState;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_remote_config_round_trip() {
+        let dir = std::env::temp_dir().join(format!("remote-config-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join(REMOTE_CONFIG_FILE);
+
+        let mut config = RemoteConfig::load(path.clone()).await.unwrap();
+        assert_eq!(config.updates().count(), 0);
+
+        config.record(
+            "test",
+            StreamConfigUpdate::Interval(Duration::from_millis(500)),
+        );
+        config.record(
+            "test",
+            StreamConfigUpdate::Interval(Duration::from_millis(200)),
+        );
+        config.record("test", StreamConfigUpdate::State(StreamState::Off));
+        config.record(
+            "test-1",
+            StreamConfigUpdate::MathFunction(MathFunction::Saw),
+        );
+        config.record("test-2", StreamConfigUpdate::Scale(2.5));
+        config.forget("test", "toggle");
+        config.forget_sensor("test-2");
+        config.save().await.unwrap();
+
+        // the intervals are persisted in milliseconds
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(content.contains("\"value\": 200"), "{content}");
+
+        let config = RemoteConfig::load(path).await.unwrap();
+        let updates: Vec<_> = config.updates().collect();
+
+        assert_eq!(
+            updates,
+            [
+                (
+                    "test",
+                    &StreamConfigUpdate::Interval(Duration::from_millis(200))
+                ),
+                (
+                    "test-1",
+                    &StreamConfigUpdate::MathFunction(MathFunction::Saw)
+                ),
+            ]
+        );
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+}
@@ -0,0 +1,165 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replay of the values recorded in a JSON lines file.
+//!
+//! Each line is an object with the `interface`, `path`, `type` and `value` of a value, and an
+//! optional `timestamp`, e.g.:
+//!
+//! ```json
+//! {"interface":"org.astarte-platform.genericsensors.Values","path":"/test/value","type":"double","value":0.5,"timestamp":"2026-10-15T08:00:00Z"}
+//! ```
+//!
+//! The `type` is one of the output types. A `binaryblob` is an array of bytes, while a `datetime`
+//! is an RFC 3339 string.
+
+use crate::output::OutputType;
+use astarte_device_sdk::chrono::{DateTime, Utc};
+use astarte_device_sdk::{AstarteType, Client};
+use color_eyre::eyre;
+use color_eyre::eyre::{eyre, WrapErr};
+use serde::Deserialize;
+use std::path::Path;
+use tracing::debug;
+
+/// Value recorded in a line of the file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecordedValue {
+    /// Interface the value is sent to
+    pub interface: String,
+    /// Endpoint the value is sent to
+    pub path: String,
+    /// Astarte type of the value
+    #[serde(rename = "type")]
+    pub output_type: OutputType,
+    /// Value, converted to the Astarte type
+    pub value: serde_json::Value,
+    /// Explicit timestamp of the value, if any
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl RecordedValue {
+    /// Parse a line of the file
+    pub fn parse(line: &str) -> eyre::Result<Self> {
+        serde_json::from_str(line).wrap_err("invalid recorded value")
+    }
+
+    /// Convert the value to its Astarte type
+    pub fn astarte_value(&self) -> eyre::Result<AstarteType> {
+        let value = self.value.clone();
+
+        let value = match self.output_type {
+            OutputType::Double => serde_json::from_value(value).map(AstarteType::Double),
+            OutputType::Integer => serde_json::from_value(value).map(AstarteType::Integer),
+            OutputType::Boolean => serde_json::from_value(value).map(AstarteType::Boolean),
+            OutputType::String => serde_json::from_value(value).map(AstarteType::String),
+            OutputType::LongInteger => serde_json::from_value(value).map(AstarteType::LongInteger),
+            OutputType::BinaryBlob => serde_json::from_value(value).map(AstarteType::BinaryBlob),
+            OutputType::DateTime => serde_json::from_value(value).map(AstarteType::DateTime),
+        };
+
+        value.wrap_err_with(|| format!("invalid {:?} value {}", self.output_type, self.value))
+    }
+}
+
+/// Send the values recorded in a file, in order, returning the number of values sent
+pub async fn replay<C>(client: &C, file: &Path) -> eyre::Result<u64>
+where
+    C: Client,
+{
+    let content = tokio::fs::read_to_string(file)
+        .await
+        .wrap_err_with(|| format!("couldn't read {}", file.display()))?;
+
+    let mut sent = 0;
+
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let invalid = || format!("invalid line {} of {}", i + 1, file.display());
+        let recorded = RecordedValue::parse(line).wrap_err_with(invalid)?;
+        let value = recorded.astarte_value().wrap_err_with(invalid)?;
+
+        debug!(
+            "replaying {}{} {value:?}",
+            recorded.interface, recorded.path
+        );
+
+        let res = match recorded.timestamp {
+            Some(timestamp) => {
+                client
+                    .send_with_timestamp(&recorded.interface, &recorded.path, value, timestamp)
+                    .await
+            }
+            None => {
+                client
+                    .send(&recorded.interface, &recorded.path, value)
+                    .await
+            }
+        };
+
+        res.map_err(|err| {
+            eyre!(
+                "couldn't send {}{} at line {}: {err}",
+                recorded.interface,
+                recorded.path,
+                i + 1
+            )
+        })?;
+
+        sent += 1;
+    }
+
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::mock;
+    use astarte_device_sdk::Value;
+
+    #[tokio::test]
+    async fn test_replay() {
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", uuid::Uuid::new_v4()));
+        tokio::fs::write(
+            &path,
+            r#"{"interface":"org.astarte-platform.genericsensors.Values","path":"/test/value","type":"double","value":0.5}
+
+            {"interface":"org.astarte-platform.stream-rust-test.BinaryBlobValues","path":"/test/value","type":"binaryblob","value":[1,2],"timestamp":"2026-10-15T08:00:00Z"}
+            "#,
+        )
+        .await
+        .unwrap();
+
+        let (client, connection) = mock();
+        assert_eq!(replay(&client, &path).await.unwrap(), 2);
+
+        let sent = connection.sent();
+        assert_eq!(sent[0].data, Value::Individual(AstarteType::Double(0.5)));
+        assert_eq!(
+            sent[1].data,
+            Value::Individual(AstarteType::BinaryBlob(vec![1, 2]))
+        );
+        assert_eq!(
+            sent[1].timestamp,
+            Some("2026-10-15T08:00:00Z".parse().unwrap())
+        );
+
+        tokio::fs::write(
+            &path,
+            r#"{"interface":"org.astarte-platform.genericsensors.Values","path":"/test/value","type":"integer","value":"a"}"#,
+        )
+        .await
+        .unwrap();
+        assert!(replay(&client, &path).await.is_err());
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}
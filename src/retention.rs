@@ -0,0 +1,275 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Limits of the data retained while offline, so that a long outage doesn't fill the disk.
+//!
+//! The Astarte Device SDK doesn't cap the stored retention, so the messages exceeding the limits are
+//! periodically evicted from the SQLite store, either the oldest or the newest ones. The retention
+//! API of the store can only read the messages with their payloads from the oldest, so the messages
+//! are counted and evicted in bounded batches directly in the database, whose schema is the one of
+//! the pinned SDK version.
+
+use clap::ValueEnum;
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use rusqlite::{Connection, OpenFlags};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Interval between two checks of the retention limits
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Messages evicted at most at once, so that the database is locked only briefly
+const EVICTION_BATCH: u64 = 100;
+
+/// Messages evicted when the retention exceeds its limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Eviction {
+    /// Evict the oldest messages, keeping the most recent data
+    #[default]
+    DropOldest,
+    /// Evict the newest messages, keeping the data from the start of the outage
+    DropNewest,
+}
+
+impl Eviction {
+    /// Sort order of the retained messages evicted first
+    fn order(&self) -> &'static str {
+        match self {
+            Eviction::DropOldest => "ASC",
+            Eviction::DropNewest => "DESC",
+        }
+    }
+}
+
+/// Limits of the stored retention
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionLimits {
+    /// Maximum number of retained messages
+    pub max_messages: Option<u64>,
+    /// Maximum bytes used by the database
+    pub max_size: Option<u64>,
+    /// Messages evicted first
+    pub eviction: Eviction,
+}
+
+impl RetentionLimits {
+    /// Limits of the retention, none if neither the messages nor the size are capped
+    pub fn new(
+        max_messages: Option<u64>,
+        max_size: Option<u64>,
+        eviction: Eviction,
+    ) -> Option<Self> {
+        (max_messages.is_some() || max_size.is_some()).then_some(Self {
+            max_messages,
+            max_size,
+            eviction,
+        })
+    }
+
+    /// Periodically evict the messages exceeding the limits from the given database
+    ///
+    /// The database is created by the SDK when connecting.
+    pub async fn enforce(self, database: PathBuf) -> eyre::Result<()> {
+        let connection = open(&database)?;
+
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match self.evict(&connection) {
+                Ok(0) => {}
+                Ok(evicted) => warn!("retention limits exceeded, evicted {evicted} messages"),
+                Err(err) => debug!("couldn't check the retention limits: {err:#}"),
+            }
+        }
+    }
+
+    /// Evict the messages exceeding the limits from the database, returning their number
+    fn evict(&self, connection: &Connection) -> eyre::Result<u64> {
+        let mut evicted = 0;
+
+        if let Some(max) = self.max_messages {
+            let mut excess = retained(connection)?.saturating_sub(max);
+
+            while excess > 0 {
+                let deleted = self.delete(connection, excess.min(EVICTION_BATCH))?;
+
+                // the messages sent and waiting for the acknowledgment aren't evicted
+                if deleted == 0 {
+                    break;
+                }
+
+                evicted += deleted;
+                excess = excess.saturating_sub(deleted);
+            }
+        }
+
+        if let Some(max) = self.max_size {
+            let batch = (retained(connection)? / 10).clamp(1, EVICTION_BATCH);
+
+            // the properties alone can exceed the size
+            while used_size(connection)? > max {
+                let deleted = self.delete(connection, batch)?;
+
+                if deleted == 0 {
+                    break;
+                }
+
+                evicted += deleted;
+            }
+        }
+
+        Ok(evicted)
+    }
+
+    /// Delete at most the given number of messages waiting to be sent, returning their number
+    fn delete(&self, connection: &Connection, limit: u64) -> eyre::Result<u64> {
+        let order = self.eviction.order();
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+
+        let deleted = connection
+            .execute(
+                &format!(
+                    "DELETE FROM retention_publish WHERE (t_millis, counter) IN (\
+                     SELECT t_millis, counter FROM retention_publish WHERE sent = FALSE \
+                     ORDER BY t_millis {order}, counter {order} LIMIT ?1)"
+                ),
+                [limit],
+            )
+            .wrap_err("couldn't evict the retained messages")?;
+
+        Ok(deleted as u64)
+    }
+}
+
+/// Number of retained messages, including the ones sent and waiting for the acknowledgment
+fn retained(connection: &Connection) -> eyre::Result<u64> {
+    connection
+        .query_row("SELECT COUNT(*) FROM retention_publish", [], |row| {
+            row.get(0)
+        })
+        .wrap_err("couldn't count the retained messages")
+}
+
+/// Open the database to evict the messages and read its size
+fn open(database: &Path) -> eyre::Result<Connection> {
+    let connection = Connection::open_with_flags(database, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .wrap_err_with(|| format!("couldn't open {}", database.display()))?;
+
+    connection.busy_timeout(Duration::from_secs(1))?;
+
+    Ok(connection)
+}
+
+/// Bytes used by the database, excluding the free pages reused by the next writes
+fn used_size(connection: &Connection) -> eyre::Result<u64> {
+    let pragma = |name: &str| -> eyre::Result<u64> {
+        connection
+            .query_row(&format!("PRAGMA {name}"), [], |row| row.get(0))
+            .wrap_err_with(|| format!("couldn't read the {name} of the database"))
+    };
+
+    let used_pages = pragma("page_count")?.saturating_sub(pragma("freelist_count")?);
+
+    Ok(used_pages * pragma("page_size")?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use astarte_device_sdk::retention::StoredRetention;
+    use astarte_device_sdk::store::SqliteStore;
+
+    /// Store created by the SDK, with the given number of messages retained while offline
+    async fn retained_store(name: &str, messages: u128) -> (SqliteStore, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let database = dir.join("store.db");
+        let store = SqliteStore::connect_db(&database).await.unwrap();
+
+        // the SDK only stores the publishes while sending, so they are inserted in its schema
+        let connection = Connection::open(&database).unwrap();
+        connection
+            .execute(
+                "INSERT INTO retention_mapping (interface, path, major_version, reliability) \
+                 VALUES ('com.example.Test', '/value', 0, 1)",
+                [],
+            )
+            .unwrap();
+
+        for t in 0..messages {
+            connection
+                .execute(
+                    "INSERT INTO retention_publish \
+                     (t_millis, counter, interface, path, sent, payload) \
+                     VALUES (?1, 0, 'com.example.Test', '/value', FALSE, zeroblob(1024))",
+                    [t.to_be_bytes()],
+                )
+                .unwrap();
+        }
+
+        (store, database)
+    }
+
+    /// Id of the oldest message waiting to be sent, read through the retention API of the SDK
+    async fn first_message(store: &SqliteStore) -> Option<String> {
+        let mut publishes = Vec::new();
+        store.unsent_publishes(1, &mut publishes).await.unwrap();
+
+        publishes.first().map(|(id, _)| id.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_retention_limits() {
+        assert_eq!(RetentionLimits::new(None, None, Eviction::DropOldest), None);
+
+        let (store, database) = retained_store("retention-oldest", 20).await;
+        let connection = open(&database).unwrap();
+        let limits = RetentionLimits::new(Some(15), None, Eviction::DropOldest).unwrap();
+        assert_eq!(limits.evict(&connection).unwrap(), 5);
+        assert_eq!(retained(&connection).unwrap(), 15);
+        assert_eq!(first_message(&store).await.as_deref(), Some("5-0"));
+
+        // within the limits
+        assert_eq!(limits.evict(&connection).unwrap(), 0);
+
+        let (store, database) = retained_store("retention-newest", 20).await;
+        let connection = open(&database).unwrap();
+        let limits = RetentionLimits::new(Some(15), None, Eviction::DropNewest).unwrap();
+        assert_eq!(limits.evict(&connection).unwrap(), 5);
+        assert_eq!(first_message(&store).await.as_deref(), Some("0-0"));
+
+        // the sent messages are counted but not evicted
+        let (store, database) = retained_store("retention-sent", 20).await;
+        let connection = open(&database).unwrap();
+        connection
+            .execute(
+                "UPDATE retention_publish SET sent = TRUE WHERE t_millis < ?1",
+                [10u128.to_be_bytes()],
+            )
+            .unwrap();
+        let limits = RetentionLimits::new(Some(5), None, Eviction::DropOldest).unwrap();
+        assert_eq!(limits.evict(&connection).unwrap(), 10);
+        assert_eq!(retained(&connection).unwrap(), 10);
+        assert_eq!(first_message(&store).await, None);
+
+        let (store, database) = retained_store("retention-size", 200).await;
+        let connection = open(&database).unwrap();
+        let max = used_size(&connection).unwrap() / 2;
+        let limits = RetentionLimits::new(None, Some(max), Eviction::DropOldest).unwrap();
+        assert!(limits.evict(&connection).unwrap() > 0);
+        assert!(used_size(&connection).unwrap() <= max);
+
+        // nothing left to evict
+        let limits = RetentionLimits::new(None, Some(0), Eviction::DropOldest).unwrap();
+        limits.evict(&connection).unwrap();
+        assert_eq!(first_message(&store).await, None);
+    }
+}
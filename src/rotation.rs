@@ -0,0 +1,97 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runtime rotation of the device credentials.
+//!
+//! A rotation is requested remotely with the `org.astarte-platform.stream-rust-test.ConnectionCommands`
+//! interface or locally with a SIGHUP. The credential is then re-read from the `config.toml` file
+//! and the environment variables, and the MQTT session is re-established with it.
+
+use astarte_device_sdk::AstarteType;
+use color_eyre::eyre;
+use color_eyre::eyre::bail;
+use tokio::sync::watch;
+use tracing::info;
+
+/// Server owned interface of the commands updating the connection
+pub const CONNECTION_COMMANDS_INTERFACE: &str =
+    "org.astarte-platform.stream-rust-test.ConnectionCommands";
+
+/// Update of the connection to Astarte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionCommand {
+    /// Re-read the credentials and re-establish the session
+    RotateCredentials,
+}
+
+impl ConnectionCommand {
+    /// Parse a command received on the [`CONNECTION_COMMANDS_INTERFACE`]
+    ///
+    /// Setting a command to `false` doesn't request anything.
+    pub fn try_from_value(path: &str, value: AstarteType) -> eyre::Result<Option<Self>> {
+        let AstarteType::Boolean(value) = value else {
+            bail!("the connection commands must be booleans");
+        };
+
+        let command = match path {
+            "/rotateCredentials" => Self::RotateCredentials,
+            _ => bail!("unknown connection command {path}"),
+        };
+
+        Ok(value.then_some(command))
+    }
+
+    /// Apply the command, notifying the requests to the connection
+    pub fn apply(&self, rotation: &watch::Sender<u64>) {
+        match self {
+            ConnectionCommand::RotateCredentials => {
+                info!("credentials rotation requested from Astarte");
+
+                request_rotation(rotation);
+            }
+        }
+    }
+}
+
+/// Request a rotation of the credentials
+pub fn request_rotation(rotation: &watch::Sender<u64>) {
+    rotation.send_modify(|requests| *requests += 1);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_connection_command() {
+        assert_eq!(
+            ConnectionCommand::try_from_value("/rotateCredentials", AstarteType::Boolean(true))
+                .unwrap(),
+            Some(ConnectionCommand::RotateCredentials)
+        );
+        assert_eq!(
+            ConnectionCommand::try_from_value("/rotateCredentials", AstarteType::Boolean(false))
+                .unwrap(),
+            None
+        );
+
+        assert!(
+            ConnectionCommand::try_from_value("/rotateCredentials", AstarteType::Integer(1))
+                .is_err()
+        );
+        assert!(ConnectionCommand::try_from_value("/repair", AstarteType::Boolean(true)).is_err());
+    }
+
+    #[test]
+    fn test_request_rotation() {
+        let (tx, mut rx) = watch::channel(0);
+
+        ConnectionCommand::RotateCredentials.apply(&tx);
+
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(*rx.borrow_and_update(), 1);
+    }
+}
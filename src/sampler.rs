@@ -0,0 +1,89 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Custom generators, to embed the streamer in other crates.
+//!
+//! A [`Sampler`] replaces the math function of a sensor, computing its values from the base value
+//! of each sample. The [`StreamBuilder`] builds a [`StreamManager`] from the configuration and the
+//! samplers of the sensors, which then sends their values to Astarte like the built-in ones:
+//!
+//! ```no_run
+//! # use stream_rust_test::cli::Config;
+//! # use stream_rust_test::sampler::{Sampler, StreamBuilder};
+//! # use clap::Parser;
+//! #[derive(Debug)]
+//! struct Counter(f64);
+//!
+//! impl Sampler for Counter {
+//!     fn name(&self) -> String {
+//!         "counter".to_string()
+//!     }
+//!
+//!     fn sample(&mut self, _input: f64) -> Option<f64> {
+//!         self.0 += 1.0;
+//!         Some(self.0)
+//!     }
+//! }
+//!
+//! # async fn build() -> color_eyre::Result<()> {
+//! let manager = StreamBuilder::new(Config::parse())
+//!     .sampler(0, Counter(0.0))
+//!     .build(std::time::SystemTime::now())
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::cli::Config;
+use crate::stream::StreamManager;
+use color_eyre::eyre;
+use std::fmt::Debug;
+use std::time::SystemTime;
+
+/// Generator of the values of a sensor
+pub trait Sampler: Debug + Send + Sync {
+    /// Name of the generator, published as the name of the sensor and in the status reports
+    fn name(&self) -> String;
+
+    /// Compute the value of a sample from its base value, or [`None`] to skip the sample
+    fn sample(&mut self, input: f64) -> Option<f64>;
+}
+
+/// Builder of a [`StreamManager`] with custom generators
+#[derive(Debug)]
+pub struct StreamBuilder {
+    cfg: Config,
+    samplers: Vec<(usize, Box<dyn Sampler>)>,
+}
+
+impl StreamBuilder {
+    /// Start from the configuration of the stream
+    pub fn new(cfg: Config) -> Self {
+        Self {
+            cfg,
+            samplers: Vec::new(),
+        }
+    }
+
+    /// Generate the values of the n-th sensor with a sampler, instead of its math function
+    ///
+    /// A math function update received from Astarte replaces the sampler.
+    pub fn sampler(mut self, sensor: usize, sampler: impl Sampler + 'static) -> Self {
+        self.samplers.push((sensor, Box::new(sampler)));
+        self
+    }
+
+    /// Build the stream manager, failing if a sampler addresses a sensor that isn't simulated
+    pub async fn build(self, now: SystemTime) -> eyre::Result<StreamManager> {
+        let mut manager = StreamManager::new(self.cfg, now).await?;
+
+        for (sensor, sampler) in self.samplers {
+            manager.set_sampler(sensor, sampler)?;
+        }
+
+        Ok(manager)
+    }
+}
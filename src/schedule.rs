@@ -0,0 +1,207 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Piecewise schedule of the math functions used to generate the data.
+//!
+//! The schedule is read from a TOML file containing a list of steps, for instance:
+//!
+//! ```toml
+//! repeat = false
+//!
+//! [[step]]
+//! function = "sin"
+//! duration = 60
+//!
+//! [[step]]
+//! function = "const"
+//! duration = 60
+//!
+//! [[step]]
+//! function = "random"
+//! ```
+
+use crate::duration::Secs;
+use crate::math::MathFunction;
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, WrapErr};
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Timeline of math functions the stream switches between
+#[derive(Debug, Clone, Deserialize)]
+pub struct Schedule {
+    /// Restart from the first step once the last one has elapsed
+    #[serde(default)]
+    repeat: bool,
+    /// Ordered list of steps
+    #[serde(rename = "step")]
+    steps: Vec<ScheduleStep>,
+}
+
+/// Single step of a [`Schedule`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleStep {
+    /// Math function used during the step
+    function: MathFunction,
+    /// Duration of the step in seconds, or a human friendly duration
+    ///
+    /// If missing, the step lasts forever.
+    duration: Option<Secs>,
+}
+
+impl ScheduleStep {
+    fn duration(&self) -> Option<Duration> {
+        self.duration.map(|Secs(secs)| Duration::from_secs(secs))
+    }
+}
+
+impl Schedule {
+    /// Read the schedule from a TOML file
+    pub async fn from_toml(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let file = tokio::fs::read_to_string(&path)
+            .await
+            .wrap_err_with(|| format!("couldn't read {}", path.as_ref().display()))?;
+
+        let schedule: Schedule = toml::from_str(&file).wrap_err("invalid schedule")?;
+
+        schedule.validate()?;
+
+        Ok(schedule)
+    }
+
+    fn validate(&self) -> eyre::Result<()> {
+        ensure!(!self.steps.is_empty(), "the schedule must contain a step");
+
+        let (last, steps) = self
+            .steps
+            .split_last()
+            .expect("the schedule contains at least a step");
+
+        ensure!(
+            steps
+                .iter()
+                .all(|s| s.duration.is_some_and(|Secs(secs)| secs > 0)),
+            "only the last step of the schedule can have no duration"
+        );
+
+        ensure!(
+            !self.repeat || last.duration.is_some_and(|Secs(secs)| secs > 0),
+            "a repeating schedule must have a duration for each step"
+        );
+
+        Ok(())
+    }
+
+    /// Math functions of the steps
+    pub fn functions(&self) -> impl Iterator<Item = &MathFunction> {
+        self.steps.iter().map(|step| &step.function)
+    }
+
+    /// Return the math function of the step active after the given time from the stream start
+    pub fn function_at(&self, elapsed: Duration) -> &MathFunction {
+        let mut elapsed = elapsed;
+
+        if self.repeat {
+            let period: Duration = self.steps.iter().filter_map(ScheduleStep::duration).sum();
+
+            if !period.is_zero() {
+                elapsed = Duration::from_nanos((elapsed.as_nanos() % period.as_nanos()) as u64);
+            }
+        }
+
+        for step in &self.steps {
+            match step.duration() {
+                Some(duration) if elapsed >= duration => elapsed -= duration,
+                _ => return &step.function,
+            }
+        }
+
+        // the schedule elapsed, keep using the last function
+        &self
+            .steps
+            .last()
+            .expect("the schedule contains at least a step")
+            .function
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SCHEDULE: &str = r#"
+        [[step]]
+        function = "sin"
+        duration = 60
+
+        [[step]]
+        function = "const"
+        duration = 60
+
+        [[step]]
+        function = "random"
+    "#;
+
+    #[test]
+    fn test_schedule_function_at() {
+        let schedule: Schedule = toml::from_str(SCHEDULE).unwrap();
+        schedule.validate().unwrap();
+
+        let at = |secs| schedule.function_at(Duration::from_secs(secs)).clone();
+
+        assert!(matches!(at(0), MathFunction::Sin));
+        assert!(matches!(at(59), MathFunction::Sin));
+        assert!(matches!(at(60), MathFunction::Const));
+        assert!(matches!(at(119), MathFunction::Const));
+        assert!(matches!(at(120), MathFunction::Random));
+        assert!(matches!(at(10_000), MathFunction::Random));
+    }
+
+    #[test]
+    fn test_schedule_repeat() {
+        let schedule: Schedule = toml::from_str(
+            r#"
+            repeat = true
+
+            [[step]]
+            function = "saw"
+            duration = 10
+
+            [[step]]
+            function = "rect"
+            duration = 5
+            "#,
+        )
+        .unwrap();
+        schedule.validate().unwrap();
+
+        let at = |secs| schedule.function_at(Duration::from_secs(secs)).clone();
+
+        assert!(matches!(at(9), MathFunction::Saw));
+        assert!(matches!(at(14), MathFunction::Rect));
+        assert!(matches!(at(15), MathFunction::Saw));
+        assert!(matches!(at(29), MathFunction::Rect));
+    }
+
+    #[test]
+    fn test_schedule_validation() {
+        let empty: Schedule = toml::from_str("step = []").unwrap();
+        assert!(empty.validate().is_err());
+
+        let unbounded: Schedule = toml::from_str(
+            r#"
+            [[step]]
+            function = "sin"
+
+            [[step]]
+            function = "const"
+            "#,
+        )
+        .unwrap();
+        assert!(unbounded.validate().is_err());
+    }
+}
@@ -0,0 +1,199 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Template generating the ids of the simulated sensors.
+//!
+//! The `{n}` placeholder is replaced by the index of the sensor, starting from 0, and `{uuid}` by a
+//! UUID derived from the device id and the index, for instance `sensor_{n}` or `{uuid}`. The ids
+//! are unique and the same across restarts, while different devices generate different UUIDs.
+//!
+//! A template without placeholders is used as is by the first sensor, while the others append
+//! `-1`, `-2` and so on to it.
+//!
+//! The `*` and `all` ids are reserved, since the remote configuration updates addressed to them
+//! apply to every sensor. Likewise, a pattern ending with `*`, e.g. `hvac.*`, addresses the group
+//! of the sensors whose id starts with the rest of the pattern, e.g. `hvac.1` and `hvac.2`.
+
+use crate::offset::stable_hash;
+use color_eyre::eyre;
+use color_eyre::eyre::ensure;
+use std::fmt::Display;
+use uuid::Builder;
+
+/// Default id of the primary sensor
+pub const DEFAULT_SENSOR_ID: &str = "test";
+
+/// Ids addressing every sensor in the remote configuration updates
+pub const BROADCAST_SENSOR_IDS: [&str; 2] = ["*", "all"];
+
+/// Suffix of the patterns addressing a group of sensors
+const GROUP_WILDCARD: char = '*';
+
+/// Placeholder replaced by the index of the sensor
+const INDEX: &str = "{n}";
+/// Placeholder replaced by a UUID derived from the device id and the index of the sensor
+const UUID: &str = "{uuid}";
+
+/// Template of the ids of the sensors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SensorIdTemplate(String);
+
+impl Default for SensorIdTemplate {
+    fn default() -> Self {
+        Self(DEFAULT_SENSOR_ID.to_string())
+    }
+}
+
+impl SensorIdTemplate {
+    /// Parse a template, rejecting the ids that aren't a single endpoint level
+    pub fn parse(template: &str) -> eyre::Result<Self> {
+        ensure!(!template.is_empty(), "the sensor id must not be empty");
+        ensure!(
+            !template.contains('/'),
+            "the sensor id {template} must not contain a '/'"
+        );
+
+        ensure!(
+            !is_broadcast(template),
+            "the sensor id {template} is reserved to address every sensor"
+        );
+        ensure!(
+            !template.contains(GROUP_WILDCARD),
+            "the sensor id {template} must not contain a '{GROUP_WILDCARD}', reserved to address \
+             groups of sensors"
+        );
+
+        let rest = template.replace(INDEX, "").replace(UUID, "");
+
+        ensure!(
+            !rest.contains(['{', '}']),
+            "unknown placeholder in sensor id {template}, expected {INDEX} or {UUID}"
+        );
+
+        Ok(Self(template.to_string()))
+    }
+
+    /// Id of the n-th sensor of a device
+    pub fn render(&self, n: usize, device_id: &str) -> String {
+        if !self.0.contains(INDEX) && !self.0.contains(UUID) {
+            return match n {
+                0 => self.0.clone(),
+                n => format!("{}-{n}", self.0),
+            };
+        }
+
+        let mut id = self.0.replace(INDEX, &n.to_string());
+
+        if id.contains(UUID) {
+            let index = n.to_le_bytes();
+            let high = stable_hash(&[b"high", device_id.as_bytes(), &index]);
+            let low = stable_hash(&[b"low", device_id.as_bytes(), &index]);
+
+            let mut bytes = [0; 16];
+            bytes[..8].copy_from_slice(&high.to_be_bytes());
+            bytes[8..].copy_from_slice(&low.to_be_bytes());
+
+            let uuid = Builder::from_custom_bytes(bytes).into_uuid();
+
+            id = id.replace(UUID, &uuid.to_string());
+        }
+
+        id
+    }
+}
+
+/// Check if a sensor id addresses every sensor
+pub fn is_broadcast(sensor_id: &str) -> bool {
+    BROADCAST_SENSOR_IDS.contains(&sensor_id)
+}
+
+/// Check if a sensor id is the pattern of a group of sensors, e.g. `hvac.*`
+pub fn is_group(sensor_id: &str) -> bool {
+    sensor_id.ends_with(GROUP_WILDCARD) && !is_broadcast(sensor_id)
+}
+
+/// Check if a sensor id addresses several sensors, either every sensor or a group
+pub fn is_pattern(sensor_id: &str) -> bool {
+    is_broadcast(sensor_id) || is_group(sensor_id)
+}
+
+/// Check if a sensor id or pattern addresses the sensor with the given id
+pub fn addresses(pattern: &str, sensor_id: &str) -> bool {
+    if is_broadcast(pattern) {
+        return true;
+    }
+
+    match pattern.strip_suffix(GROUP_WILDCARD) {
+        Some(prefix) => sensor_id.starts_with(prefix),
+        None => pattern == sensor_id,
+    }
+}
+
+/// Parse the pattern of a group of sensors
+pub fn parse_group(pattern: &str) -> eyre::Result<String> {
+    ensure!(
+        is_group(pattern),
+        "the group {pattern} must end with '{GROUP_WILDCARD}', e.g. hvac.*"
+    );
+    ensure!(
+        !pattern.contains('/'),
+        "the group {pattern} must not contain a '/'"
+    );
+
+    Ok(pattern.to_string())
+}
+
+impl Display for SensorIdTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sensor_id_template() {
+        let template = SensorIdTemplate::default();
+        assert_eq!(template.render(0, "device"), "test");
+        assert_eq!(template.render(2, "device"), "test-2");
+
+        let template = SensorIdTemplate::parse("sensor_{n}").unwrap();
+        assert_eq!(template.render(0, "device"), "sensor_0");
+        assert_eq!(template.render(3, "device"), "sensor_3");
+
+        let template = SensorIdTemplate::parse("{uuid}").unwrap();
+        let id = template.render(0, "device");
+        assert_eq!(id, template.render(0, "device"));
+        assert_ne!(id, template.render(1, "device"));
+        assert_ne!(id, template.render(0, "other"));
+        assert!(uuid::Uuid::parse_str(&id).is_ok());
+
+        for template in ["", "a/b", "{id}", "sensor_{n", "*", "all", "hvac.*"] {
+            assert!(SensorIdTemplate::parse(template).is_err(), "{template}");
+        }
+    }
+
+    #[test]
+    fn test_sensor_groups() {
+        assert!(addresses("*", "hvac.1"));
+        assert!(addresses("all", "hvac.1"));
+        assert!(addresses("hvac.*", "hvac.1"));
+        assert!(addresses("hvac.1", "hvac.1"));
+        assert!(!addresses("hvac.*", "pump.1"));
+        assert!(!addresses("hvac.1", "hvac.10"));
+
+        assert!(is_pattern("hvac.*"));
+        assert!(is_pattern("all"));
+        assert!(!is_pattern("hvac.1"));
+
+        assert_eq!(parse_group("hvac.*").unwrap(), "hvac.*");
+        for pattern in ["hvac", "*", "a/b*"] {
+            assert!(parse_group(pattern).is_err(), "{pattern}");
+        }
+    }
+}
@@ -4,10 +4,29 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-//! Define shutdown futures to stop the docker container with CTRL+C command
+//! Define shutdown futures to stop the docker container with CTRL+C command, and the signals
+//! pausing and resuming the streams and rotating the credentials locally
 
 use color_eyre::eyre;
-use tracing::error;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{error, info};
+
+/// Exit code of the process terminated by the [`watchdog`]
+pub const WATCHDOG_EXIT_CODE: i32 = 2;
+
+/// Terminate the process once the timeout elapses.
+///
+/// The watchdog runs on a dedicated thread, so that it fires even if the async tasks are wedged.
+pub fn watchdog(timeout: Duration) {
+    std::thread::spawn(move || {
+        std::thread::sleep(timeout);
+
+        error!("shutdown not completed within {timeout:?}, terminating the process");
+
+        std::process::exit(WATCHDOG_EXIT_CODE);
+    });
+}
 
 #[cfg(unix)]
 /// Shut down the application in case a SIGTERM or SIGINT is received.
@@ -49,3 +68,82 @@ pub fn shutdown() -> eyre::Result<impl std::future::Future<Output = ()>> {
         }
     }))
 }
+
+#[cfg(unix)]
+/// Pause the streams when a SIGUSR1 is received and resume them on SIGUSR2
+///
+/// The returned receiver is `true` while the streams are paused.
+pub fn pause_signals() -> eyre::Result<watch::Receiver<bool>> {
+    use color_eyre::eyre::WrapErr;
+    use tokio::signal::unix::SignalKind;
+
+    let mut pause = tokio::signal::unix::signal(SignalKind::user_defined1())
+        .wrap_err("couldn't create SIGUSR1 listener")?;
+    let mut resume = tokio::signal::unix::signal(SignalKind::user_defined2())
+        .wrap_err("couldn't create SIGUSR2 listener")?;
+
+    let (tx, rx) = watch::channel(false);
+
+    tokio::spawn(async move {
+        loop {
+            let paused = tokio::select! {
+                Some(()) = pause.recv() => true,
+                Some(()) = resume.recv() => false,
+                else => break,
+            };
+
+            info!(
+                "{} received, {} the streams",
+                if paused { "SIGUSR1" } else { "SIGUSR2" },
+                if paused { "pausing" } else { "resuming" }
+            );
+
+            if tx.send(paused).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+#[cfg(not(unix))]
+/// Signals aren't supported, so the streams are never paused locally
+pub fn pause_signals() -> eyre::Result<watch::Receiver<bool>> {
+    let (_, rx) = watch::channel(false);
+
+    Ok(rx)
+}
+
+#[cfg(unix)]
+/// Request a rotation of the credentials when a SIGHUP is received
+///
+/// The returned sender counts the requests, so that the remote commands can request them too.
+pub fn rotation_signals() -> eyre::Result<watch::Sender<u64>> {
+    use color_eyre::eyre::WrapErr;
+    use tokio::signal::unix::SignalKind;
+
+    let mut hangup = tokio::signal::unix::signal(SignalKind::hangup())
+        .wrap_err("couldn't create SIGHUP listener")?;
+
+    let (tx, _) = watch::channel(0);
+    let rotation = tx.clone();
+
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received, rotating the credentials");
+
+            crate::rotation::request_rotation(&rotation);
+        }
+    });
+
+    Ok(tx)
+}
+
+#[cfg(not(unix))]
+/// Signals aren't supported, so the credentials are rotated only by the remote commands
+pub fn rotation_signals() -> eyre::Result<watch::Sender<u64>> {
+    let (tx, _) = watch::channel(0);
+
+    Ok(tx)
+}
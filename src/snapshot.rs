@@ -0,0 +1,102 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Snapshot of the runtime state of the streams, so that after a crash or a restart the signals
+//! continue from where they left off instead of starting again from the current time.
+//!
+//! The snapshot is saved periodically as JSON in a sidecar file of the store directory.
+
+use crate::lanes::LaneCounters;
+use color_eyre::eyre;
+use color_eyre::eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+/// Name of the file, in the store directory, where the snapshot is saved
+pub const SNAPSHOT_FILE: &str = "snapshot.json";
+
+/// Runtime state of a sensor
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SensorSnapshot {
+    /// Input of the math function
+    pub value: f64,
+    /// Whether the value was above the alarm threshold
+    pub alarm_active: bool,
+}
+
+/// Runtime state of the streams
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// State of each sensor, by sensor id
+    pub sensors: BTreeMap<String, SensorSnapshot>,
+    /// Messages sent and failed on each lane
+    pub counters: LaneCounters,
+}
+
+impl Snapshot {
+    /// Load the snapshot saved in a file, none if missing
+    pub async fn load(path: &Path) -> eyre::Result<Option<Self>> {
+        match tokio::fs::read_to_string(path).await {
+            Ok(content) => serde_json::from_str(&content)
+                .map(Some)
+                .wrap_err_with(|| format!("invalid snapshot in {}", path.display())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err).wrap_err_with(|| format!("couldn't read {}", path.display())),
+        }
+    }
+
+    /// Save the snapshot, replacing the file atomically
+    pub async fn save(&self, path: &Path) -> eyre::Result<()> {
+        let content =
+            serde_json::to_string_pretty(self).wrap_err("couldn't serialize the snapshot")?;
+
+        let tmp = path.with_extension("json.tmp");
+
+        tokio::fs::write(&tmp, content)
+            .await
+            .wrap_err_with(|| format!("couldn't write {}", tmp.display()))?;
+
+        tokio::fs::rename(&tmp, path)
+            .await
+            .wrap_err_with(|| format!("couldn't replace {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lanes::Lane;
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let dir = std::env::temp_dir().join(format!("snapshot-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join(SNAPSHOT_FILE);
+
+        assert_eq!(Snapshot::load(&path).await.unwrap(), None);
+
+        let mut snapshot = Snapshot::default();
+        snapshot.sensors.insert(
+            "test".to_string(),
+            SensorSnapshot {
+                value: 42.5,
+                alarm_active: true,
+            },
+        );
+        snapshot.counters.record(Lane::Telemetry, true);
+        snapshot.counters.record(Lane::Critical, false);
+        snapshot.save(&path).await.unwrap();
+
+        assert_eq!(Snapshot::load(&path).await.unwrap(), Some(snapshot));
+
+        tokio::fs::write(&path, "not json").await.unwrap();
+        assert!(Snapshot::load(&path).await.is_err());
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+}
@@ -0,0 +1,78 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Status of the streamer, periodically published so that the test devices can be monitored
+//! through Astarte only.
+
+use crate::lanes::LaneCounter;
+use astarte_device_sdk::AstarteType;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Device owned object interface the status is published on
+pub const STATUS_INTERFACE: &str = "org.astarte-platform.stream-rust-test.Status";
+
+/// Snapshot of the status of the streamer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Status {
+    /// Time elapsed since the stream started, on the simulated clock
+    pub uptime: Duration,
+    /// Messages sent on every lane
+    pub messages: LaneCounter,
+    /// Last error encountered by the streamer
+    pub last_error: Option<String>,
+    /// Math function currently generating the data
+    pub generator: String,
+    /// Times the connection was replaced, re-attaching to the Message Hub or failing over
+    pub reattachments: u64,
+}
+
+impl Status {
+    /// Fields of the object sent on the [`STATUS_INTERFACE`]
+    pub fn fields(&self) -> HashMap<String, AstarteType> {
+        let long = |value: u64| AstarteType::LongInteger(i64::try_from(value).unwrap_or(i64::MAX));
+
+        HashMap::from([
+            ("uptime".to_string(), long(self.uptime.as_secs())),
+            ("messagesSent".to_string(), long(self.messages.sent)),
+            ("messagesFailed".to_string(), long(self.messages.failed)),
+            (
+                "lastError".to_string(),
+                AstarteType::String(self.last_error.clone().unwrap_or_default()),
+            ),
+            (
+                "generator".to_string(),
+                AstarteType::String(self.generator.clone()),
+            ),
+            ("reattachments".to_string(), long(self.reattachments)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_status_fields() {
+        let status = Status {
+            uptime: Duration::from_millis(61_500),
+            messages: LaneCounter { sent: 3, failed: 1 },
+            last_error: None,
+            generator: "sin".to_string(),
+            reattachments: 2,
+        };
+
+        let fields = status.fields();
+        assert_eq!(fields.len(), 6);
+        assert_eq!(fields["uptime"], AstarteType::LongInteger(61));
+        assert_eq!(fields["messagesSent"], AstarteType::LongInteger(3));
+        assert_eq!(fields["messagesFailed"], AstarteType::LongInteger(1));
+        assert_eq!(fields["lastError"], AstarteType::String(String::new()));
+        assert_eq!(fields["generator"], AstarteType::String("sin".to_string()));
+        assert_eq!(fields["reattachments"], AstarteType::LongInteger(2));
+    }
+}
@@ -0,0 +1,302 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Store of the device properties and of the data retained while offline.
+//!
+//! The SQLite store persists them in the store directory, while the volatile one keeps them in
+//! memory, losing them once the device stops, e.g. for ephemeral CI runs.
+//!
+//! The Astarte Device SDK doesn't expose its SQLite connections, so the pragmas are set on a
+//! connection to the database once the SDK opened it. The write-ahead log is persisted in the
+//! database and used by the connections of the SDK too, while the other journal modes and the
+//! synchronous level only last for the connection setting them.
+
+use astarte_device_sdk::store::memory::MemoryStore;
+use astarte_device_sdk::store::{PropertyStore, SqliteStore, StoreCapabilities};
+use clap::ValueEnum;
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, WrapErr};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use rusqlite::Connection;
+use serde::Deserialize;
+use std::fmt::Display;
+use std::path::Path;
+use tracing::debug;
+
+/// Default name of the SQLite database in the store directory, the one of the Astarte Device SDK
+pub const DEFAULT_SQLITE_FILE: &str = "prop-cache.db";
+
+/// Type of the device store
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum StoreType {
+    /// SQLite database in the store directory
+    #[default]
+    Sqlite,
+    /// In memory store, without the stored retention
+    Volatile,
+}
+
+/// Journal mode of the SQLite database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalMode {
+    /// Rollback journal deleted at the end of each transaction
+    Delete,
+    /// Rollback journal truncated at the end of each transaction
+    Truncate,
+    /// Rollback journal invalidated, without deleting it, at the end of each transaction
+    Persist,
+    /// Rollback journal kept in memory
+    Memory,
+    /// Write-ahead log
+    Wal,
+    /// No journal, a crash can corrupt the database
+    Off,
+}
+
+/// Synchronization of the SQLite database with the disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Synchronous {
+    /// Never wait for the data to reach the disk
+    Off,
+    /// Wait for the data to reach the disk at the critical moments only
+    Normal,
+    /// Wait for the data to reach the disk at every transaction
+    Full,
+    /// Like full, also syncing the directory of the rollback journal
+    Extra,
+}
+
+impl Display for JournalMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = self
+            .to_possible_value()
+            .expect("no skipped journal mode")
+            .get_name()
+            .to_uppercase();
+
+        write!(f, "{mode}")
+    }
+}
+
+impl Display for Synchronous {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = self
+            .to_possible_value()
+            .expect("no skipped synchronous level")
+            .get_name()
+            .to_uppercase();
+
+        write!(f, "{level}")
+    }
+}
+
+/// Options of the SQLite store, the missing ones keep the defaults of the Astarte Device SDK
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, clap::Args)]
+pub struct SqliteOptions {
+    /// Name of the SQLite database file in the store directory, so that multiple devices can share
+    /// the directory
+    #[clap(long = "sqlite-file", env = "ASTARTE_SQLITE_FILE")]
+    pub file: Option<String>,
+    /// Journal mode of the SQLite database
+    #[clap(
+        long = "sqlite-journal-mode",
+        env = "ASTARTE_SQLITE_JOURNAL_MODE",
+        value_enum
+    )]
+    pub journal_mode: Option<JournalMode>,
+    /// Synchronization of the SQLite database with the disk
+    #[clap(
+        long = "sqlite-synchronous",
+        env = "ASTARTE_SQLITE_SYNCHRONOUS",
+        value_enum
+    )]
+    pub synchronous: Option<Synchronous>,
+}
+
+impl SqliteOptions {
+    /// Take the missing options from the other ones
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            file: self.file.or(other.file),
+            journal_mode: self.journal_mode.or(other.journal_mode),
+            synchronous: self.synchronous.or(other.synchronous),
+        }
+    }
+
+    /// Keys of the options set
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &'static str> {
+        [
+            ("sqlite.file", self.file.is_some()),
+            ("sqlite.journal_mode", self.journal_mode.is_some()),
+            ("sqlite.synchronous", self.synchronous.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(key, set)| set.then_some(key))
+    }
+
+    /// Path of the database in the store directory
+    pub(crate) fn database(&self, dir: &Path) -> eyre::Result<std::path::PathBuf> {
+        let file = self.file.as_deref().unwrap_or(DEFAULT_SQLITE_FILE);
+
+        ensure!(
+            !file.is_empty() && Path::new(file).file_name() == Some(file.as_ref()),
+            "the SQLite file {file} must be a file name, without directories"
+        );
+
+        Ok(dir.join(file))
+    }
+
+    /// Statements setting the pragmas, none if there is nothing to set
+    fn pragmas(&self) -> Option<String> {
+        let mut pragmas = Vec::new();
+
+        // the synchronous level first, since the journal mode fails on the read only connections
+        if let Some(synchronous) = self.synchronous {
+            pragmas.push(format!("PRAGMA synchronous = {synchronous};"));
+        }
+
+        if let Some(journal_mode) = self.journal_mode {
+            pragmas.push(format!("PRAGMA journal_mode = {journal_mode};"));
+        }
+
+        (!pragmas.is_empty()).then(|| pragmas.join(" "))
+    }
+
+    /// Apply the pragmas to the given database
+    fn apply_pragmas(&self, database: &Path) -> eyre::Result<()> {
+        let Some(pragmas) = self.pragmas() else {
+            return Ok(());
+        };
+
+        debug!("applying the SQLite pragmas {pragmas:?}");
+
+        Connection::open(database)
+            .and_then(|connection| connection.execute_batch(&pragmas))
+            .wrap_err_with(|| {
+                format!(
+                    "couldn't apply the SQLite pragmas to {}",
+                    database.display()
+                )
+            })
+    }
+}
+
+/// Store opened by the device when connecting
+pub trait DeviceStore: PropertyStore + StoreCapabilities + Sized {
+    /// Open the store in the writable directory of the device
+    fn open<'a>(dir: &'a Path, options: &'a SqliteOptions) -> BoxFuture<'a, eyre::Result<Self>>;
+}
+
+impl DeviceStore for SqliteStore {
+    fn open<'a>(dir: &'a Path, options: &'a SqliteOptions) -> BoxFuture<'a, eyre::Result<Self>> {
+        async move {
+            let database = options.database(dir)?;
+
+            let store = SqliteStore::connect_db(&database).await.wrap_err_with(|| {
+                format!("couldn't open the SQLite store {}", database.display())
+            })?;
+
+            options.apply_pragmas(&database)?;
+
+            Ok(store)
+        }
+        .boxed()
+    }
+}
+
+impl DeviceStore for MemoryStore {
+    fn open<'a>(_dir: &'a Path, _options: &'a SqliteOptions) -> BoxFuture<'a, eyre::Result<Self>> {
+        futures::future::ok(MemoryStore::new()).boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use astarte_device_sdk::interface::def::Ownership;
+    use astarte_device_sdk::store::StoredProp;
+    use astarte_device_sdk::AstarteType;
+
+    #[tokio::test]
+    async fn test_volatile_store() {
+        let store = MemoryStore::open(Path::new("/nonexistent"), &SqliteOptions::default())
+            .await
+            .unwrap();
+
+        assert!(store.get_retention().is_none());
+
+        store
+            .store_prop(StoredProp {
+                interface: "com.test.Properties",
+                path: "/value",
+                value: &AstarteType::Integer(42),
+                interface_major: 0,
+                ownership: Ownership::Device,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(store.load_all_props().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_options() {
+        let dir = std::env::temp_dir().join(format!("sqlite-options-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let options = SqliteOptions {
+            file: Some("device-1.db".to_string()),
+            journal_mode: Some(JournalMode::Wal),
+            synchronous: Some(Synchronous::Normal),
+        };
+
+        assert_eq!(
+            options.pragmas().unwrap(),
+            "PRAGMA synchronous = NORMAL; PRAGMA journal_mode = WAL;"
+        );
+        assert_eq!(SqliteOptions::default().pragmas(), None);
+
+        let store = SqliteStore::open(&dir, &options).await.unwrap();
+        assert!(dir.join("device-1.db").exists());
+        store
+            .store_prop(StoredProp {
+                interface: "com.test.Properties",
+                path: "/value",
+                value: &AstarteType::Integer(42),
+                interface_major: 0,
+                ownership: Ownership::Device,
+            })
+            .await
+            .unwrap();
+        // the connection of the SDK writes to the write-ahead log
+        assert!(dir.join("device-1.db-wal").exists());
+        drop(store);
+
+        // the pragmas of each database are independent
+        let other = SqliteOptions {
+            file: Some("device-2.db".to_string()),
+            journal_mode: Some(JournalMode::Delete),
+            synchronous: Some(Synchronous::Full),
+        };
+        SqliteStore::open(&dir, &other).await.unwrap();
+        assert!(dir.join("device-2.db").exists());
+        assert!(!dir.join("device-2.db-wal").exists());
+
+        for file in ["", "../device.db", "dir/device.db"] {
+            let options = SqliteOptions {
+                file: Some(file.to_string()),
+                ..Default::default()
+            };
+
+            assert!(options.database(&dir).is_err(), "{file}");
+        }
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+}
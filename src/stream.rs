@@ -0,0 +1,3561 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Stream of data sent to Astarte, configurable at runtime through Astarte.
+
+use crate::churn::{Churn, ChurnEvent};
+use crate::cli::{Config, MAX_BURST_SIZE};
+use crate::clock::SimClock;
+use crate::connection_state::{ConnectionDiagnostics, ConnectionObserver, ConnectionState};
+use crate::correlation::Correlation;
+use crate::degradation::Degradation;
+use crate::diagnostics::{Diagnostics, Transport, DIAGNOSTICS_INTERFACE};
+use crate::duration;
+use crate::duty_cycle::DutyCycle;
+use crate::exec::ExecFunction;
+use crate::extra_stream::{self, ExtraStream};
+use crate::introspection::{IntrospectionCommand, INTROSPECTION_COMMANDS_INTERFACE};
+use crate::jitter::Jitter;
+use crate::lanes::{Lane, LaneCounters};
+use crate::latency::LatencyStats;
+use crate::logging::SUMMARY_TARGET;
+use crate::math::{BaseValue, MathFunction};
+use crate::offset::SensorOffset;
+use crate::output::{OutputConfig, OutputType, MAX_BLOB_SIZE};
+use crate::path::EndpointPath;
+use crate::path_template::PathTemplate;
+use crate::privacy::PrivacyNoise;
+use crate::ramp_up::RampUp;
+use crate::rate_limit::RateLimiter;
+use crate::reliability::{Reliability, ReliabilityProbe, RELIABILITY_INTERFACE};
+use crate::remote_config::{RemoteConfig, REMOTE_CONFIG_FILE};
+use crate::rotation::{ConnectionCommand, CONNECTION_COMMANDS_INTERFACE};
+use crate::sampler::Sampler;
+use crate::schedule::Schedule;
+use crate::sensor_id::{addresses, is_pattern};
+use crate::snapshot::{SensorSnapshot, Snapshot, SNAPSHOT_FILE};
+use crate::status::{Status, STATUS_INTERFACE};
+use crate::supervisor::RestartPolicy;
+use crate::timestamp::Timestamps;
+use crate::type_fuzz::TypeFuzzer;
+use crate::validation::ValidationSchema;
+use crate::window::{self, ActiveWindow};
+use astarte_device_sdk::chrono::{DateTime, Utc};
+use astarte_device_sdk::client::{ClientDisconnect, RecvError};
+use astarte_device_sdk::introspection::DynamicIntrospection;
+use astarte_device_sdk::properties::PropAccess;
+use astarte_device_sdk::{AstarteType, Client, DeviceEvent, Value};
+use clap::ValueEnum;
+use color_eyre::eyre;
+use color_eyre::eyre::{bail, ensure, eyre, OptionExt, WrapErr};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+use std::fmt::Display;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{mpsc, watch};
+use tokio::time::{Interval, MissedTickBehavior};
+use tracing::{debug, debug_span, error, info, warn, Instrument};
+
+/// Server owned interface used to update the stream configuration
+pub const COMMANDS_INTERFACE: &str = "org.astarte-platform.stream-rust-test.Commands";
+/// Server owned properties interface used to update the stream configuration
+pub const CONFIG_PROPERTIES_INTERFACE: &str =
+    "org.astarte-platform.stream-rust-test.ConfigProperties";
+/// Device owned interface where the rejected updates are published
+pub const VALIDATION_ERRORS_INTERFACE: &str =
+    "org.astarte-platform.stream-rust-test.ValidationErrors";
+/// Device owned properties interface where the effective configuration is published
+pub const STREAM_CONFIG_INTERFACE: &str = "org.astarte-platform.stream-rust-test.StreamConfig";
+/// Device owned properties interface periodically set and unset
+pub const LIFECYCLE_PROPERTIES_INTERFACE: &str =
+    "org.astarte-platform.stream-rust-test.LifecycleProperties";
+/// Device owned properties interface where the metadata of the sensors is published
+pub const AVAILABLE_SENSORS_INTERFACE: &str =
+    "org.astarte-platform.genericsensors.AvailableSensors";
+/// Server owned properties interface enabling the sensors and setting their sampling period
+pub const SAMPLING_RATE_INTERFACE: &str = "org.astarte-platform.genericsensors.SamplingRate";
+/// Device owned interface where the critical events are published
+pub const EVENTS_INTERFACE: &str = "org.astarte-platform.stream-rust-test.Events";
+
+/// State of the stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamState {
+    /// The stream is sending data
+    #[default]
+    On,
+    /// The stream is paused
+    Off,
+}
+
+impl Display for StreamState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamState::On => write!(f, "on"),
+            StreamState::Off => write!(f, "off"),
+        }
+    }
+}
+
+/// Configuration of the stream, updatable at runtime
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    /// State of the stream
+    state: StreamState,
+    /// Interface explicitly set by the user
+    interface: Option<String>,
+    /// Math function used to generate the data
+    math_function: MathFunction,
+    /// Time to wait between two samples
+    interval: Duration,
+    /// Scale for the generation of the data
+    scale: f64,
+    /// Conversion of the generated values
+    output: OutputConfig,
+    /// Samples sent back-to-back every interval
+    burst_size: usize,
+    /// Send the missed samples right after, to maintain the requested rate
+    catch_up: bool,
+}
+
+impl From<&Config> for StreamConfig {
+    fn from(cfg: &Config) -> Self {
+        Self {
+            state: StreamState::default(),
+            interface: cfg.stream.interface_datastream_do.clone(),
+            math_function: cfg.stream.math_function.clone(),
+            interval: cfg.stream.sample_interval(),
+            scale: cfg.stream.scale,
+            output: cfg.stream.output.clone(),
+            burst_size: cfg.stream.burst_size,
+            catch_up: cfg.stream.rate.is_some(),
+        }
+    }
+}
+
+impl StreamConfig {
+    /// Current value of every parameter updatable from Astarte
+    pub fn parameters(&self) -> [StreamConfigUpdate; 7] {
+        [
+            StreamConfigUpdate::State(self.state),
+            StreamConfigUpdate::MathFunction(self.math_function.clone()),
+            StreamConfigUpdate::Interval(self.interval),
+            StreamConfigUpdate::Scale(self.scale),
+            StreamConfigUpdate::OutputType(self.output.output_type),
+            StreamConfigUpdate::BlobSize(self.output.blob_size),
+            StreamConfigUpdate::BurstSize(self.burst_size),
+        ]
+    }
+
+    /// Current value of a parameter updatable from Astarte, by name
+    pub fn parameter(&self, parameter: &str) -> Option<StreamConfigUpdate> {
+        self.parameters()
+            .into_iter()
+            .find(|update| update.parameter() == parameter)
+    }
+
+    /// Set the value of a parameter
+    fn set(&mut self, update: StreamConfigUpdate) {
+        match update {
+            StreamConfigUpdate::State(state) => self.state = state,
+            StreamConfigUpdate::MathFunction(function) => self.math_function = function,
+            StreamConfigUpdate::Interval(interval) => self.interval = interval,
+            StreamConfigUpdate::Scale(scale) => self.scale = scale,
+            StreamConfigUpdate::OutputType(output_type) => self.output.output_type = output_type,
+            StreamConfigUpdate::BlobSize(size) => self.output.blob_size = size,
+            StreamConfigUpdate::BurstSize(size) => self.burst_size = size,
+        }
+    }
+
+    /// Create the ticker of the samples, catching up on the missed ticks if requested
+    fn ticker(&self) -> Interval {
+        let mut ticker = periodic_ticker(self.interval);
+
+        if self.catch_up {
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+        }
+
+        ticker
+    }
+
+    /// Check if the stream is sending data
+    pub fn is_on(&self) -> bool {
+        self.state == StreamState::On
+    }
+
+    /// Name of the interface to send data to
+    ///
+    /// Defaults to the bundled interface matching the output type.
+    pub fn interface(&self) -> &str {
+        self.interface
+            .as_deref()
+            .unwrap_or_else(|| self.output.default_interface())
+    }
+}
+
+/// Update of a stream parameter received from Astarte
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "parameter", content = "value", rename_all = "snake_case")]
+pub enum StreamConfigUpdate {
+    /// Start or stop the stream
+    State(StreamState),
+    /// Change the math function
+    MathFunction(MathFunction),
+    /// Change the time between two samples, persisted in milliseconds
+    Interval(#[serde(with = "duration::as_millis")] Duration),
+    /// Change the scale
+    Scale(f64),
+    /// Change the output type
+    OutputType(OutputType),
+    /// Change the size in bytes of the binary blobs
+    BlobSize(usize),
+    /// Change the number of samples sent back-to-back every interval
+    BurstSize(usize),
+}
+
+impl StreamConfigUpdate {
+    /// Name of the updated parameter, as in the endpoints of the [`COMMANDS_INTERFACE`]
+    pub fn parameter(&self) -> &'static str {
+        match self {
+            StreamConfigUpdate::State(_) => "toggle",
+            StreamConfigUpdate::MathFunction(_) => "function",
+            StreamConfigUpdate::Interval(_) => "interval_btw_samples",
+            StreamConfigUpdate::Scale(_) => "scale",
+            StreamConfigUpdate::OutputType(_) => "output_type",
+            StreamConfigUpdate::BlobSize(_) => "blob_size",
+            StreamConfigUpdate::BurstSize(_) => "burst_size",
+        }
+    }
+
+    /// Value of the updated parameter, as in the mappings of the [`STREAM_CONFIG_INTERFACE`]
+    pub fn value(&self) -> AstarteType {
+        // the names accepted by the commands, not the human readable ones
+        fn name(value: &impl ValueEnum) -> String {
+            value
+                .to_possible_value()
+                .map(|value| value.get_name().to_string())
+                .unwrap_or_default()
+        }
+
+        match self {
+            StreamConfigUpdate::State(state) => AstarteType::Boolean(*state == StreamState::On),
+            StreamConfigUpdate::MathFunction(function) => AstarteType::String(name(function)),
+            StreamConfigUpdate::Interval(interval) => {
+                AstarteType::LongInteger(i64::try_from(interval.as_millis()).unwrap_or(i64::MAX))
+            }
+            StreamConfigUpdate::Scale(scale) => AstarteType::Double(*scale),
+            StreamConfigUpdate::OutputType(output_type) => AstarteType::String(name(output_type)),
+            StreamConfigUpdate::BlobSize(size) | StreamConfigUpdate::BurstSize(size) => {
+                AstarteType::LongInteger(i64::try_from(*size).unwrap_or(i64::MAX))
+            }
+        }
+    }
+
+    /// Parse the update of a parameter from the value received from Astarte
+    pub fn try_from_value(parameter: &str, value: AstarteType) -> eyre::Result<Self> {
+        let update = match parameter {
+            "toggle" => {
+                let on = bool::try_from(value).wrap_err("toggle must be a boolean")?;
+
+                StreamConfigUpdate::State(if on {
+                    StreamState::On
+                } else {
+                    StreamState::Off
+                })
+            }
+            "function" => {
+                let function = String::try_from(value).wrap_err("function must be a string")?;
+
+                MathFunction::from_str(&function, true)
+                    .map(StreamConfigUpdate::MathFunction)
+                    .map_err(|_| eyre!("unknown math function {function}"))?
+            }
+            // the duration of the interval endpoint, or the milliseconds of the original one
+            "interval" | "interval_btw_samples" => {
+                if let AstarteType::String(interval) = value {
+                    duration::parse_positive_millis(&interval)
+                        .map(|interval| {
+                            StreamConfigUpdate::Interval(Duration::from_millis(interval))
+                        })
+                        .wrap_err_with(|| format!("invalid {parameter} {interval}"))?
+                } else {
+                    let interval =
+                        i64::try_from(value).wrap_err("interval_btw_samples must be an integer")?;
+
+                    match u64::try_from(interval) {
+                        Ok(interval) if interval > 0 => {
+                            StreamConfigUpdate::Interval(Duration::from_millis(interval))
+                        }
+                        _ => bail!("interval_btw_samples must be positive, received {interval}"),
+                    }
+                }
+            }
+            "scale" => f64::try_from(value)
+                .map(StreamConfigUpdate::Scale)
+                .wrap_err("scale must be a double")?,
+            "output_type" => {
+                let output_type =
+                    String::try_from(value).wrap_err("output_type must be a string")?;
+
+                OutputType::from_str(&output_type, true)
+                    .map(StreamConfigUpdate::OutputType)
+                    .map_err(|_| eyre!("unknown output type {output_type}"))?
+            }
+            "blob_size" => {
+                let size = i64::try_from(value).wrap_err("blob_size must be an integer")?;
+
+                match usize::try_from(size) {
+                    Ok(size) if size <= MAX_BLOB_SIZE => StreamConfigUpdate::BlobSize(size),
+                    Ok(_) => bail!("blob_size must be at most {MAX_BLOB_SIZE}, received {size}"),
+                    Err(_) => bail!("blob_size must not be negative, received {size}"),
+                }
+            }
+            "burst_size" => {
+                let size = i64::try_from(value).wrap_err("burst_size must be an integer")?;
+
+                match usize::try_from(size) {
+                    Ok(size) if (1..=MAX_BURST_SIZE).contains(&size) => {
+                        StreamConfigUpdate::BurstSize(size)
+                    }
+                    _ => {
+                        bail!("burst_size must be between 1 and {MAX_BURST_SIZE}, received {size}")
+                    }
+                }
+            }
+            _ => bail!("unknown parameter {parameter}"),
+        };
+
+        Ok(update)
+    }
+
+    /// Parse the update of a parameter from the value received on the [`SAMPLING_RATE_INTERFACE`]
+    pub fn try_from_sampling_rate(mapping: &str, value: AstarteType) -> eyre::Result<Self> {
+        let update = match mapping {
+            "enable" => {
+                let on = bool::try_from(value).wrap_err("enable must be a boolean")?;
+
+                StreamConfigUpdate::State(if on {
+                    StreamState::On
+                } else {
+                    StreamState::Off
+                })
+            }
+            "samplingPeriod" => {
+                let period = i32::try_from(value).wrap_err("samplingPeriod must be an integer")?;
+
+                match u64::try_from(period) {
+                    Ok(period) if period > 0 => {
+                        StreamConfigUpdate::Interval(Duration::from_secs(period))
+                    }
+                    _ => bail!("samplingPeriod must be positive, received {period}"),
+                }
+            }
+            _ => bail!("unknown sampling rate mapping {mapping}"),
+        };
+
+        Ok(update)
+    }
+
+    /// Name of the parameter updated by a mapping of the [`SAMPLING_RATE_INTERFACE`]
+    pub fn sampling_rate_parameter(mapping: &str) -> Option<&'static str> {
+        match mapping {
+            "enable" => Some("toggle"),
+            "samplingPeriod" => Some("interval_btw_samples"),
+            _ => None,
+        }
+    }
+}
+
+/// Create a ticker firing every period, starting one period from now
+///
+/// If a tick is missed because sending took too long, the next ones are delayed instead of being
+/// sent in a burst.
+pub(crate) fn periodic_ticker(period: Duration) -> Interval {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    ticker
+}
+
+/// Schedule the next tick of a stream, applying the ramp-up and the jitter to its interval
+///
+/// A ticker catching up on the missed ticks keeps its schedule, since rescheduling it would drop
+/// them.
+fn schedule_next(
+    ticker: &mut Interval,
+    ramp_up: Option<RampUp>,
+    jitter: Option<Jitter>,
+    elapsed: Duration,
+) {
+    let catch_up = ticker.missed_tick_behavior() == MissedTickBehavior::Burst;
+
+    if catch_up || (ramp_up.is_none() && jitter.is_none()) {
+        return;
+    }
+
+    let mut interval = ticker.period();
+
+    if let Some(ramp_up) = ramp_up {
+        interval = ramp_up.interval(interval, elapsed);
+    }
+
+    if let Some(jitter) = jitter {
+        interval = jitter.apply(interval);
+    }
+
+    ticker.reset_after(interval);
+}
+
+/// Check that the exec math function has a command to compute it, if any function is `exec`
+fn ensure_exec<'a>(
+    exec: &Option<ExecFunction>,
+    mut functions: impl Iterator<Item = &'a MathFunction>,
+) -> eyre::Result<()> {
+    ensure!(
+        exec.is_some() || !functions.any(|function| *function == MathFunction::Exec),
+        "the exec math function requires an exec command"
+    );
+
+    Ok(())
+}
+
+/// Wait for the turn of a telemetry message, if the rate is limited
+async fn throttle(rate_limiter: &mut Option<RateLimiter>) {
+    if let Some(rate_limiter) = rate_limiter {
+        rate_limiter.acquire().await;
+    }
+}
+
+/// Wait for a watched state to change, never completing if missing or closed
+async fn changed<T: Clone>(state: &mut Option<watch::Receiver<T>>) -> T {
+    let Some(rx) = state else {
+        return std::future::pending().await;
+    };
+
+    if rx.changed().await.is_err() {
+        return std::future::pending().await;
+    }
+
+    let state = rx.borrow_and_update().clone();
+    state
+}
+
+/// Wait for the client of a new connection, never completing if missing or closed
+async fn next_client<C>(clients: &mut Option<mpsc::Receiver<C>>) -> C {
+    let Some(rx) = clients else {
+        return std::future::pending().await;
+    };
+
+    match rx.recv().await {
+        Some(client) => client,
+        None => std::future::pending().await,
+    }
+}
+
+/// Wait for the next tick of an optional ticker, never completing if missing
+async fn tick(ticker: &mut Option<Interval>) {
+    match ticker {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Send data on a lane, recording the outcome in the lane counters
+async fn send_on<C>(
+    client: &C,
+    counters: &mut LaneCounters,
+    lane: Lane,
+    interface: &str,
+    path: &str,
+    data: AstarteType,
+    timestamp: Option<DateTime<Utc>>,
+) -> eyre::Result<()>
+where
+    C: Client + Send + Sync,
+{
+    let res = match timestamp {
+        Some(timestamp) => {
+            client
+                .send_with_timestamp(interface, path, data.clone(), timestamp)
+                .await
+        }
+        None => client.send(interface, path, data.clone()).await,
+    };
+
+    counters.record(lane, res.is_ok());
+
+    res.wrap_err_with(|| format!("couldn't send {lane} data on {interface}{path}"))?;
+
+    debug!(
+        event_type = "data_sent",
+        %lane,
+        interface,
+        path,
+        value = ?data,
+        "data sent"
+    );
+
+    Ok(())
+}
+
+/// Publish the current value of a parameter of a sensor on the [`STREAM_CONFIG_INTERFACE`]
+async fn publish_parameter<C>(
+    client: &C,
+    sensor_id: &str,
+    update: &StreamConfigUpdate,
+) -> eyre::Result<()>
+where
+    C: Client + Send + Sync,
+{
+    let path = format!("/{sensor_id}/{}", update.parameter());
+
+    client
+        .send(STREAM_CONFIG_INTERFACE, &path, update.value())
+        .await
+        .wrap_err_with(|| format!("couldn't publish {STREAM_CONFIG_INTERFACE}{path}"))
+}
+
+/// Publish the name and, if known, the unit of a sensor on the [`AVAILABLE_SENSORS_INTERFACE`]
+async fn publish_sensor<C>(
+    client: &C,
+    sensor_id: &str,
+    name: &str,
+    unit: Option<&str>,
+) -> eyre::Result<()>
+where
+    C: Client + Send + Sync,
+{
+    let metadata = std::iter::once(("name", name)).chain(unit.map(|unit| ("unit", unit)));
+
+    for (mapping, value) in metadata {
+        let path = format!("/{sensor_id}/{mapping}");
+
+        client
+            .send(
+                AVAILABLE_SENSORS_INTERFACE,
+                &path,
+                AstarteType::String(value.to_string()),
+            )
+            .await
+            .wrap_err_with(|| format!("couldn't publish {AVAILABLE_SENSORS_INTERFACE}{path}"))?;
+    }
+
+    Ok(())
+}
+
+/// Send object aggregated data on a lane, recording the outcome in the lane counters
+async fn send_object_on<C>(
+    client: &C,
+    counters: &mut LaneCounters,
+    lane: Lane,
+    interface: &str,
+    path: &str,
+    data: HashMap<String, AstarteType>,
+    timestamp: Option<DateTime<Utc>>,
+) -> eyre::Result<()>
+where
+    C: Client + Send + Sync,
+{
+    let res = match timestamp {
+        Some(timestamp) => {
+            client
+                .send_object_with_timestamp(interface, path, data.clone(), timestamp)
+                .await
+        }
+        None => client.send_object(interface, path, data.clone()).await,
+    };
+
+    counters.record(lane, res.is_ok());
+
+    res.wrap_err_with(|| format!("couldn't send {lane} object on {interface}{path}"))?;
+
+    debug!(
+        event_type = "object_sent",
+        %lane,
+        interface,
+        path,
+        value = ?data,
+        "object sent"
+    );
+
+    Ok(())
+}
+
+/// Wait for the next sample of any of the sensors, returning its index
+///
+/// The sensors due at the same time are queued, so that the ones with the highest priority are
+/// sampled first and aren't starved by the others. Never completes if there are no sensors.
+async fn next_sample(sensors: &mut [Sensor], due: &mut Vec<usize>) -> usize {
+    if due.is_empty() {
+        if sensors.is_empty() {
+            return std::future::pending().await;
+        }
+
+        let ticks = sensors
+            .iter_mut()
+            .map(|sensor| Box::pin(sensor.ticker.tick()));
+
+        let (_, idx, _) = futures::future::select_all(ticks).await;
+
+        due.push(idx);
+
+        for (i, sensor) in sensors.iter_mut().enumerate() {
+            if i != idx && sensor.ticker.tick().now_or_never().is_some() {
+                due.push(i);
+            }
+        }
+    }
+
+    // highest priority first, then in order of index
+    let pos = due
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, &idx)| (sensors[idx].priority, Reverse(idx)))
+        .map(|(pos, _)| pos)
+        .unwrap_or_default();
+
+    due.remove(pos)
+}
+
+/// Initial state of the sensors, used to create them at startup and from Astarte
+#[derive(Debug)]
+struct SensorTemplate {
+    /// CLI configuration, assigning the math functions to the sensors
+    cfg: Config,
+    stream_cfg: StreamConfig,
+    base_value: BaseValue,
+    schedule: Option<Schedule>,
+    timestamps: Option<Timestamps>,
+}
+
+impl SensorTemplate {
+    /// Build the sensors configured at startup, generating their ids from the device id
+    fn initial_sensors(&self, device_id: &str) -> Vec<Sensor> {
+        let ids = self.cfg.stream.sensor_id();
+
+        (0..self.cfg.stream.sensors() as usize)
+            .map(|n| self.build(n, ids.render(n, device_id), false))
+            .collect()
+    }
+
+    /// Build the n-th sensor, with the given id
+    fn build(&self, n: usize, id: String, dynamic: bool) -> Sensor {
+        let mut stream_cfg = self.stream_cfg.clone();
+        stream_cfg.math_function = self.cfg.stream.sensor_function(n, &id).clone();
+        stream_cfg.interval = self.cfg.stream.sensor_interval(n, &id);
+        stream_cfg.scale = self.cfg.stream.sensor_scale(n, &id);
+        let mut base_value = self.base_value;
+        base_value.set_scale(stream_cfg.scale);
+        let duty_cycle = self.cfg.stream.sensor_duty_cycle(n, &id);
+        let priority = self.cfg.stream.sensor_priority(n, &id);
+        let correlation = self
+            .cfg
+            .stream
+            .correlations
+            .iter()
+            .find(|correlation| correlation.sensor == id)
+            .cloned();
+
+        Sensor {
+            id,
+            initial_cfg: stream_cfg.clone(),
+            ticker: stream_cfg.ticker(),
+            stream_cfg,
+            base_value,
+            schedule: self.schedule.clone(),
+            offset: SensorOffset::default(),
+            mappings: self
+                .cfg
+                .stream
+                .mappings
+                .iter()
+                .map(|mapping| (mapping.clone(), SensorOffset::default()))
+                .collect(),
+            alarm_active: false,
+            timestamps: self.timestamps,
+            priority,
+            failures: 0,
+            duty_cycle,
+            overrides: HashSet::new(),
+            sampler: None,
+            correlation,
+            last_value: None,
+            dynamic,
+        }
+    }
+}
+
+/// Simulated sensor, with its own configuration, generator state and timer
+#[derive(Debug)]
+struct Sensor {
+    /// Id used in the endpoints of the interfaces
+    id: String,
+    stream_cfg: StreamConfig,
+    initial_cfg: StreamConfig,
+    base_value: BaseValue,
+    schedule: Option<Schedule>,
+    offset: SensorOffset,
+    /// Mappings the data is sent to, with the offsets deriving their values
+    ///
+    /// The first mapping uses the sensor offset, so it sends the values of the stream.
+    mappings: Vec<(String, SensorOffset)>,
+    alarm_active: bool,
+    timestamps: Option<Timestamps>,
+    ticker: Interval,
+    /// Sensors with a higher priority are sampled first when due at the same time
+    priority: u8,
+    /// Consecutive samples that failed to be sent
+    failures: u32,
+    duty_cycle: Option<DutyCycle>,
+    /// Parameters updated by addressing the sensor, not overridden by the group updates
+    overrides: HashSet<&'static str>,
+    /// Custom generator, taking precedence over the math function
+    sampler: Option<Box<dyn Sampler>>,
+    /// Derives the values from the ones of another sensor, taking precedence over the generator
+    correlation: Option<Correlation>,
+    /// Last value computed, used by the correlated sensors
+    last_value: Option<f64>,
+    /// Created from Astarte, and deleted when its enable property is unset
+    dynamic: bool,
+}
+
+impl Sensor {
+    /// Reset the failures after a successful sample
+    fn recovered(&mut self) {
+        if self.failures > 0 {
+            info!(
+                "sensor {} recovered after {} failures",
+                self.id, self.failures
+            );
+
+            self.failures = 0;
+        }
+    }
+
+    /// Name of the generator of the values
+    fn generator(&self) -> String {
+        if let Some(correlation) = &self.correlation {
+            return format!("correlated to {}", correlation.source);
+        }
+
+        match &self.sampler {
+            Some(sampler) => sampler.name(),
+            None => self.stream_cfg.math_function.to_string(),
+        }
+    }
+
+    /// Derive the offsets of the sensor and of its mappings from the id of the device
+    ///
+    /// Without an offset seed, the primary sensor sends the plain values of the math function,
+    /// while the other sensors are offset so that they send independent values.
+    fn derive_offsets(&mut self, device_id: &str, seed: Option<u64>, primary: bool) {
+        self.offset = match seed {
+            Some(seed) => SensorOffset::derive(device_id, &self.id, seed),
+            None if primary => SensorOffset::default(),
+            None => SensorOffset::derive(device_id, &self.id, 0),
+        };
+
+        for (i, (mapping, offset)) in self.mappings.iter_mut().enumerate() {
+            *offset = if i == 0 {
+                self.offset
+            } else {
+                let mapping = format!("{}/{mapping}", self.id);
+
+                SensorOffset::derive(device_id, &mapping, seed.unwrap_or_default())
+            };
+        }
+    }
+
+    /// Restart the timer if the interval between samples changed, applying it from now
+    fn update_ticker(&mut self) {
+        if self.ticker.period() != self.stream_cfg.interval {
+            self.ticker = self.stream_cfg.ticker();
+        }
+    }
+
+    /// Restore the initial value of a parameter
+    ///
+    /// The initial value is not validated against the schema, since it was set on the device.
+    fn restore_cfg(&mut self, parameter: &str) -> eyre::Result<()> {
+        let update = self
+            .initial_cfg
+            .parameter(parameter)
+            .ok_or_else(|| eyre!("unknown parameter {parameter}"))?;
+
+        self.apply_cfg(update);
+
+        Ok(())
+    }
+
+    /// Change the initial value of a parameter, from a reloaded configuration
+    ///
+    /// The new value is applied unless the parameter was updated from Astarte, and in that case
+    /// it's restored once the update is unset. Returns whether the current value changed.
+    fn reconfigure(&mut self, update: StreamConfigUpdate) -> bool {
+        let parameter = update.parameter();
+        let initial = self.initial_cfg.parameter(parameter);
+
+        if initial.as_ref() == Some(&update) {
+            return false;
+        }
+
+        self.initial_cfg.set(update.clone());
+
+        if self.stream_cfg.parameter(parameter) != initial {
+            debug!(
+                "sensor {} {parameter} updated from Astarte, keeping it",
+                self.id
+            );
+
+            return false;
+        }
+
+        self.apply_cfg(update);
+
+        true
+    }
+
+    /// Apply an update to the stream configuration
+    fn apply_cfg(&mut self, update: StreamConfigUpdate) {
+        let id = &self.id;
+
+        match &update {
+            StreamConfigUpdate::State(state) => {
+                info!("sensor {id} stream state set to {state}");
+            }
+            StreamConfigUpdate::MathFunction(function) => {
+                if self.schedule.take().is_some() {
+                    info!("sensor {id} schedule disabled by the math function update");
+                }
+
+                if let Some(sampler) = self.sampler.take() {
+                    info!(
+                        "sensor {id} {} sampler replaced by the math function update",
+                        sampler.name()
+                    );
+                }
+
+                info!("sensor {id} math function set to {function}");
+            }
+            StreamConfigUpdate::Interval(interval) => {
+                info!("sensor {id} interval between samples set to {interval:?}");
+            }
+            StreamConfigUpdate::Scale(scale) => {
+                info!("sensor {id} scale set to {scale}");
+                self.base_value.set_scale(*scale);
+            }
+            StreamConfigUpdate::OutputType(output_type) => {
+                info!("sensor {id} output type set to {output_type}");
+            }
+            StreamConfigUpdate::BlobSize(size) => {
+                info!("sensor {id} binary blob size set to {size} bytes");
+            }
+            StreamConfigUpdate::BurstSize(size) => {
+                info!("sensor {id} burst size set to {size} samples");
+            }
+        }
+
+        self.stream_cfg.set(update);
+    }
+}
+
+/// Stream manager
+///
+/// It handles the following operations:
+/// - send the data of the simulated sensors to Astarte
+/// - receive new stream configurations from Astarte
+pub struct StreamManager {
+    /// Id of the primary sensor, also used in the endpoints of the device-wide events
+    sensor_id: String,
+    sensors: Vec<Sensor>,
+    /// Indexes of the sensors due to be sampled
+    due: Vec<usize>,
+    template: SensorTemplate,
+    device_id: String,
+    /// Configuration received from Astarte, persisted across restarts
+    remote_config: Option<RemoteConfig>,
+    /// File where the runtime state is saved, if the store directory is known
+    snapshot_path: Option<PathBuf>,
+    /// Interval between two snapshots of the runtime state
+    snapshot: Option<Duration>,
+    exec: Option<ExecFunction>,
+    schema: ValidationSchema,
+    clock: SimClock,
+    active_windows: Vec<ActiveWindow>,
+    jitter: Option<Jitter>,
+    ramp_up: Option<RampUp>,
+    /// Cap of the aggregate telemetry rate
+    rate_limiter: Option<RateLimiter>,
+    /// Samples left to send before stopping, if limited
+    remaining: Option<u64>,
+    restart_policy: RestartPolicy,
+    /// Configurations reloaded from the `config.toml` file
+    config_reload: Option<watch::Receiver<Arc<Config>>>,
+    /// Local pause state, set through the signals
+    pause_signal: Option<watch::Receiver<bool>>,
+    /// Paused locally, without changing the configuration
+    paused: bool,
+    /// Requests of rotating the credentials, received from Astarte
+    credentials_rotation: Option<watch::Sender<u64>>,
+    /// State of the connection to Astarte, observed from the SDK
+    connection_signal: Option<watch::Receiver<ConnectionState>>,
+    connection_state: ConnectionState,
+    /// Observer of the connection of this device, recording the data sent and received
+    activity: Option<ConnectionObserver>,
+    /// Pause the streams while the connection is down, instead of piling the data in the store
+    pause_while_disconnected: bool,
+    /// Times the connection was replaced, re-attaching to the Message Hub or failing over
+    reattachments: u64,
+    /// Whether the last sample was in an active window
+    window_active: bool,
+    heartbeat: Option<Duration>,
+    alarm_threshold: Option<f64>,
+    counters: LaneCounters,
+    latency: LatencyStats,
+    offset_seed: Option<u64>,
+    noise: Option<PrivacyNoise>,
+    property_cycle: Option<Duration>,
+    reliability: Option<Duration>,
+    reliability_probe: ReliabilityProbe,
+    /// Interval between two changes of the sensor population
+    churn_interval: Option<Duration>,
+    churn: Churn,
+    /// Maximum sensors created from Astarte at the same time
+    remote_max_sensors: usize,
+    /// Edge cases sent instead of the generated values
+    type_fuzzer: Option<TypeFuzzer>,
+    /// Interval between two status reports
+    status: Option<Duration>,
+    /// Interval between two diagnostics reports
+    diagnostics: Option<Duration>,
+    /// Transport of the connection, published in the diagnostics reports
+    transport: Option<Transport>,
+    connection_diagnostics: Option<watch::Receiver<ConnectionDiagnostics>>,
+    /// Last error encountered, published in the status reports
+    last_error: Option<String>,
+    property_sets: i64,
+    property_set: bool,
+    degradation: Option<Degradation>,
+    extra_streams: Vec<ExtraStream>,
+    path_template: PathTemplate,
+    /// Name of the sensors, defaulting to their math function and output type
+    sensor_name: Option<String>,
+    sensor_unit: Option<String>,
+}
+
+impl StreamManager {
+    /// Build the stream manager from the CLI configuration
+    ///
+    /// The base value is derived from the time elapsed since `now`.
+    pub async fn new(cfg: Config, now: SystemTime) -> eyre::Result<Self> {
+        let mut stream_cfg = StreamConfig::from(&cfg);
+        stream_cfg.output.load_string_file().await?;
+
+        let base_value = BaseValue::try_from_system_time(now, cfg.stream.scale)?;
+
+        let schedule = match &cfg.stream.schedule {
+            Some(path) => {
+                let schedule = Schedule::from_toml(path).await?;
+                debug!("parsed schedule: {:#?}", schedule);
+                Some(schedule)
+            }
+            None => None,
+        };
+
+        let exec = cfg
+            .stream
+            .exec_command
+            .as_deref()
+            .map(|cmd| ExecFunction::new(cmd, Duration::from_millis(cfg.stream.exec_timeout)))
+            .transpose()?;
+
+        ensure_exec(
+            &exec,
+            cfg.stream
+                .math_functions()
+                .chain(schedule.iter().flat_map(Schedule::functions)),
+        )?;
+
+        let schema = match &cfg.stream.validation_schema {
+            Some(path) => {
+                let schema = ValidationSchema::from_toml(path).await?;
+                debug!("parsed validation schema: {:#?}", schema);
+                schema
+            }
+            None => ValidationSchema::default(),
+        };
+
+        let noise = PrivacyNoise::new(&cfg.stream.privacy)?;
+
+        if let Some(noise) = &noise {
+            debug!("privacy noise: {noise:?}");
+        }
+
+        ensure!(
+            !cfg.stream.mappings.is_empty(),
+            "at least one mapping is required"
+        );
+
+        for mapping in &cfg.stream.mappings {
+            ensure!(
+                !mapping.is_empty() && !mapping.contains('/'),
+                "invalid mapping {mapping:?}"
+            );
+        }
+
+        let timestamps = Timestamps::new(&cfg.stream.timestamp)?;
+
+        if let Some(timestamps) = &timestamps {
+            debug!("explicit timestamps: {timestamps:?}");
+        }
+
+        let extra_streams = cfg
+            .stream
+            .extra_streams
+            .iter()
+            .map(|extra| ExtraStream::new(extra.clone(), &cfg.stream.output, now))
+            .collect::<eyre::Result<Vec<ExtraStream>>>()?;
+
+        let template = SensorTemplate {
+            cfg: cfg.clone(),
+            stream_cfg,
+            base_value,
+            schedule,
+            timestamps,
+        };
+
+        let mut manager = Self {
+            sensor_id: String::new(),
+            sensors: Vec::new(),
+            due: Vec::new(),
+            template,
+            device_id: String::new(),
+            remote_config: None,
+            snapshot_path: None,
+            snapshot: cfg.stream.snapshot_interval.map(Duration::from_millis),
+            exec,
+            schema,
+            clock: SimClock::new(cfg.stream.clock_speedup),
+            active_windows: cfg.stream.active_window.clone(),
+            jitter: Jitter::new(cfg.stream.jitter_percent),
+            rate_limiter: cfg.stream.max_rate.map(|rate| {
+                let burst = cfg.stream.max_rate_burst.unwrap_or(rate);
+
+                RateLimiter::new(rate, burst, tokio::time::Instant::now())
+            }),
+            remaining: cfg.stream.count,
+            config_reload: None,
+            pause_signal: None,
+            paused: false,
+            credentials_rotation: None,
+            connection_signal: None,
+            connection_state: ConnectionState::default(),
+            activity: None,
+            pause_while_disconnected: cfg.stream.pause_while_disconnected,
+            reattachments: 0,
+            restart_policy: RestartPolicy::new(
+                Duration::from_millis(cfg.stream.restart_backoff),
+                Duration::from_millis(cfg.stream.restart_backoff_max),
+                cfg.stream.max_restarts,
+            ),
+            ramp_up: RampUp::new(
+                Duration::from_secs(cfg.stream.ramp_up),
+                cfg.stream.ramp_up_start_percent,
+            ),
+            window_active: true,
+            heartbeat: cfg.stream.heartbeat_interval.map(Duration::from_millis),
+            alarm_threshold: cfg.stream.alarm_threshold,
+            counters: LaneCounters::default(),
+            latency: LatencyStats::default(),
+            offset_seed: cfg.stream.offset_seed,
+            noise,
+            property_cycle: cfg
+                .stream
+                .property_cycle_interval
+                .map(Duration::from_millis),
+            reliability: cfg.stream.reliability_interval.map(Duration::from_millis),
+            reliability_probe: ReliabilityProbe::default(),
+            churn_interval: cfg.stream.churn_interval.map(Duration::from_millis),
+            churn: Churn::new(cfg.stream.churn_max_sensors as usize),
+            remote_max_sensors: cfg.stream.remote_max_sensors as usize,
+            status: cfg.stream.status_interval.map(Duration::from_millis),
+            diagnostics: cfg.stream.diagnostics_interval.map(Duration::from_millis),
+            transport: None,
+            connection_diagnostics: None,
+            type_fuzzer: cfg.stream.fuzz_types.then(TypeFuzzer::default),
+            last_error: None,
+            property_sets: 0,
+            property_set: false,
+            degradation: Degradation::new(&cfg.stream.degradation),
+            extra_streams,
+            path_template: cfg.stream.path_template.clone(),
+            sensor_name: cfg.stream.sensor_name.clone(),
+            sensor_unit: cfg.stream.sensor_unit.clone(),
+        };
+
+        // generated again from the device id once known
+        manager.set_device_id("");
+
+        Ok(manager)
+    }
+
+    /// Generate the sensors from the id of the device, deriving their ids and offsets
+    ///
+    /// The state of the sensors is reset, so it must be called before handling the stream.
+    pub fn set_device_id(&mut self, device_id: &str) {
+        self.device_id = device_id.to_string();
+
+        let samplers: Vec<_> = self
+            .sensors
+            .drain(..)
+            .filter(|sensor| !sensor.dynamic)
+            .map(|sensor| sensor.sampler)
+            .collect();
+
+        self.sensors = self.template.initial_sensors(device_id);
+        self.due.clear();
+
+        for (sensor, sampler) in self.sensors.iter_mut().zip(samplers) {
+            sensor.sampler = sampler;
+        }
+        self.sensor_id = self
+            .sensors
+            .first()
+            .map(|sensor| sensor.id.clone())
+            .unwrap_or_default();
+
+        for (i, sensor) in self.sensors.iter_mut().enumerate() {
+            sensor.derive_offsets(device_id, self.offset_seed, i == 0);
+
+            debug!("sensor {} offset: {:?}", sensor.id, sensor.offset);
+        }
+    }
+
+    /// Pause and resume every stream locally when the state of the receiver changes
+    ///
+    /// Unlike the toggle, the local pause isn't published to Astarte.
+    pub fn pause_on(&mut self, paused: watch::Receiver<bool>) {
+        self.paused = *paused.borrow();
+        self.pause_signal = Some(paused);
+    }
+
+    /// Apply the configurations reloaded from the `config.toml` file received on the receiver
+    pub fn reload_config_on(&mut self, configs: watch::Receiver<Arc<Config>>) {
+        self.config_reload = Some(configs);
+    }
+
+    /// Request the rotations of the credentials received from Astarte on the given sender
+    pub fn rotate_credentials_on(&mut self, rotation: watch::Sender<u64>) {
+        self.credentials_rotation = Some(rotation);
+    }
+
+    /// Track the state of the connection to Astarte, logging its transitions
+    pub fn connection_state_on(&mut self, state: watch::Receiver<ConnectionState>) {
+        self.connection_state = *state.borrow();
+        self.connection_signal = Some(state);
+    }
+
+    /// Record on the observer of the connection of this device the data sent and received through
+    /// the client, showing that the connection is alive
+    pub fn record_activity_on(&mut self, observer: ConnectionObserver) {
+        self.activity = Some(observer);
+    }
+
+    /// Record the activity of the connection, if observed
+    fn record_activity(&self) {
+        if let Some(observer) = &self.activity {
+            observer.record_activity();
+        }
+    }
+
+    /// Publish the diagnostics of the connection of this device through the given transport
+    pub fn diagnostics_on(
+        &mut self,
+        transport: Transport,
+        diagnostics: watch::Receiver<ConnectionDiagnostics>,
+    ) {
+        self.transport = Some(transport);
+        self.connection_diagnostics = Some(diagnostics);
+    }
+
+    /// Check if the streams are paused, either locally or while disconnected
+    fn is_paused(&self) -> bool {
+        self.paused
+            || (self.pause_while_disconnected
+                && self.connection_state != ConnectionState::Connected)
+    }
+
+    /// Generate the values of the n-th sensor with a custom sampler
+    pub(crate) fn set_sampler(&mut self, n: usize, sampler: Box<dyn Sampler>) -> eyre::Result<()> {
+        let count = self.sensors.len();
+        let sensor = self
+            .sensors
+            .get_mut(n)
+            .ok_or_else(|| eyre!("no sensor {n} for the sampler, only {count} simulated"))?;
+
+        info!(
+            "sensor {} generated by the {} sampler",
+            sensor.id,
+            sampler.name()
+        );
+        sensor.sampler = Some(sampler);
+
+        Ok(())
+    }
+
+    /// Create a sensor requested from Astarte, in the state set by the update
+    ///
+    /// Returns the index of the new sensor, or fails once the maximum sensors created from Astarte
+    /// are present.
+    fn add_sensor(&mut self, sensor_id: &str, state: StreamConfigUpdate) -> eyre::Result<usize> {
+        self.schema.validate(&state)?;
+
+        let created = self.sensors.iter().filter(|sensor| sensor.dynamic).count();
+
+        ensure!(
+            created < self.remote_max_sensors,
+            "couldn't create sensor {sensor_id}, {created} sensors already created from Astarte"
+        );
+
+        let n = self.sensors.len();
+        let mut sensor = self.template.build(n, sensor_id.to_string(), true);
+
+        sensor.derive_offsets(&self.device_id, self.offset_seed, false);
+        sensor.apply_cfg(state);
+
+        info!(
+            "sensor {sensor_id} created with {} math function",
+            sensor.stream_cfg.math_function
+        );
+
+        self.sensors.push(sensor);
+
+        Ok(n)
+    }
+
+    /// Create a sensor requested from Astarte and publish its configuration and metadata
+    async fn create_sensor<C>(
+        &mut self,
+        client: &C,
+        sensor_id: &str,
+        state: StreamConfigUpdate,
+    ) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        let idx = self.add_sensor(sensor_id, state)?;
+        let sensor = &self.sensors[idx];
+
+        for parameter in sensor.stream_cfg.parameters() {
+            publish_parameter(client, sensor_id, &parameter).await?;
+        }
+
+        publish_sensor(
+            client,
+            sensor_id,
+            &self.name_of(sensor),
+            self.sensor_unit.as_deref(),
+        )
+        .await
+    }
+
+    /// Delete a sensor created from Astarte, unsetting its configuration and metadata
+    async fn delete_sensor<C>(&mut self, client: &C, sensor_id: &str) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        if let Some(idx) = self
+            .sensors
+            .iter()
+            .position(|sensor| sensor.id == sensor_id)
+        {
+            self.sensors.remove(idx);
+
+            // the indexes of the following sensors are shifted
+            self.due.retain(|&due| due != idx);
+            self.due
+                .iter_mut()
+                .filter(|due| **due > idx)
+                .for_each(|due| *due -= 1);
+        }
+
+        info!("sensor {sensor_id} deleted");
+
+        self.churn.forget(sensor_id);
+
+        if let Some(remote_config) = &mut self.remote_config {
+            remote_config.forget_sensor(sensor_id);
+
+            self.save_remote_config().await;
+        }
+
+        let parameters = self.template.stream_cfg.parameters();
+        let config = parameters
+            .iter()
+            .map(|parameter| (STREAM_CONFIG_INTERFACE, parameter.parameter()));
+        let metadata = [
+            (AVAILABLE_SENSORS_INTERFACE, "name"),
+            (AVAILABLE_SENSORS_INTERFACE, "unit"),
+        ];
+
+        for (interface, mapping) in config.chain(metadata) {
+            let path = format!("/{sensor_id}/{mapping}");
+
+            client
+                .unset(interface, &path)
+                .await
+                .wrap_err_with(|| format!("couldn't unset {interface}{path}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a reloaded configuration, without dropping the connection
+    ///
+    /// The configured sensors get the new math function, interval and scale, and are added or
+    /// deleted to match the new number. The sensors created from Astarte are left untouched.
+    async fn reload_config<C>(&mut self, client: &C, cfg: &Config) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        ensure_exec(&self.exec, cfg.stream.math_functions())?;
+
+        self.template.cfg = cfg.clone();
+
+        let count = cfg.stream.sensors() as usize;
+        let configured: Vec<String> = self
+            .sensors
+            .iter()
+            .filter(|sensor| !sensor.dynamic)
+            .map(|sensor| sensor.id.clone())
+            .collect();
+
+        for sensor_id in configured.iter().skip(count) {
+            self.delete_sensor(client, sensor_id).await?;
+        }
+
+        let mut updated = Vec::new();
+
+        for (n, sensor) in self
+            .sensors
+            .iter_mut()
+            .filter(|sensor| !sensor.dynamic)
+            .enumerate()
+        {
+            let updates = [
+                StreamConfigUpdate::MathFunction(cfg.stream.sensor_function(n, &sensor.id).clone()),
+                StreamConfigUpdate::Interval(cfg.stream.sensor_interval(n, &sensor.id)),
+                StreamConfigUpdate::Scale(cfg.stream.sensor_scale(n, &sensor.id)),
+            ];
+
+            for update in updates {
+                if sensor.reconfigure(update.clone()) {
+                    updated.push((sensor.id.clone(), update));
+                }
+            }
+        }
+
+        for (sensor_id, update) in updated {
+            publish_parameter(client, &sensor_id, &update).await?;
+        }
+
+        let ids = cfg.stream.sensor_id();
+
+        for n in configured.len()..count {
+            let sensor_id = ids.render(n, &self.device_id);
+
+            if self.has_sensor(&sensor_id) {
+                warn!("sensor {sensor_id} already created from Astarte, not added");
+
+                continue;
+            }
+
+            let mut sensor = self.template.build(n, sensor_id.clone(), false);
+            sensor.derive_offsets(&self.device_id, self.offset_seed, n == 0);
+
+            info!("sensor {sensor_id} added by the reloaded configuration");
+
+            for parameter in sensor.stream_cfg.parameters() {
+                publish_parameter(client, &sensor_id, &parameter).await?;
+            }
+
+            publish_sensor(
+                client,
+                &sensor_id,
+                &self.name_of(&sensor),
+                self.sensor_unit.as_deref(),
+            )
+            .await?;
+
+            self.sensors.push(sensor);
+        }
+
+        Ok(())
+    }
+
+    /// Change the sensor population, creating a new sensor or deleting a churned one
+    async fn churn_sensors<C>(&mut self, client: &C) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        let event = self.churn.next(&self.sensor_id);
+
+        info!("{event}");
+
+        match event {
+            ChurnEvent::Appear(sensor_id) => {
+                let state = StreamConfigUpdate::State(StreamState::On);
+
+                self.create_sensor(client, &sensor_id, state).await
+            }
+            ChurnEvent::Disappear(sensor_id) if self.is_dynamic(&sensor_id) => {
+                self.delete_sensor(client, &sensor_id).await
+            }
+            // already deleted from Astarte
+            ChurnEvent::Disappear(_) => Ok(()),
+        }
+    }
+
+    /// Persist the configuration received from Astarte in the store directory
+    ///
+    /// The configuration already persisted is applied when handling the stream.
+    pub async fn persist_remote_config(&mut self, store_directory: &Path) -> eyre::Result<()> {
+        let remote_config = RemoteConfig::load(store_directory.join(REMOTE_CONFIG_FILE)).await?;
+
+        debug!(
+            "remote configuration persisted in {}",
+            remote_config.path().display()
+        );
+
+        self.remote_config = Some(remote_config);
+
+        Ok(())
+    }
+
+    /// Save the snapshots of the runtime state in the store directory
+    ///
+    /// The last snapshot is restored when handling the stream.
+    pub fn persist_snapshot(&mut self, store_directory: &Path) {
+        if self.snapshot.is_some() {
+            self.snapshot_path = Some(store_directory.join(SNAPSHOT_FILE));
+        }
+    }
+
+    /// Restore the runtime state from the last snapshot
+    ///
+    /// The sensors missing from the snapshot keep their initial state.
+    async fn restore_snapshot(&mut self) -> eyre::Result<()> {
+        let Some(path) = &self.snapshot_path else {
+            return Ok(());
+        };
+
+        let Some(snapshot) = Snapshot::load(path).await? else {
+            return Ok(());
+        };
+
+        let mut restored = 0;
+
+        for sensor in &mut self.sensors {
+            let Some(state) = snapshot.sensors.get(&sensor.id) else {
+                continue;
+            };
+
+            sensor.base_value = BaseValue::new(state.value, sensor.stream_cfg.scale);
+            sensor.alarm_active = state.alarm_active;
+            restored += 1;
+        }
+
+        self.counters = snapshot.counters;
+
+        info!(
+            "restored {restored} sensors from the snapshot in {}",
+            path.display()
+        );
+
+        Ok(())
+    }
+
+    /// Save a snapshot of the runtime state, reporting the errors
+    async fn save_snapshot(&mut self) {
+        let Some(path) = &self.snapshot_path else {
+            return;
+        };
+
+        let snapshot = Snapshot {
+            sensors: self
+                .sensors
+                .iter()
+                .map(|sensor| {
+                    let state = SensorSnapshot {
+                        value: sensor.base_value.value(),
+                        alarm_active: sensor.alarm_active,
+                    };
+
+                    (sensor.id.clone(), state)
+                })
+                .collect(),
+            counters: self.counters,
+        };
+
+        if let Err(err) = snapshot.save(path).await {
+            self.report_error(format!("{err:#}"));
+        }
+    }
+
+    /// Apply the persisted configuration received from Astarte
+    ///
+    /// It's applied after the stored properties, since it also contains the last updates of the
+    /// properties.
+    fn apply_remote_config(&mut self) {
+        let Some(remote_config) = &self.remote_config else {
+            return;
+        };
+
+        let updates: Vec<(String, StreamConfigUpdate)> = remote_config
+            .updates()
+            .map(|(sensor_id, update)| (sensor_id.to_string(), update.clone()))
+            .collect();
+
+        for (sensor_id, update) in updates {
+            let parameter = update.parameter();
+
+            if let Err(err) = self.update_cfg(&sensor_id, update) {
+                warn!("ignored persisted {parameter} update of sensor {sensor_id}: {err:#}");
+            }
+        }
+    }
+
+    /// Save the persisted configuration, reporting the errors
+    async fn save_remote_config(&mut self) {
+        let Some(remote_config) = &self.remote_config else {
+            return;
+        };
+
+        if let Err(err) = remote_config.save().await {
+            self.report_error(format!("{err:#}"));
+        }
+    }
+
+    /// Check if a sensor with the given id was created from Astarte
+    fn is_dynamic(&self, sensor_id: &str) -> bool {
+        self.sensors
+            .iter()
+            .any(|sensor| sensor.id == sensor_id && sensor.dynamic)
+    }
+
+    /// Name of a sensor, defaulting to its math function and output type
+    fn name_of(&self, sensor: &Sensor) -> String {
+        self.sensor_name.clone().unwrap_or_else(|| {
+            let generator = match &sensor.sampler {
+                Some(sampler) => sampler.name(),
+                None => sensor.initial_cfg.math_function.to_string(),
+            };
+
+            format!("{generator} {}", sensor.initial_cfg.output.output_type)
+        })
+    }
+
+    /// Check if a sensor with the given id is simulated
+    fn has_sensor(&self, sensor_id: &str) -> bool {
+        self.sensor(sensor_id).is_some()
+    }
+
+    /// Get the sensor with the given id
+    fn sensor(&self, sensor_id: &str) -> Option<&Sensor> {
+        self.sensors.iter().find(|sensor| sensor.id == sensor_id)
+    }
+
+    /// Get the sensors addressed by an id, every sensor for a broadcast id or the sensors of a
+    /// group
+    fn addressed<'a>(&'a self, sensor_id: &'a str) -> impl Iterator<Item = &'a Sensor> {
+        self.sensors
+            .iter()
+            .filter(move |sensor| addresses(sensor_id, &sensor.id))
+    }
+
+    /// Get the sensors addressed by an id, failing if the sensor is unknown
+    fn addressed_mut(&mut self, sensor_id: &str) -> eyre::Result<Vec<&mut Sensor>> {
+        let sensors: Vec<&mut Sensor> = self
+            .sensors
+            .iter_mut()
+            .filter(|sensor| addresses(sensor_id, &sensor.id))
+            .collect();
+
+        ensure!(
+            is_pattern(sensor_id) || !sensors.is_empty(),
+            "unknown sensor {sensor_id}"
+        );
+
+        Ok(sensors)
+    }
+
+    /// Suspend a sensor that failed to send a sample, restarting it after a backoff
+    ///
+    /// Fails if the sensor exceeded the maximum number of consecutive restarts.
+    fn restart_sensor(&mut self, idx: usize, err: eyre::Report) -> eyre::Result<()> {
+        let sensor = &mut self.sensors[idx];
+        sensor.failures += 1;
+
+        if !self.restart_policy.can_restart(sensor.failures) {
+            return Err(err.wrap_err(format!(
+                "sensor {} failed {} consecutive times",
+                sensor.id, sensor.failures
+            )));
+        }
+
+        let backoff = self.restart_policy.backoff(sensor.failures);
+        sensor.ticker.reset_after(backoff);
+
+        let id = sensor.id.clone();
+        self.report_error(format!(
+            "sensor {id} failed, restarting in {backoff:?}: {err:#}"
+        ));
+
+        Ok(())
+    }
+
+    /// Suspend an additional stream that failed to send a sample, restarting it after a backoff
+    ///
+    /// Fails if the stream exceeded the maximum number of consecutive restarts.
+    fn restart_extra(&mut self, idx: usize, err: eyre::Report) -> eyre::Result<()> {
+        let extra = &mut self.extra_streams[idx];
+        let failures = extra.failed();
+
+        if !self.restart_policy.can_restart(failures) {
+            return Err(err.wrap_err(format!(
+                "additional stream {} failed {failures} consecutive times",
+                extra.name()
+            )));
+        }
+
+        let backoff = self.restart_policy.backoff(failures);
+        extra.ticker().reset_after(backoff);
+
+        let name = extra.name();
+        self.report_error(format!(
+            "additional stream {name} failed, restarting in {backoff:?}: {err:#}"
+        ));
+
+        Ok(())
+    }
+
+    /// Check if the current time is in an active window, logging when entering or leaving them
+    fn in_active_window(&mut self) -> bool {
+        let active = window::is_active(&self.active_windows, self.clock.now());
+
+        if active != self.window_active {
+            if active {
+                info!("entered an active window, sending data");
+            } else {
+                info!("left the active windows, data paused");
+            }
+
+            self.window_active = active;
+        }
+
+        active
+    }
+
+    /// Check if any sensor is sending data
+    fn is_on(&self) -> bool {
+        self.sensors.iter().any(|sensor| sensor.stream_cfg.is_on())
+    }
+
+    /// Handle sending and receiving data until the stop future completes
+    ///
+    /// The client is replaced by the ones received after a re-attachment to the Message Hub or a
+    /// failover, if any, instead of exiting once disconnected. When stopped, the client is disconnected,
+    /// flushing the pending data, and a summary is logged.
+    pub async fn handle<C, F>(
+        mut self,
+        mut client: C,
+        mut reattached: Option<mpsc::Receiver<C>>,
+        stop: F,
+    ) -> eyre::Result<()>
+    where
+        C: Client + ClientDisconnect + DynamicIntrospection + PropAccess + Send + Sync,
+        F: Future<Output = ()>,
+    {
+        for sensor in &mut self.sensors {
+            if let Some(schedule) = &sensor.schedule {
+                sensor.stream_cfg.math_function = schedule.function_at(Duration::ZERO).clone();
+            }
+        }
+
+        if let Err(err) = self.load_properties(&client).await {
+            self.report_error(format!(
+                "couldn't load the stored configuration properties: {err:#}"
+            ));
+        }
+
+        self.apply_remote_config();
+
+        if let Err(err) = self.restore_snapshot().await {
+            self.report_error(format!("couldn't restore the snapshot: {err:#}"));
+        }
+
+        for sensor in &self.sensors {
+            info!(
+                "sensor {} sending {} data to Astarte interface {} with {} math function every {:?}",
+                sensor.id,
+                sensor.stream_cfg.output.output_type,
+                sensor.stream_cfg.interface(),
+                sensor.stream_cfg.math_function,
+                sensor.stream_cfg.interval
+            );
+        }
+
+        for sensor in &self.sensors {
+            let Some(correlation) = &sensor.correlation else {
+                continue;
+            };
+
+            if self.has_sensor(&correlation.source) {
+                info!("sensor {} correlated as {correlation}", sensor.id);
+            } else {
+                warn!(
+                    "sensor {} correlated to the missing sensor {}, no data will be sent",
+                    sensor.id, correlation.source
+                );
+            }
+        }
+
+        if self.clock.is_accelerated() {
+            info!(
+                "simulated clock running {}x faster than the wall clock",
+                self.clock.speedup()
+            );
+        }
+
+        if !self.active_windows.is_empty() {
+            let windows: Vec<String> = self.active_windows.iter().map(|w| w.to_string()).collect();
+
+            info!(
+                "sending data only in the active windows {}",
+                windows.join(",")
+            );
+        }
+
+        let parameters: Vec<(String, StreamConfigUpdate)> = self
+            .sensors
+            .iter()
+            .flat_map(|sensor| {
+                sensor
+                    .stream_cfg
+                    .parameters()
+                    .map(|parameter| (sensor.id.clone(), parameter))
+            })
+            .collect();
+
+        for (sensor_id, parameter) in parameters {
+            if let Err(err) = publish_parameter(&client, &sensor_id, &parameter).await {
+                self.report_error(format!("{err:#}"));
+            }
+        }
+
+        self.publish_sensors(&client).await;
+
+        for sensor in &mut self.sensors {
+            sensor.ticker = sensor.stream_cfg.ticker();
+        }
+
+        let mut heartbeat = self.heartbeat.map(periodic_ticker);
+        let mut property_cycle = self.property_cycle.map(periodic_ticker);
+        let mut reliability = self.reliability.map(periodic_ticker);
+        let mut status = self.status.map(periodic_ticker);
+        let mut diagnostics = self.diagnostics.map(periodic_ticker);
+        let mut churn = self.churn_interval.map(periodic_ticker);
+        let mut snapshot = self.snapshot.map(periodic_ticker);
+        let mut stop = std::pin::pin!(stop);
+        let started = tokio::time::Instant::now();
+        let mut sent = self.counters.total().sent;
+
+        if let Some(ramp_up) = &self.ramp_up {
+            info!("ramping up the sample rate over {:?}", ramp_up.warm_up());
+        }
+
+        loop {
+            tokio::select! {
+                _ = &mut stop => break,
+                res = client.recv() => {
+                    match res {
+                        Ok(event) => {
+                            self.record_activity();
+
+                            if let Err(err) = self.receive_data(&client, event).await {
+                                self.report_error(format!("couldn't handle the received data: {err:#}"));
+                            }
+
+                            for sensor in &mut self.sensors {
+                                sensor.update_ticker();
+                            }
+                        }
+                        Err(RecvError::Disconnected) => {
+                            let Some(clients) = &mut reattached else {
+                                return Err(eyre!("disconnected from Astarte"));
+                            };
+
+                            warn!("disconnected from Astarte, waiting for a new connection");
+
+                            tokio::select! {
+                                _ = &mut stop => break,
+                                new = clients.recv() => {
+                                    let new = new.ok_or_eyre("disconnected from Astarte")?;
+
+                                    self.reattach(&mut client, new);
+                                }
+                            }
+                        }
+                        Err(err) => self.report_error(format!("error while receiving data from Astarte: {err}")),
+                    }
+                }
+                idx = next_sample(&mut self.sensors, &mut self.due) => {
+                    let ticker = &mut self.sensors[idx].ticker;
+                    schedule_next(ticker, self.ramp_up, self.jitter, started.elapsed());
+
+                    let span = debug_span!("sample", sensor_id = %self.sensors[idx].id);
+
+                    match self.send_data(&client, idx).instrument(span).await {
+                        Ok(()) => self.sensors[idx].recovered(),
+                        Err(err) => self.restart_sensor(idx, err)?,
+                    }
+
+                    if self.remaining == Some(0) {
+                        info!("sent the requested number of samples, shutting down");
+
+                        break;
+                    }
+                }
+                new = next_client(&mut reattached) => {
+                    self.reattach(&mut client, new);
+                }
+                cfg = changed(&mut self.config_reload) => {
+                    if let Err(err) = self.reload_config(&client, &cfg).await {
+                        self.report_error(format!("couldn't apply the reloaded configuration: {err:#}"));
+                    }
+
+                    for sensor in &mut self.sensors {
+                        sensor.update_ticker();
+                    }
+                }
+                paused = changed(&mut self.pause_signal) => {
+                    if paused != self.paused {
+                        info!("streams {} locally", if paused { "paused" } else { "resumed" });
+
+                        self.paused = paused;
+                    }
+                }
+                state = changed(&mut self.connection_signal) => {
+                    if state != self.connection_state {
+                        info!("connection to Astarte {state}");
+
+                        if self.pause_while_disconnected {
+                            let paused = state != ConnectionState::Connected;
+                            info!("streams {} while {state}", if paused { "paused" } else { "resumed" });
+                        }
+
+                        self.connection_state = state;
+
+                        // report the reconnection right away
+                        if state == ConnectionState::Connected && self.diagnostics.is_some() {
+                            if let Err(err) = self.send_diagnostics(&client).await {
+                                self.report_error(format!("{err:#}"));
+                            }
+                        }
+                    }
+                }
+                _ = tick(&mut heartbeat) => {
+                    self.send_heartbeat(&client).await?;
+                }
+                idx = extra_stream::next_tick(&mut self.extra_streams) => {
+                    let ticker = self.extra_streams[idx].ticker();
+                    schedule_next(ticker, self.ramp_up, self.jitter, started.elapsed());
+
+                    match self.send_extra(&client, idx).await {
+                        Ok(()) => self.extra_streams[idx].recovered(),
+                        Err(err) => self.restart_extra(idx, err)?,
+                    }
+                }
+                _ = tick(&mut property_cycle) => {
+                    if let Err(err) = self.cycle_property(&client).await {
+                        self.report_error(format!("{err:#}"));
+                    }
+                }
+                _ = tick(&mut reliability) => {
+                    self.send_reliability_probe(&client).await;
+                }
+                _ = tick(&mut status) => {
+                    if let Err(err) = self.send_status(&client).await {
+                        self.report_error(format!("{err:#}"));
+                    }
+                }
+                _ = tick(&mut diagnostics) => {
+                    if let Err(err) = self.send_diagnostics(&client).await {
+                        self.report_error(format!("{err:#}"));
+                    }
+                }
+                _ = tick(&mut churn) => {
+                    if let Err(err) = self.churn_sensors(&client).await {
+                        self.report_error(format!("{err:#}"));
+                    }
+                }
+                _ = tick(&mut snapshot) => {
+                    self.save_snapshot().await;
+                }
+            }
+
+            // the data sent since the last iteration shows that the connection is alive
+            let total = self.counters.total().sent;
+
+            if total > sent {
+                self.record_activity();
+                sent = total;
+            }
+        }
+
+        self.save_snapshot().await;
+        self.log_summary();
+
+        client
+            .disconnect()
+            .await
+            .wrap_err("couldn't disconnect from Astarte")
+    }
+
+    /// Replace the client with the one of the new connection, after a re-attachment to the
+    /// Message Hub or a failover
+    fn reattach<C>(&mut self, client: &mut C, new: C) {
+        *client = new;
+        self.reattachments += 1;
+
+        info!(
+            "sending data through the new connection, {} re-attachments",
+            self.reattachments
+        );
+    }
+
+    /// Publish the metadata of the sensors and of the ones of the additional streams
+    ///
+    /// An additional stream sending on the sensor id of an already published sensor is skipped.
+    async fn publish_sensors<C>(&self, client: &C)
+    where
+        C: Client + Send + Sync,
+    {
+        let sensors = self.sensors.iter().map(|sensor| {
+            (
+                sensor.id.as_str(),
+                self.name_of(sensor),
+                self.sensor_unit.as_deref(),
+            )
+        });
+
+        let extra = self.extra_streams.iter().filter_map(|extra| {
+            EndpointPath::parse(extra.path())
+                .ok()
+                .map(|path| (path.sensor_id, extra.name(), None))
+        });
+
+        let mut published = Vec::new();
+
+        for (sensor_id, name, unit) in sensors.chain(extra) {
+            if published.contains(&sensor_id) {
+                continue;
+            }
+
+            published.push(sensor_id);
+
+            match publish_sensor(client, sensor_id, &name, unit).await {
+                Ok(()) => debug!("sensor {sensor_id} published as {name}"),
+                Err(err) => error!("{err:#}"),
+            }
+        }
+    }
+
+    /// Log the data sent and the latency measured since the start
+    fn log_summary(&self) {
+        let elapsed = self.clock.real_elapsed();
+
+        info!(target: SUMMARY_TARGET, "stream stopped after {elapsed:?}, {}", self.counters);
+
+        if !elapsed.is_zero() {
+            let rate = self.counters.total().sent as f64 / elapsed.as_secs_f64();
+
+            info!(target: SUMMARY_TARGET, "sent {rate:.1} messages per second");
+        }
+
+        if self.clock.is_accelerated() {
+            info!(
+                target: SUMMARY_TARGET,
+                "simulated {:?} at {}x speedup",
+                self.clock.elapsed(),
+                self.clock.speedup()
+            );
+        }
+
+        if let Some(summary) = self.latency.summary() {
+            info!(target: SUMMARY_TARGET, "downlink latency distribution: {summary}");
+        }
+
+        if let Some(degradation) = &self.degradation {
+            info!(target: SUMMARY_TARGET, "degradation level {}", degradation.level());
+        }
+
+        if self.reliability.is_some() {
+            info!(target: SUMMARY_TARGET, "reliability probes: {}", self.reliability_probe);
+        }
+
+        if self.reattachments > 0 {
+            info!(target: SUMMARY_TARGET, "re-attached {} times", self.reattachments);
+        }
+    }
+
+    /// Sample a sensor and send its data to Astarte
+    ///
+    /// The value is sent on the telemetry lane only if the stream of the sensor is on, while the
+    /// alarms are sent on the critical lane regardless of the stream state.
+    async fn send_data<C>(&mut self, client: &C, idx: usize) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        if self.is_paused() {
+            return Ok(());
+        }
+
+        let sensor = &mut self.sensors[idx];
+
+        // nothing to send while paused, unless we need to monitor the alarm
+        if !sensor.stream_cfg.is_on() && self.alarm_threshold.is_none() {
+            return Ok(());
+        }
+
+        if let Some(schedule) = &sensor.schedule {
+            let scheduled = schedule.function_at(self.clock.elapsed());
+
+            if *scheduled != sensor.stream_cfg.math_function {
+                info!(
+                    "sensor {} schedule switched math function from {} to {scheduled}",
+                    sensor.id, sensor.stream_cfg.math_function
+                );
+                sensor.stream_cfg.math_function = scheduled.clone();
+
+                let update = StreamConfigUpdate::MathFunction(scheduled.clone());
+                if let Err(err) = publish_parameter(client, &sensor.id, &update).await {
+                    self.report_error(format!("{err:#}"));
+                }
+            }
+        }
+
+        // the samples of a burst are sent back-to-back
+        for _ in 0..self.sensors[idx].stream_cfg.burst_size {
+            if self.remaining == Some(0) {
+                break;
+            }
+
+            let sensor = &self.sensors[idx];
+            let value = self
+                .compute(idx, sensor.base_value.value(), sensor.offset)
+                .await?;
+
+            if let Some(value) = value {
+                self.sensors[idx].last_value = Some(value);
+                self.check_alarm(client, idx, value).await?;
+
+                let sensor = &self.sensors[idx];
+                let on_duty = sensor
+                    .duty_cycle
+                    .map_or(true, |duty_cycle| duty_cycle.is_on(self.clock.elapsed()));
+
+                if sensor.stream_cfg.is_on() && on_duty && self.in_active_window() {
+                    self.send_sample(client, idx, value).await?;
+
+                    if let Some(remaining) = &mut self.remaining {
+                        *remaining -= 1;
+                    }
+                }
+            }
+
+            // update the data to send at the next sample, skipping the decimated samples
+            self.sensors[idx].base_value.advance(self.clock.speedup());
+        }
+
+        Ok(())
+    }
+
+    /// Send a sample of an additional stream, while any sensor is on
+    async fn send_extra<C>(&mut self, client: &C, idx: usize) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        if !self.is_on() || self.is_paused() {
+            return Ok(());
+        }
+
+        let active = self.in_active_window();
+        let elapsed = self.clock.elapsed();
+        let extra = self
+            .extra_streams
+            .get_mut(idx)
+            .ok_or_eyre("additional stream out of bound")?;
+
+        let data = extra.sample(self.clock.speedup())?;
+
+        // the generator keeps advancing while silent
+        if !active || !extra.is_on_duty(elapsed) {
+            return Ok(());
+        }
+
+        throttle(&mut self.rate_limiter).await;
+
+        send_on(
+            client,
+            &mut self.counters,
+            Lane::Telemetry,
+            extra.interface(),
+            extra.path(),
+            data,
+            None,
+        )
+        .await
+    }
+
+    /// Send a sample of a sensor, according to the degradation level if enabled
+    ///
+    /// Without degradation, a send error is returned, so that the sensor is restarted after a
+    /// backoff. Otherwise, the error is only reported and recorded to step down the degradation
+    /// ladder.
+    async fn send_sample<C>(&mut self, client: &C, idx: usize, value: f64) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        let Some(degradation) = &mut self.degradation else {
+            return self.send_telemetry(client, idx, value).await;
+        };
+
+        if !degradation.sample() {
+            return Ok(());
+        }
+
+        let res = self.send_telemetry(client, idx, value).await;
+
+        if let Err(err) = &res {
+            self.report_error(format!("{err:#}"));
+        }
+
+        self.record_outcome(client, res.is_ok()).await;
+
+        Ok(())
+    }
+
+    /// Record the outcome of a send in the degradation ladder, publishing the level changes
+    async fn record_outcome<C>(&mut self, client: &C, success: bool)
+    where
+        C: Client + Send + Sync,
+    {
+        let Some(level) = self
+            .degradation
+            .as_mut()
+            .and_then(|degradation| degradation.record(success))
+        else {
+            return;
+        };
+
+        warn!("degradation level changed to {level}");
+
+        let res = send_on(
+            client,
+            &mut self.counters,
+            Lane::Critical,
+            EVENTS_INTERFACE,
+            &format!("/{}/degradation", self.sensor_id),
+            AstarteType::String(level.to_string()),
+            None,
+        )
+        .await;
+
+        if let Err(err) = res {
+            self.report_error(format!("{err:#}"));
+        }
+    }
+
+    /// Add the privacy noise, if configured, to a sent value
+    fn add_noise(&self, value: f64) -> f64 {
+        match &self.noise {
+            Some(noise) => noise.apply(value),
+            None => value,
+        }
+    }
+
+    /// Send the generated value of a sensor on the telemetry lane, as configured by the output
+    /// options
+    ///
+    /// The privacy noise is added to the sent values only, not to the ones monitored by the alarm.
+    async fn send_telemetry<C>(&mut self, client: &C, idx: usize, value: f64) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        let sensor_id = self.sensors[idx].id.clone();
+
+        if let Some(fuzzer) = &mut self.type_fuzzer {
+            let case = fuzzer.next_case();
+
+            debug!("sending {} edge case", case.description);
+
+            throttle(&mut self.rate_limiter).await;
+
+            return send_on(
+                client,
+                &mut self.counters,
+                Lane::Telemetry,
+                case.interface,
+                &self.path_template.render(&sensor_id, "value"),
+                case.value.clone(),
+                None,
+            )
+            .await
+            .wrap_err_with(|| format!("{} edge case rejected", case.description));
+        }
+
+        let now = self.clock.now();
+        let timestamp = self.sensors[idx]
+            .timestamps
+            .as_mut()
+            .map(|timestamps| timestamps.next(now))
+            .transpose()?;
+
+        if let Some(aggregate) = self.sensors[idx].stream_cfg.output.aggregate {
+            let fields = aggregate.fields(self.add_noise(value))?;
+
+            throttle(&mut self.rate_limiter).await;
+
+            return send_object_on(
+                client,
+                &mut self.counters,
+                Lane::Telemetry,
+                self.sensors[idx].stream_cfg.interface(),
+                &self.path_template.render_object(&sensor_id),
+                fields,
+                timestamp,
+            )
+            .await;
+        }
+
+        for m in 0..self.sensors[idx].mappings.len() {
+            let (mapping, offset) = self.sensors[idx].mappings[m].clone();
+
+            let value = if m == 0 {
+                value
+            } else {
+                let input = self.sensors[idx].base_value.value();
+
+                match self.compute(idx, input, offset).await? {
+                    Some(value) => value,
+                    None => continue,
+                }
+            };
+
+            let data = match self.sensors[idx].stream_cfg.output.array_length {
+                Some(len) => match self.compute_array(idx, value, len, offset).await? {
+                    Some(values) => {
+                        let values: Vec<f64> =
+                            values.into_iter().map(|v| self.add_noise(v)).collect();
+
+                        self.sensors[idx].stream_cfg.output.convert_array(&values)?
+                    }
+                    None => continue,
+                },
+                None => {
+                    let value = self.add_noise(value);
+
+                    self.sensors[idx].stream_cfg.output.convert(value)?
+                }
+            };
+
+            throttle(&mut self.rate_limiter).await;
+
+            send_on(
+                client,
+                &mut self.counters,
+                Lane::Telemetry,
+                self.sensors[idx].stream_cfg.interface(),
+                &self.path_template.render(&sensor_id, &mapping),
+                data,
+                timestamp,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Compute the math function of a sensor on the given input, applying the offset
+    ///
+    /// Returns [`None`] if the exec math function fails, or if the source of a correlated sensor
+    /// has no value yet, to skip the sample.
+    async fn compute(
+        &mut self,
+        idx: usize,
+        input: f64,
+        offset: SensorOffset,
+    ) -> eyre::Result<Option<f64>> {
+        if let Some(correlation) = &self.sensors[idx].correlation {
+            // skipped until the source sensor computes its first value
+            let value = self
+                .sensors
+                .iter()
+                .find(|sensor| sensor.id == correlation.source)
+                .and_then(|sensor| sensor.last_value)
+                .map(|value| correlation.apply(value));
+
+            return Ok(value);
+        }
+
+        let input = offset.input(input);
+
+        if let Some(sampler) = &mut self.sensors[idx].sampler {
+            return Ok(sampler.sample(input).map(|value| offset.output(value)));
+        }
+
+        if let Some(value) = self.sensors[idx].stream_cfg.math_function.compute(input) {
+            return Ok(Some(offset.output(value)));
+        }
+
+        let exec = self
+            .exec
+            .as_mut()
+            .ok_or_eyre("the exec math function requires an exec command")?;
+
+        match exec.compute(input).await {
+            Ok(value) => Ok(Some(offset.output(value))),
+            Err(err) => {
+                self.report_error(format!("couldn't compute the exec math function: {err:#}"));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Compute an array of values spanning a period of the math function of a sensor
+    ///
+    /// The first item is the already computed value of the current sample. Returns [`None`] if an
+    /// item couldn't be computed, to skip the sample.
+    async fn compute_array(
+        &mut self,
+        idx: usize,
+        first: f64,
+        len: usize,
+        offset: SensorOffset,
+    ) -> eyre::Result<Option<Vec<f64>>> {
+        let mut values = Vec::with_capacity(len);
+        values.extend(std::iter::once(first).take(len));
+
+        for i in 1..len {
+            let input = self.sensors[idx].base_value.value() + 2.0 * PI * i as f64 / len as f64;
+
+            let Some(value) = self.compute(idx, input, offset).await? else {
+                return Ok(None);
+            };
+
+            values.push(value);
+        }
+
+        Ok(Some(values))
+    }
+
+    /// Send an alarm on the critical lane when the value of a sensor crosses the alarm threshold
+    async fn check_alarm<C>(&mut self, client: &C, idx: usize, value: f64) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        let Some(threshold) = self.alarm_threshold else {
+            return Ok(());
+        };
+
+        let sensor = &mut self.sensors[idx];
+        let above = value > threshold;
+
+        if above == sensor.alarm_active {
+            return Ok(());
+        }
+
+        sensor.alarm_active = above;
+
+        if above {
+            warn!(
+                "sensor {} alarm raised, value {value} above threshold {threshold}",
+                sensor.id
+            );
+        } else {
+            info!(
+                "sensor {} alarm cleared, value {value} below threshold {threshold}",
+                sensor.id
+            );
+        }
+
+        send_on(
+            client,
+            &mut self.counters,
+            Lane::Critical,
+            EVENTS_INTERFACE,
+            &format!("/{}/alarm", sensor.id),
+            AstarteType::Boolean(above),
+            None,
+        )
+        .await
+    }
+
+    /// Send a heartbeat on the critical lane
+    async fn send_heartbeat<C>(&mut self, client: &C) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        if let Some(degradation) = &self.degradation {
+            if !degradation.heartbeats() {
+                return Ok(());
+            }
+        }
+
+        let uptime = i64::try_from(self.clock.elapsed().as_secs()).unwrap_or(i64::MAX);
+
+        let res = send_on(
+            client,
+            &mut self.counters,
+            Lane::Critical,
+            EVENTS_INTERFACE,
+            &format!("/{}/heartbeat", self.sensor_id),
+            AstarteType::LongInteger(uptime),
+            None,
+        )
+        .await;
+
+        let Some(degradation) = &self.degradation else {
+            res?;
+            info!("heartbeat sent, {}", self.counters);
+
+            return Ok(());
+        };
+
+        match &res {
+            Ok(()) => info!(
+                "heartbeat sent, {}, degradation level {}",
+                self.counters,
+                degradation.level()
+            ),
+            Err(err) => self.report_error(format!("{err:#}")),
+        }
+
+        self.record_outcome(client, res.is_ok()).await;
+
+        Ok(())
+    }
+
+    /// Publish the status of the streamer on the critical lane
+    async fn send_status<C>(&mut self, client: &C) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        let status = Status {
+            uptime: self.clock.elapsed(),
+            messages: self.counters.total(),
+            last_error: self.last_error.clone(),
+            generator: self.generators(),
+            reattachments: self.reattachments,
+        };
+
+        send_object_on(
+            client,
+            &mut self.counters,
+            Lane::Critical,
+            STATUS_INTERFACE,
+            &format!("/{}", self.sensor_id),
+            status.fields(),
+            None,
+        )
+        .await?;
+
+        debug!("status sent: {status:?}");
+
+        Ok(())
+    }
+
+    /// Publish the diagnostics of the connection on the critical lane, if its transport is known
+    async fn send_diagnostics<C>(&mut self, client: &C) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        let (Some(transport), Some(connection)) = (&self.transport, &self.connection_diagnostics)
+        else {
+            return Ok(());
+        };
+
+        let diagnostics = Diagnostics {
+            transport: transport.clone(),
+            connection: connection.borrow().clone(),
+        };
+
+        send_object_on(
+            client,
+            &mut self.counters,
+            Lane::Critical,
+            DIAGNOSTICS_INTERFACE,
+            &format!("/{}", self.sensor_id),
+            diagnostics.fields(),
+            None,
+        )
+        .await?;
+
+        debug!("diagnostics sent: {diagnostics:?}");
+
+        Ok(())
+    }
+
+    /// Distinct math functions of the sensors, separated by commas
+    fn generators(&self) -> String {
+        let mut generators = Vec::new();
+
+        for sensor in &self.sensors {
+            let function = sensor.generator();
+
+            if !generators.contains(&function) {
+                generators.push(function);
+            }
+        }
+
+        generators.join(",")
+    }
+
+    /// Log an error, keeping it as the last one published in the status reports
+    fn report_error(&mut self, err: String) {
+        error!("{err}");
+
+        self.last_error = Some(err);
+    }
+
+    /// Send a sequence number with every reliability, while any sensor is on
+    async fn send_reliability_probe<C>(&mut self, client: &C)
+    where
+        C: Client + Send + Sync,
+    {
+        if !self.is_on() {
+            return;
+        }
+
+        let sequence = self.reliability_probe.next_sequence();
+
+        for reliability in Reliability::ALL {
+            let path = format!("/{}/{}", self.sensor_id, reliability.mapping());
+
+            let res = client
+                .send(
+                    RELIABILITY_INTERFACE,
+                    &path,
+                    AstarteType::LongInteger(sequence),
+                )
+                .await;
+
+            self.reliability_probe.record(reliability, res.is_ok());
+
+            if let Err(err) = res {
+                self.report_error(format!(
+                    "couldn't send reliability probe on {RELIABILITY_INTERFACE}{path}: {err}"
+                ));
+            }
+        }
+
+        info!(
+            "reliability probe {sequence} sent, {}",
+            self.reliability_probe
+        );
+    }
+
+    /// Alternately set and unset the lifecycle property, while any sensor is on
+    async fn cycle_property<C>(&mut self, client: &C) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        if !self.is_on() {
+            return Ok(());
+        }
+
+        let path = format!("/{}/value", self.sensor_id);
+
+        throttle(&mut self.rate_limiter).await;
+
+        let res = if self.property_set {
+            client.unset(LIFECYCLE_PROPERTIES_INTERFACE, &path).await
+        } else {
+            let value = self.property_sets.saturating_add(1);
+
+            client
+                .send(
+                    LIFECYCLE_PROPERTIES_INTERFACE,
+                    &path,
+                    AstarteType::LongInteger(value),
+                )
+                .await
+                .map(|()| self.property_sets = value)
+        };
+
+        self.counters.record(Lane::Telemetry, res.is_ok());
+
+        let action = if self.property_set { "unset" } else { "set" };
+        res.wrap_err_with(|| {
+            format!("couldn't {action} property {LIFECYCLE_PROPERTIES_INTERFACE}{path}")
+        })?;
+
+        debug!(
+            "property {LIFECYCLE_PROPERTIES_INTERFACE}{path} {action}, set {} times",
+            self.property_sets
+        );
+
+        self.property_set = !self.property_set;
+
+        Ok(())
+    }
+
+    /// Handle an event received from Astarte
+    ///
+    /// Both the commands and the configuration properties update the addressed sensor, while
+    /// unsetting a property restores the initial value of the parameter. Setting the sampling rate
+    /// `enable` property of an unknown sensor creates it, while unsetting it deletes the sensors
+    /// created this way. The introspection commands update the interfaces of the device, while the
+    /// connection commands request a rotation of the credentials.
+    async fn receive_data<C>(&mut self, client: &C, event: DeviceEvent) -> eyre::Result<()>
+    where
+        C: Client + DynamicIntrospection + Send + Sync,
+    {
+        debug!(
+            event_type = "data_received",
+            interface = %event.interface,
+            path = %event.path,
+            value = ?event.data,
+            "data received"
+        );
+
+        if event.interface == INTROSPECTION_COMMANDS_INTERFACE {
+            let Value::Individual(value) = event.data else {
+                bail!(
+                    "expected individual data on {}{}",
+                    event.interface,
+                    event.path
+                );
+            };
+
+            return IntrospectionCommand::try_from_value(&event.path, value)?
+                .apply(client)
+                .await;
+        }
+
+        if event.interface == CONNECTION_COMMANDS_INTERFACE {
+            let Value::Individual(value) = event.data else {
+                bail!(
+                    "expected individual data on {}{}",
+                    event.interface,
+                    event.path
+                );
+            };
+
+            let Some(command) = ConnectionCommand::try_from_value(&event.path, value)? else {
+                return Ok(());
+            };
+
+            match &self.credentials_rotation {
+                Some(rotation) => command.apply(rotation),
+                None => warn!("the connection doesn't support the {command:?} command"),
+            }
+
+            return Ok(());
+        }
+
+        let sampling_rate = event.interface == SAMPLING_RATE_INTERFACE;
+
+        if event.interface != COMMANDS_INTERFACE
+            && event.interface != CONFIG_PROPERTIES_INTERFACE
+            && !sampling_rate
+        {
+            warn!("received data on unexpected interface {}", event.interface);
+            return Ok(());
+        }
+
+        let EndpointPath {
+            sensor_id,
+            parameter,
+        } = EndpointPath::parse(&event.path)
+            .wrap_err_with(|| format!("invalid path {}", event.path))?;
+
+        // the enable property creates and deletes the sensors from Astarte
+        if sampling_rate && parameter == "enable" {
+            match event.data {
+                Value::Individual(value)
+                    if !self.has_sensor(sensor_id) && !is_pattern(sensor_id) =>
+                {
+                    let state = StreamConfigUpdate::try_from_sampling_rate(parameter, value)?;
+
+                    return self.create_sensor(client, sensor_id, state).await;
+                }
+                Value::Unset if self.is_dynamic(sensor_id) => {
+                    return self.delete_sensor(client, sensor_id).await;
+                }
+                _ => {}
+            }
+        }
+
+        let restored = matches!(event.data, Value::Unset);
+
+        let res = match event.data {
+            Value::Individual(value) if parameter == "ping" => {
+                debug!("received ping for sensor {sensor_id}: {value:?}");
+
+                return self.record_ping(value);
+            }
+            Value::Individual(value) => {
+                debug!("received {parameter} update for sensor {sensor_id}: {value:?}");
+
+                let update = if sampling_rate {
+                    StreamConfigUpdate::try_from_sampling_rate(parameter, value)
+                } else {
+                    StreamConfigUpdate::try_from_value(parameter, value)
+                };
+
+                update.and_then(|update| {
+                    let parameter = update.parameter();
+
+                    self.update_cfg(sensor_id, update)?;
+
+                    Ok(parameter)
+                })
+            }
+            Value::Unset if event.interface == CONFIG_PROPERTIES_INTERFACE => {
+                debug!("received {parameter} unset for sensor {sensor_id}");
+
+                self.restore_cfg(sensor_id, parameter).map(|()| parameter)
+            }
+            Value::Unset if sampling_rate => {
+                debug!("received {parameter} unset for sensor {sensor_id}");
+
+                StreamConfigUpdate::sampling_rate_parameter(parameter)
+                    .ok_or_else(|| eyre!("unknown sampling rate mapping {parameter}"))
+                    .and_then(|parameter| {
+                        self.restore_cfg(sensor_id, parameter).map(|()| parameter)
+                    })
+            }
+            Value::Object(_) | Value::Unset => {
+                bail!(
+                    "expected individual data on {}{}",
+                    event.interface,
+                    event.path
+                );
+            }
+        };
+
+        if let Ok(updated) = res {
+            let updates: Vec<(String, StreamConfigUpdate)> = self
+                .addressed(sensor_id)
+                .filter_map(|sensor| {
+                    let update = sensor.stream_cfg.parameter(updated)?;
+
+                    Some((sensor.id.clone(), update))
+                })
+                .collect();
+
+            for (sensor_id, update) in &updates {
+                publish_parameter(client, sensor_id, update).await?;
+            }
+
+            if let Some(remote_config) = &mut self.remote_config {
+                for (sensor_id, update) in updates {
+                    if restored {
+                        remote_config.forget(&sensor_id, update.parameter());
+                    } else {
+                        remote_config.record(&sensor_id, update);
+                    }
+                }
+
+                self.save_remote_config().await;
+            }
+        }
+
+        if let Err(err) = res {
+            warn!("rejected {parameter} update for sensor {sensor_id}: {err}");
+
+            client
+                .send(
+                    VALIDATION_ERRORS_INTERFACE,
+                    &format!("/{sensor_id}/{parameter}/error"),
+                    err.to_string(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Measure the downlink latency of a ping command, timestamped by the sender
+    fn record_ping(&mut self, value: AstarteType) -> eyre::Result<()> {
+        let sent_at = DateTime::<Utc>::try_from(value).wrap_err("ping must be a datetime")?;
+
+        let latency = Utc::now().signed_duration_since(sent_at);
+
+        let Ok(latency) = latency.to_std() else {
+            warn!("ping received {latency} before being sent, check the clocks synchronization");
+
+            return Ok(());
+        };
+
+        self.latency.record(latency);
+
+        if let Some(summary) = self.latency.summary() {
+            info!("downlink latency {latency:?}, distribution: {summary}");
+        }
+
+        Ok(())
+    }
+
+    /// Apply the configuration and sampling rate properties stored by the device
+    ///
+    /// Since they are stored, the properties set from Astarte survive the restarts of the device,
+    /// as do the sensors created through them.
+    async fn load_properties<C>(&mut self, client: &C) -> eyre::Result<()>
+    where
+        C: PropAccess + Send + Sync,
+    {
+        type Parser = fn(&str, AstarteType) -> eyre::Result<StreamConfigUpdate>;
+
+        let interfaces: [(&str, Parser); 2] = [
+            (
+                CONFIG_PROPERTIES_INTERFACE,
+                StreamConfigUpdate::try_from_value,
+            ),
+            (
+                SAMPLING_RATE_INTERFACE,
+                StreamConfigUpdate::try_from_sampling_rate,
+            ),
+        ];
+
+        let mut stored = Vec::new();
+
+        for (interface, parse) in interfaces {
+            let props = client
+                .interface_props(interface)
+                .await
+                .wrap_err_with(|| format!("couldn't read the stored properties of {interface}"))?;
+
+            stored.extend(props.into_iter().map(|prop| (interface, parse, prop)));
+        }
+
+        // recreate the sensors created from Astarte before applying their configuration
+        for (interface, parse, prop) in &stored {
+            let Ok(endpoint) = EndpointPath::parse(&prop.path) else {
+                continue;
+            };
+
+            if *interface != SAMPLING_RATE_INTERFACE
+                || endpoint.parameter != "enable"
+                || self.has_sensor(endpoint.sensor_id)
+            {
+                continue;
+            }
+
+            let res = parse(endpoint.parameter, prop.value.clone())
+                .and_then(|state| self.add_sensor(endpoint.sensor_id, state));
+
+            if let Err(err) = res {
+                warn!("couldn't recreate sensor {}: {err:#}", endpoint.sensor_id);
+            }
+        }
+
+        for (interface, parse, prop) in stored {
+            let endpoint = match EndpointPath::parse(&prop.path) {
+                // already applied when recreating the sensor
+                Ok(endpoint)
+                    if interface == SAMPLING_RATE_INTERFACE
+                        && endpoint.parameter == "enable"
+                        && self.is_dynamic(endpoint.sensor_id) =>
+                {
+                    continue;
+                }
+                Ok(endpoint)
+                    if self.has_sensor(endpoint.sensor_id) || is_pattern(endpoint.sensor_id) =>
+                {
+                    endpoint
+                }
+                Ok(endpoint) => {
+                    debug!(
+                        "skipped stored property {interface}{} of sensor {}",
+                        prop.path, endpoint.sensor_id
+                    );
+                    continue;
+                }
+                Err(err) => {
+                    warn!("ignored stored property {interface}{}: {err}", prop.path);
+                    continue;
+                }
+            };
+
+            let res = parse(endpoint.parameter, prop.value)
+                .and_then(|update| self.update_cfg(endpoint.sensor_id, update));
+
+            if let Err(err) = res {
+                warn!("ignored stored property {interface}{}: {err:#}", prop.path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Restore the initial value of a parameter of the addressed sensors
+    ///
+    /// Restoring a parameter of a sensor drops its override, while the updates addressed to a
+    /// pattern skip the overridden sensors.
+    fn restore_cfg(&mut self, sensor_id: &str, parameter: &str) -> eyre::Result<()> {
+        let pattern = is_pattern(sensor_id);
+
+        for sensor in self.addressed_mut(sensor_id)? {
+            if pattern && sensor.overrides.contains(parameter) {
+                debug!("sensor {} {parameter} overridden, not restored", sensor.id);
+                continue;
+            }
+
+            sensor.overrides.remove(parameter);
+            sensor.restore_cfg(parameter)?;
+        }
+
+        Ok(())
+    }
+
+    /// Validate the update against the schema and apply it to the addressed sensors
+    ///
+    /// An update addressed to a sensor overrides the parameter, so that the updates addressed to
+    /// its group, or to every sensor, no longer change it.
+    fn update_cfg(&mut self, sensor_id: &str, update: StreamConfigUpdate) -> eyre::Result<()> {
+        self.schema.validate(&update)?;
+
+        if let StreamConfigUpdate::MathFunction(function) = &update {
+            ensure_exec(&self.exec, std::iter::once(function))?;
+        }
+
+        let pattern = is_pattern(sensor_id);
+        let parameter = update.parameter();
+
+        for sensor in self.addressed_mut(sensor_id)? {
+            if !pattern {
+                sensor.overrides.insert(parameter);
+            } else if sensor.overrides.contains(parameter) {
+                debug!(
+                    "sensor {} {parameter} overridden, update ignored",
+                    sensor.id
+                );
+                continue;
+            }
+
+            sensor.apply_cfg(update.clone());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_stream_config_update_try_from_value() {
+        let update = |parameter, value| StreamConfigUpdate::try_from_value(parameter, value);
+
+        assert_eq!(
+            update("toggle", AstarteType::Boolean(false)).unwrap(),
+            StreamConfigUpdate::State(StreamState::Off)
+        );
+        assert_eq!(
+            update("function", AstarteType::String("noise-sin".to_string())).unwrap(),
+            StreamConfigUpdate::MathFunction(MathFunction::NoiseSin)
+        );
+        assert_eq!(
+            update("interval_btw_samples", AstarteType::LongInteger(500)).unwrap(),
+            StreamConfigUpdate::Interval(Duration::from_millis(500))
+        );
+        assert_eq!(
+            update("interval", AstarteType::String("1m30s".to_string())).unwrap(),
+            StreamConfigUpdate::Interval(Duration::from_secs(90))
+        );
+        assert_eq!(
+            update("output_type", AstarteType::String("integer".to_string())).unwrap(),
+            StreamConfigUpdate::OutputType(OutputType::Integer)
+        );
+        assert_eq!(
+            update("blob_size", AstarteType::LongInteger(1024)).unwrap(),
+            StreamConfigUpdate::BlobSize(1024)
+        );
+        assert_eq!(
+            update("burst_size", AstarteType::LongInteger(10)).unwrap(),
+            StreamConfigUpdate::BurstSize(10)
+        );
+
+        assert!(update("function", AstarteType::String("unknown".to_string())).is_err());
+        assert!(update("burst_size", AstarteType::LongInteger(0)).is_err());
+        assert!(update("burst_size", AstarteType::LongInteger(1_000_000)).is_err());
+        assert!(update("blob_size", AstarteType::LongInteger(-1)).is_err());
+        assert!(update("blob_size", AstarteType::LongInteger(i64::MAX)).is_err());
+        assert!(update("interval_btw_samples", AstarteType::LongInteger(-1)).is_err());
+        assert!(update("interval", AstarteType::String("0s".to_string())).is_err());
+        assert!(update("scale", AstarteType::Boolean(true)).is_err());
+        assert!(update("unknown", AstarteType::Double(1.0)).is_err());
+    }
+
+    #[test]
+    fn test_stream_config_update_try_from_sampling_rate() {
+        let update = |mapping, value| StreamConfigUpdate::try_from_sampling_rate(mapping, value);
+
+        assert_eq!(
+            update("enable", AstarteType::Boolean(false)).unwrap(),
+            StreamConfigUpdate::State(StreamState::Off)
+        );
+        assert_eq!(
+            update("samplingPeriod", AstarteType::Integer(2)).unwrap(),
+            StreamConfigUpdate::Interval(Duration::from_millis(2000))
+        );
+
+        assert!(update("samplingPeriod", AstarteType::Integer(0)).is_err());
+        assert!(update("enable", AstarteType::Integer(1)).is_err());
+        assert!(update("toggle", AstarteType::Boolean(true)).is_err());
+
+        for mapping in ["enable", "samplingPeriod"] {
+            let parameter = StreamConfigUpdate::sampling_rate_parameter(mapping).unwrap();
+            let update = update(mapping, AstarteType::Integer(1))
+                .or_else(|_| update(mapping, AstarteType::Boolean(true)))
+                .unwrap();
+
+            assert_eq!(update.parameter(), parameter);
+        }
+    }
+
+    #[test]
+    fn test_stream_config_update_value_round_trip() {
+        let parameters = [
+            StreamConfigUpdate::State(StreamState::Off),
+            StreamConfigUpdate::MathFunction(MathFunction::RandomSpikesSin),
+            StreamConfigUpdate::Interval(Duration::from_millis(250)),
+            StreamConfigUpdate::Scale(0.5),
+            StreamConfigUpdate::OutputType(OutputType::LongInteger),
+            StreamConfigUpdate::BlobSize(1024),
+        ];
+
+        for parameter in parameters {
+            let value = parameter.value();
+
+            assert_eq!(
+                StreamConfigUpdate::try_from_value(parameter.parameter(), value).unwrap(),
+                parameter
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_independent_sensors() {
+        let cfg = Config::parse_from(["stream-rust-test", "--sensors", "3"]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+
+        manager.set_device_id("device");
+
+        let ids: Vec<&str> = manager.sensors.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["test", "test-1", "test-2"]);
+
+        // without a seed, only the primary sensor sends the plain values
+        assert_eq!(manager.sensors[0].offset, SensorOffset::default());
+        assert_ne!(manager.sensors[1].offset, manager.sensors[2].offset);
+
+        // the updates only apply to the addressed sensor
+        manager
+            .update_cfg("test-1", StreamConfigUpdate::State(StreamState::Off))
+            .unwrap();
+        assert!(!manager.sensors[1].stream_cfg.is_on());
+        assert!(manager.is_on());
+
+        manager.restore_cfg("test-1", "toggle").unwrap();
+        assert!(manager.sensors[1].stream_cfg.is_on());
+
+        assert!(manager
+            .update_cfg("unknown", StreamConfigUpdate::Scale(2.0))
+            .is_err());
+
+        // a broadcast id updates every sensor
+        manager
+            .update_cfg("*", StreamConfigUpdate::State(StreamState::Off))
+            .unwrap();
+        assert!(!manager.is_on());
+    }
+
+    #[tokio::test]
+    async fn test_restart_extra() {
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--stream",
+            "interface=org.astarte-platform.genericsensors.Values,path=/extra/value",
+            "--max-restarts",
+            "1",
+        ]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+
+        // the first failure suspends the stream, the next consecutive one stops the process
+        manager.restart_extra(0, eyre!("failed")).unwrap();
+        assert!(manager.restart_extra(0, eyre!("failed")).is_err());
+
+        // a successful sample resets the failures
+        manager.extra_streams[0].recovered();
+        manager.restart_extra(0, eyre!("failed")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remote_output_type() {
+        let cfg = Config::parse_from(["stream-rust-test"]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+        manager.set_device_id("device");
+
+        manager
+            .update_cfg("test", StreamConfigUpdate::OutputType(OutputType::Integer))
+            .unwrap();
+
+        let stream_cfg = &mut manager.sensors[0].stream_cfg;
+        assert_eq!(
+            stream_cfg.interface(),
+            "org.astarte-platform.stream-rust-test.IntegerValues"
+        );
+        assert_eq!(
+            stream_cfg.output.convert(41.6).unwrap(),
+            AstarteType::Integer(42)
+        );
+
+        manager.restore_cfg("test", "output_type").unwrap();
+        assert_eq!(
+            manager.sensors[0].stream_cfg.interface(),
+            "org.astarte-platform.genericsensors.Values"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exec_requires_command() {
+        let new = |args: &[&str]| {
+            let cfg = Config::parse_from(["stream-rust-test"].iter().chain(args));
+            StreamManager::new(cfg, SystemTime::now())
+        };
+
+        assert!(new(&["--math-function", "exec"]).await.is_err());
+        assert!(new(&["--sensor-functions", "sin,exec"]).await.is_err());
+        assert!(new(&["--group-functions", "hvac.*=exec"]).await.is_err());
+
+        let mut manager = new(&[]).await.unwrap();
+        manager.set_device_id("device");
+
+        // the remote update is rejected instead of failing the sample
+        assert!(manager
+            .update_cfg("test", StreamConfigUpdate::MathFunction(MathFunction::Exec))
+            .is_err());
+        assert_eq!(
+            manager.sensors[0].stream_cfg.math_function,
+            MathFunction::Default
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_restore() {
+        let dir = std::env::temp_dir().join(format!("snapshot-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let args = [
+            "stream-rust-test",
+            "--sensors",
+            "2",
+            "--snapshot-interval",
+            "1000",
+        ];
+        let mut manager = StreamManager::new(Config::parse_from(args), SystemTime::now())
+            .await
+            .unwrap();
+        manager.set_device_id("device");
+        manager.persist_snapshot(&dir);
+
+        manager.sensors[1].base_value = BaseValue::new(123.0, 1.0);
+        manager.sensors[1].alarm_active = true;
+        manager.counters.record(Lane::Telemetry, true);
+        manager.save_snapshot().await;
+
+        let mut restarted = StreamManager::new(Config::parse_from(args), SystemTime::now())
+            .await
+            .unwrap();
+        restarted.set_device_id("device");
+        restarted.persist_snapshot(&dir);
+        restarted.restore_snapshot().await.unwrap();
+
+        assert_eq!(restarted.sensors[1].base_value.value(), 123.0);
+        assert!(restarted.sensors[1].alarm_active);
+        assert!(!restarted.sensors[0].alarm_active);
+        assert_eq!(restarted.counters, manager.counters);
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_correlated_sensors() {
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--sensors",
+            "2",
+            "--correlations",
+            "test-1=test:2:1",
+        ]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+        manager.set_device_id("device");
+
+        let offset = manager.sensors[1].offset;
+
+        // no value until the source is sampled
+        assert_eq!(manager.compute(1, 0.0, offset).await.unwrap(), None);
+
+        manager.sensors[0].last_value = Some(3.0);
+        assert_eq!(manager.compute(1, 0.0, offset).await.unwrap(), Some(7.0));
+        assert_eq!(manager.sensors[1].generator(), "correlated to test");
+    }
+
+    #[tokio::test]
+    async fn test_reattach() {
+        let cfg = Config::parse_from(["stream-rust-test"]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+
+        // no client without re-attachments
+        assert!(next_client::<u8>(&mut None).now_or_never().is_none());
+
+        let (tx, rx) = mpsc::channel(1);
+        let mut clients = Some(rx);
+        tx.send(2u8).await.unwrap();
+
+        let mut client = 1u8;
+        let new = next_client(&mut clients).await;
+        manager.reattach(&mut client, new);
+
+        assert_eq!(client, 2);
+        assert_eq!(manager.reattachments, 1);
+
+        // a closed channel never completes
+        drop(tx);
+        assert!(next_client(&mut clients).now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sample_priority() {
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--sensors",
+            "3",
+            "--sensor-priorities",
+            "0,0,5",
+        ]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+
+        for sensor in &mut manager.sensors {
+            sensor.ticker = periodic_ticker(Duration::from_millis(1));
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let mut due = Vec::new();
+        let mut order = Vec::new();
+
+        for _ in 0..3 {
+            order.push(next_sample(&mut manager.sensors, &mut due).await);
+        }
+
+        assert_eq!(order, [2, 0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_sensor_groups() {
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--sensors",
+            "3",
+            "--sensor-id",
+            "hvac.{n}",
+        ]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+        manager
+            .add_sensor("pump.0", StreamConfigUpdate::State(StreamState::On))
+            .unwrap();
+
+        let interval = |manager: &StreamManager, idx: usize| {
+            manager.sensors[idx].stream_cfg.interval.as_millis()
+        };
+
+        // the per-sensor update overrides the group ones, even if received before
+        manager
+            .update_cfg(
+                "hvac.1",
+                StreamConfigUpdate::Interval(Duration::from_millis(200)),
+            )
+            .unwrap();
+        manager
+            .update_cfg(
+                "hvac.*",
+                StreamConfigUpdate::Interval(Duration::from_millis(500)),
+            )
+            .unwrap();
+
+        assert_eq!(interval(&manager, 0), 500);
+        assert_eq!(interval(&manager, 1), 200);
+        assert_eq!(interval(&manager, 2), 500);
+        assert_eq!(interval(&manager, 3), 1000);
+
+        manager
+            .restore_cfg("hvac.*", "interval_btw_samples")
+            .unwrap();
+        assert_eq!(interval(&manager, 0), 1000);
+        assert_eq!(interval(&manager, 1), 200);
+
+        // restoring the sensor drops its override
+        manager
+            .restore_cfg("hvac.1", "interval_btw_samples")
+            .unwrap();
+        manager
+            .update_cfg(
+                "hvac.*",
+                StreamConfigUpdate::Interval(Duration::from_millis(500)),
+            )
+            .unwrap();
+        assert_eq!(interval(&manager, 1), 500);
+
+        // an empty group is not an error
+        manager
+            .update_cfg(
+                "boiler.*",
+                StreamConfigUpdate::Interval(Duration::from_millis(500)),
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handle_mock_connection() {
+        let cfg = Config::parse_from(["stream-rust-test", "--interval-btw-samples", "10"]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+        manager.set_device_id("device");
+
+        let observer = ConnectionObserver::new();
+        let activity = observer.activity();
+        manager.record_activity_on(observer);
+
+        let (client, connection) = crate::mock::mock();
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let handle = tokio::spawn(manager.handle(client, None, async {
+            let _ = stop_rx.await;
+        }));
+
+        let values = "org.astarte-platform.genericsensors.Values";
+        let exchange = async {
+            let sent = connection
+                .wait_for(|message| message.interface == values)
+                .await;
+            assert_eq!(sent.path, "/test/value");
+            assert!(matches!(
+                sent.data,
+                Value::Individual(AstarteType::Double(_))
+            ));
+
+            // a sensor enabled from Astarte is published and starts streaming
+            connection.inject(
+                SAMPLING_RATE_INTERFACE,
+                "/pump/enable",
+                Value::Individual(AstarteType::Boolean(true)),
+            );
+            connection
+                .wait_for(|message| {
+                    message.interface == AVAILABLE_SENSORS_INTERFACE && message.path == "/pump/name"
+                })
+                .await;
+            connection
+                .wait_for(|message| message.interface == values && message.path == "/pump/value")
+                .await;
+        };
+
+        tokio::time::timeout(Duration::from_secs(5), exchange)
+            .await
+            .unwrap();
+
+        stop_tx.send(()).unwrap();
+        handle.await.unwrap().unwrap();
+
+        assert!(connection
+            .sent()
+            .iter()
+            .any(|message| message.interface == STREAM_CONFIG_INTERFACE));
+
+        // the sent and received data show that the connection is alive
+        assert!(*activity.borrow() > 1);
+    }
+
+    #[derive(Debug)]
+    struct Constant(f64);
+
+    impl Sampler for Constant {
+        fn name(&self) -> String {
+            "constant".to_string()
+        }
+
+        fn sample(&mut self, _input: f64) -> Option<f64> {
+            Some(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_sampler() {
+        let cfg = Config::parse_from(["stream-rust-test", "--sensors", "2"]);
+        let mut manager = crate::sampler::StreamBuilder::new(cfg)
+            .sampler(1, Constant(42.0))
+            .build(SystemTime::now())
+            .await
+            .unwrap();
+
+        // the samplers are kept when the sensors are rebuilt for the device
+        manager.set_device_id("device");
+
+        let offset = SensorOffset::default();
+        assert_eq!(manager.compute(1, 1.0, offset).await.unwrap(), Some(42.0));
+        assert_eq!(manager.generators(), "default,constant");
+
+        manager
+            .update_cfg(
+                "test-1",
+                StreamConfigUpdate::MathFunction(MathFunction::Saw),
+            )
+            .unwrap();
+        assert_ne!(manager.compute(1, 1.0, offset).await.unwrap(), Some(42.0));
+
+        let cfg = Config::parse_from(["stream-rust-test"]);
+        assert!(crate::sampler::StreamBuilder::new(cfg)
+            .sampler(1, Constant(0.0))
+            .build(SystemTime::now())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_sensors() {
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--sensor-functions",
+            "sin,saw",
+            "--remote-max-sensors",
+            "1",
+        ]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+
+        let idx = manager
+            .add_sensor("new_sensor", StreamConfigUpdate::State(StreamState::Off))
+            .unwrap();
+
+        // the sensors created from Astarte are capped
+        assert!(manager
+            .add_sensor("other_sensor", StreamConfigUpdate::State(StreamState::On))
+            .is_err());
+
+        let sensor = &manager.sensors[idx];
+        assert_eq!(sensor.id, "new_sensor");
+        assert_eq!(sensor.stream_cfg.math_function, MathFunction::Saw);
+        assert!(!sensor.stream_cfg.is_on());
+
+        assert!(manager.is_dynamic("new_sensor"));
+        assert!(!manager.is_dynamic("test"));
+    }
+
+    #[tokio::test]
+    async fn test_reload_rate() {
+        let cfg = Config::parse_from(["stream-rust-test", "--rate", "3"]);
+        let mut manager = StreamManager::new(cfg.clone(), SystemTime::now())
+            .await
+            .unwrap();
+        manager.set_device_id("device");
+
+        let third = Duration::from_secs(1) / 3;
+        manager.sensors[0].ticker = manager.sensors[0].stream_cfg.ticker();
+        assert_eq!(manager.sensors[0].ticker.period(), third);
+
+        // the same rate keeps the sub-millisecond interval, without restarting the ticker
+        let (client, _connection) = crate::mock::mock();
+        manager.reload_config(&client, &cfg).await.unwrap();
+        assert_eq!(manager.sensors[0].stream_cfg.interval, third);
+
+        let cfg = Config::parse_from(["stream-rust-test", "--rate", "6"]);
+        manager.reload_config(&client, &cfg).await.unwrap();
+        manager.sensors[0].update_ticker();
+        assert_eq!(
+            manager.sensors[0].ticker.period(),
+            Duration::from_secs(1) / 6
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reload_config() {
+        let cfg = Config::parse_from(["stream-rust-test", "--sensors", "2"]);
+        let mut manager = StreamManager::new(cfg, SystemTime::now()).await.unwrap();
+        manager.set_device_id("device");
+
+        // the interval updated from Astarte is kept
+        manager
+            .update_cfg(
+                "test-1",
+                StreamConfigUpdate::Interval(Duration::from_millis(50)),
+            )
+            .unwrap();
+
+        let (client, connection) = crate::mock::mock();
+        let cfg = Config::parse_from([
+            "stream-rust-test",
+            "--sensors",
+            "3",
+            "--scale",
+            "4",
+            "--interval-btw-samples",
+            "200",
+        ]);
+        manager.reload_config(&client, &cfg).await.unwrap();
+
+        let ids: Vec<&str> = manager.sensors.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["test", "test-1", "test-2"]);
+        assert_eq!(manager.sensors[0].stream_cfg.scale, 4.0);
+        assert_eq!(
+            manager.sensors[0].stream_cfg.interval,
+            Duration::from_millis(200)
+        );
+        assert_eq!(
+            manager.sensors[1].stream_cfg.interval,
+            Duration::from_millis(50)
+        );
+        assert_eq!(manager.sensors[2].stream_cfg.scale, 4.0);
+        assert!(connection.sent().iter().any(|message| {
+            message.interface == AVAILABLE_SENSORS_INTERFACE && message.path == "/test-2/name"
+        }));
+
+        // the restored interval is the reloaded one
+        manager
+            .restore_cfg("test-1", "interval_btw_samples")
+            .unwrap();
+        assert_eq!(
+            manager.sensors[1].stream_cfg.interval,
+            Duration::from_millis(200)
+        );
+
+        let cfg = Config::parse_from(["stream-rust-test"]);
+        manager.reload_config(&client, &cfg).await.unwrap();
+
+        let ids: Vec<&str> = manager.sensors.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["test"]);
+    }
+}
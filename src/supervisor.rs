@@ -0,0 +1,70 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Supervision of the streams, so that a failed send doesn't stop the whole process.
+//!
+//! The sensors and the additional streams share the state of the stream manager, so they are
+//! supervised within its loop instead of running as separate tasks. A stream failing to send a
+//! sample is suspended and restarted after an exponential backoff, while the others keep sending
+//! data. The process stops only when a stream fails more than the maximum number of consecutive
+//! restarts, if set.
+
+use std::time::Duration;
+
+/// Policy restarting the failed streams
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    backoff: Duration,
+    max_backoff: Duration,
+    max_restarts: Option<u32>,
+}
+
+impl RestartPolicy {
+    /// Double the backoff at every consecutive failure, from the initial one up to the maximum
+    pub fn new(backoff: Duration, max_backoff: Duration, max_restarts: Option<u32>) -> Self {
+        Self {
+            backoff,
+            max_backoff: max_backoff.max(backoff),
+            max_restarts,
+        }
+    }
+
+    /// Check if a stream can be restarted after the given number of consecutive failures
+    pub fn can_restart(&self, failures: u32) -> bool {
+        self.max_restarts.map_or(true, |max| failures <= max)
+    }
+
+    /// Time to wait before restarting a stream after the given number of consecutive failures
+    pub fn backoff(&self, failures: u32) -> Duration {
+        let exp = failures.saturating_sub(1).min(31);
+
+        self.backoff
+            .checked_mul(1 << exp)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_restart_policy() {
+        let secs = Duration::from_secs;
+        let policy = RestartPolicy::new(secs(1), secs(10), Some(3));
+
+        assert_eq!(policy.backoff(1), secs(1));
+        assert_eq!(policy.backoff(2), secs(2));
+        assert_eq!(policy.backoff(4), secs(8));
+        assert_eq!(policy.backoff(5), secs(10));
+        assert_eq!(policy.backoff(100), secs(10));
+
+        assert!(policy.can_restart(3));
+        assert!(!policy.can_restart(4));
+
+        assert!(RestartPolicy::new(secs(1), secs(10), None).can_restart(u32::MAX));
+    }
+}
@@ -0,0 +1,331 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Explicit timestamps of the sent values, to test the historical ingestion of Astarte.
+//!
+//! The mappings receiving timestamped values must have `explicit_timestamp` set, like the ones of
+//! the bundled `org.astarte-platform.stream-rust-test.TimestampedValues` interface.
+//!
+//! A random skew can be added to the timestamps, to send out of order or backdated data.
+
+use crate::duration;
+use crate::math::standard_normal;
+use crate::output::DateTimeSource;
+use astarte_device_sdk::chrono::{DateTime, TimeDelta, Utc};
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, OptionExt, WrapErr};
+
+/// Distribution of the skew added to the timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SkewDistribution {
+    /// Uniform skew between minus and plus the magnitude, sending out of order timestamps
+    Uniform,
+    /// Gaussian skew, with the magnitude as standard deviation
+    Gaussian,
+    /// Uniform skew between minus the magnitude and zero, backdating the timestamps
+    Backdate,
+}
+
+/// Options of the explicit timestamps
+#[derive(Debug, Clone, clap::Args)]
+pub struct TimestampConfig {
+    /// Source of the explicit timestamp sent with each value
+    ///
+    /// If not set, the values are sent without timestamp and Astarte uses the reception time.
+    #[clap(long, env = "TIMESTAMP_SOURCE")]
+    pub timestamp_source: Option<DateTimeSource>,
+    /// Time added to the current time with the `offset` timestamp source, can be negative (e.g.
+    /// `-1h`, `30s`; bare numbers are seconds)
+    #[clap(
+        long,
+        default_value = "0",
+        allow_hyphen_values = true,
+        env = "TIMESTAMP_OFFSET",
+        value_parser = duration::parse_signed_secs
+    )]
+    pub timestamp_offset: i64,
+    /// First timestamp sent with the `synthetic` timestamp source, in RFC 3339 format
+    ///
+    /// Defaults to the time of the first sample.
+    #[clap(long, env = "TIMESTAMP_START")]
+    pub timestamp_start: Option<DateTime<Utc>>,
+    /// Interval between two timestamps sent with the `synthetic` timestamp source (e.g. `500ms`,
+    /// `2s`; bare numbers are milliseconds)
+    #[clap(
+        long,
+        default_value = "1000",
+        env = "TIMESTAMP_STEP",
+        value_parser = duration::parse_millis
+    )]
+    pub timestamp_step: u64,
+    /// Distribution of the random skew added to the timestamps
+    ///
+    /// If not set, the timestamps are not skewed.
+    #[clap(long, env = "TIMESTAMP_SKEW")]
+    pub timestamp_skew: Option<SkewDistribution>,
+    /// Magnitude of the skew (e.g. `500ms`, `2s`; bare numbers are milliseconds)
+    #[clap(
+        long,
+        default_value = "1000",
+        env = "TIMESTAMP_SKEW_MAGNITUDE",
+        value_parser = parse_skew_magnitude
+    )]
+    pub timestamp_skew_magnitude: u64,
+    /// Probability of skewing a timestamp, between 0 and 1
+    #[clap(
+        long,
+        default_value = "1.0",
+        env = "TIMESTAMP_SKEW_PROBABILITY",
+        value_parser = parse_skew_probability
+    )]
+    pub timestamp_skew_probability: f64,
+}
+
+/// Parse the magnitude of the skew, a positive duration that a timestamp can be shifted by
+fn parse_skew_magnitude(s: &str) -> eyre::Result<u64> {
+    let millis = duration::parse_positive_millis(s)?;
+
+    ensure!(
+        i64::try_from(millis)
+            .ok()
+            .and_then(TimeDelta::try_milliseconds)
+            .is_some(),
+        "the timestamp skew magnitude {s} is too big"
+    );
+
+    Ok(millis)
+}
+
+/// Parse the probability of skewing a timestamp, between 0 and 1
+fn parse_skew_probability(s: &str) -> eyre::Result<f64> {
+    let probability = s
+        .parse::<f64>()
+        .wrap_err_with(|| format!("invalid probability {s}"))?;
+
+    ensure!(
+        (0.0..=1.0).contains(&probability),
+        "the timestamp skew probability must be between 0 and 1, received {probability}"
+    );
+
+    Ok(probability)
+}
+
+/// Random skew of the timestamps
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Skew {
+    distribution: SkewDistribution,
+    /// Magnitude in milliseconds
+    magnitude: f64,
+    probability: f64,
+}
+
+impl Skew {
+    fn new(cfg: &TimestampConfig) -> eyre::Result<Option<Self>> {
+        let Some(distribution) = cfg.timestamp_skew else {
+            return Ok(None);
+        };
+
+        let probability = cfg.timestamp_skew_probability;
+
+        ensure!(
+            (0.0..=1.0).contains(&probability),
+            "the timestamp skew probability must be between 0 and 1, received {probability}"
+        );
+
+        Ok(Some(Self {
+            distribution,
+            magnitude: cfg.timestamp_skew_magnitude as f64,
+            probability,
+        }))
+    }
+
+    /// Skew of a timestamp, zero if the timestamp isn't skewed
+    fn sample(&self) -> Option<TimeDelta> {
+        if rand::random::<f64>() >= self.probability {
+            return Some(TimeDelta::zero());
+        }
+
+        let millis = match self.distribution {
+            SkewDistribution::Uniform => self.magnitude * (2.0 * rand::random::<f64>() - 1.0),
+            SkewDistribution::Gaussian => self.magnitude * standard_normal(),
+            SkewDistribution::Backdate => -self.magnitude * rand::random::<f64>(),
+        };
+
+        // the cast saturates, returning None since out of range
+        TimeDelta::try_milliseconds(millis.round() as i64)
+    }
+}
+
+/// Generator of the explicit timestamps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timestamps {
+    source: DateTimeSource,
+    offset: TimeDelta,
+    step: TimeDelta,
+    /// Next synthetic timestamp
+    next: Option<DateTime<Utc>>,
+    skew: Option<Skew>,
+}
+
+impl Timestamps {
+    /// Build the generator, returning [`None`] if no timestamp source is configured
+    pub fn new(cfg: &TimestampConfig) -> eyre::Result<Option<Self>> {
+        let Some(source) = cfg.timestamp_source else {
+            return Ok(None);
+        };
+
+        let offset =
+            TimeDelta::try_seconds(cfg.timestamp_offset).ok_or_eyre("timestamp offset too big")?;
+
+        let step = i64::try_from(cfg.timestamp_step)
+            .ok()
+            .and_then(TimeDelta::try_milliseconds)
+            .ok_or_eyre("timestamp step too big")?;
+
+        ensure!(
+            step > TimeDelta::zero(),
+            "the timestamp step must be positive"
+        );
+
+        Ok(Some(Self {
+            source,
+            offset,
+            step,
+            next: cfg.timestamp_start,
+            skew: Skew::new(cfg)?,
+        }))
+    }
+
+    /// Timestamp of the next sample, given the current time
+    pub fn next(&mut self, now: DateTime<Utc>) -> eyre::Result<DateTime<Utc>> {
+        let timestamp = match self.source {
+            DateTimeSource::Now => now,
+            DateTimeSource::Offset => now
+                .checked_add_signed(self.offset)
+                .ok_or_eyre("timestamp offset out of range")?,
+            DateTimeSource::Synthetic => {
+                let timestamp = *self.next.get_or_insert(now);
+
+                self.next = Some(
+                    timestamp
+                        .checked_add_signed(self.step)
+                        .ok_or_eyre("synthetic timestamp out of range")?,
+                );
+
+                timestamp
+            }
+        };
+
+        let Some(skew) = &self.skew else {
+            return Ok(timestamp);
+        };
+
+        skew.sample()
+            .and_then(|skew| timestamp.checked_add_signed(skew))
+            .ok_or_eyre("skewed timestamp out of range")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(source: Option<DateTimeSource>) -> TimestampConfig {
+        TimestampConfig {
+            timestamp_source: source,
+            timestamp_offset: -3600,
+            timestamp_start: None,
+            timestamp_step: 500,
+            timestamp_skew: None,
+            timestamp_skew_magnitude: 1000,
+            timestamp_skew_probability: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_timestamps() {
+        assert_eq!(Timestamps::new(&config(None)).unwrap(), None);
+
+        let now = Utc::now();
+
+        let mut timestamps = Timestamps::new(&config(Some(DateTimeSource::Now)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(timestamps.next(now).unwrap(), now);
+
+        let mut timestamps = Timestamps::new(&config(Some(DateTimeSource::Offset)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            timestamps.next(now).unwrap(),
+            now - TimeDelta::try_hours(1).unwrap()
+        );
+
+        let mut timestamps = Timestamps::new(&config(Some(DateTimeSource::Synthetic)))
+            .unwrap()
+            .unwrap();
+        let first = timestamps.next(now).unwrap();
+        assert_eq!(first, now);
+        // the wall clock doesn't affect the synthetic timestamps
+        assert_eq!(
+            timestamps.next(now).unwrap(),
+            first + TimeDelta::try_milliseconds(500).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_timestamps_skew() {
+        let now = Utc::now();
+        let magnitude = TimeDelta::try_seconds(1).unwrap();
+
+        let mut cfg = config(Some(DateTimeSource::Now));
+        cfg.timestamp_skew = Some(SkewDistribution::Backdate);
+        let mut timestamps = Timestamps::new(&cfg).unwrap().unwrap();
+
+        for _ in 0..1000 {
+            let timestamp = timestamps.next(now).unwrap();
+            assert!(timestamp <= now && timestamp >= now - magnitude);
+        }
+
+        cfg.timestamp_skew = Some(SkewDistribution::Uniform);
+        let mut timestamps = Timestamps::new(&cfg).unwrap().unwrap();
+
+        let skewed: Vec<_> = (0..1000).map(|_| timestamps.next(now).unwrap()).collect();
+        assert!(skewed
+            .iter()
+            .all(|t| *t >= now - magnitude && *t <= now + magnitude));
+        assert!(skewed.iter().any(|t| *t > now));
+
+        cfg.timestamp_skew_probability = 0.0;
+        let mut timestamps = Timestamps::new(&cfg).unwrap().unwrap();
+        assert_eq!(timestamps.next(now).unwrap(), now);
+
+        cfg.timestamp_skew_probability = 1.5;
+        assert!(Timestamps::new(&cfg).is_err());
+    }
+
+    #[test]
+    fn test_parse_skew() {
+        assert_eq!(parse_skew_magnitude("2s").unwrap(), 2000);
+        assert!(parse_skew_magnitude("0").is_err());
+        assert!(parse_skew_magnitude(&u64::MAX.to_string()).is_err());
+
+        assert_eq!(parse_skew_probability("0.25").unwrap(), 0.25);
+        assert_eq!(parse_skew_probability("1").unwrap(), 1.0);
+        assert!(parse_skew_probability("1.5").is_err());
+        assert!(parse_skew_probability("-0.1").is_err());
+        assert!(parse_skew_probability("NaN").is_err());
+        assert!(parse_skew_probability("often").is_err());
+    }
+
+    #[test]
+    fn test_timestamps_invalid_step() {
+        let mut cfg = config(Some(DateTimeSource::Synthetic));
+        cfg.timestamp_step = 0;
+
+        assert!(Timestamps::new(&cfg).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Boundary and edge-case values of every Astarte type, to shake out serialization issues end to
+//! end.
+//!
+//! Each case is sent on the bundled interface of its type. NaN, infinities and subnormal doubles
+//! are not valid Astarte values, so the doubles closest to them are sent instead.
+
+use crate::output::{OutputType, LONGINTEGER_BOUNDARIES};
+use astarte_device_sdk::chrono::{DateTime, TimeDelta, Utc};
+use astarte_device_sdk::AstarteType;
+
+/// Size in bytes of the huge strings and blobs, the maximum accepted by Astarte
+const HUGE_SIZE: usize = 65_536;
+
+/// Edge-case value sent on the bundled interface of its type
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeCase {
+    /// Interface the value is sent to
+    pub interface: &'static str,
+    /// Description of the case, logged when sent
+    pub description: String,
+    /// Value sent
+    pub value: AstarteType,
+}
+
+/// Every edge case, grouped by type
+pub fn edge_cases() -> Vec<EdgeCase> {
+    let doubles = [
+        ("zero", 0.0),
+        ("negative zero", -0.0),
+        ("epsilon", f64::EPSILON),
+        ("one plus epsilon", 1.0 + f64::EPSILON),
+        ("smallest normal", f64::MIN_POSITIVE),
+        ("negative smallest normal", -f64::MIN_POSITIVE),
+        ("2^53 plus one", 9_007_199_254_740_993.0),
+        ("max", f64::MAX),
+        ("min", f64::MIN),
+    ];
+
+    let integers = [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX - 1, i32::MAX];
+
+    let strings = [
+        ("empty", String::new()),
+        ("escapes", "\"\\\n\t\r".to_string()),
+        ("nul byte", "a\u{0}b".to_string()),
+        ("multi-byte", "π ∞ 🚀 e\u{301}".to_string()),
+        ("huge", "a".repeat(HUGE_SIZE)),
+    ];
+
+    let blobs = [
+        ("empty", Vec::new()),
+        ("zero byte", vec![0]),
+        ("all ones", vec![0xff; 64]),
+        ("huge", vec![0xa5; HUGE_SIZE]),
+    ];
+
+    let datetimes = [
+        ("epoch", DateTime::<Utc>::UNIX_EPOCH),
+        (
+            "before epoch",
+            DateTime::<Utc>::UNIX_EPOCH - TimeDelta::milliseconds(1),
+        ),
+        (
+            "sub-millisecond",
+            DateTime::<Utc>::UNIX_EPOCH + TimeDelta::nanoseconds(1_000_999),
+        ),
+        (
+            "year 9999",
+            DateTime::parse_from_rfc3339("9999-12-31T23:59:59.999Z")
+                .map(|datetime| datetime.to_utc())
+                .unwrap_or_default(),
+        ),
+    ];
+
+    let mut cases = Vec::new();
+
+    let mut push = |output_type: OutputType, array: bool, description: String, value| {
+        let interface = if array {
+            output_type.default_array_interface()
+        } else {
+            output_type.default_interface()
+        };
+
+        cases.push(EdgeCase {
+            interface,
+            description: format!(
+                "{output_type}{} {description}",
+                if array { " array" } else { "" }
+            ),
+            value,
+        });
+    };
+
+    for (description, value) in doubles {
+        push(
+            OutputType::Double,
+            false,
+            description.to_string(),
+            AstarteType::Double(value),
+        );
+    }
+    for value in integers {
+        push(
+            OutputType::Integer,
+            false,
+            value.to_string(),
+            AstarteType::Integer(value),
+        );
+    }
+    for value in [false, true] {
+        push(
+            OutputType::Boolean,
+            false,
+            value.to_string(),
+            AstarteType::Boolean(value),
+        );
+    }
+    for value in LONGINTEGER_BOUNDARIES {
+        push(
+            OutputType::LongInteger,
+            false,
+            value.to_string(),
+            AstarteType::LongInteger(value),
+        );
+    }
+    for (description, value) in strings.clone() {
+        push(
+            OutputType::String,
+            false,
+            description.to_string(),
+            AstarteType::String(value),
+        );
+    }
+    for (description, value) in blobs.clone() {
+        push(
+            OutputType::BinaryBlob,
+            false,
+            description.to_string(),
+            AstarteType::BinaryBlob(value),
+        );
+    }
+    for (description, value) in datetimes {
+        push(
+            OutputType::DateTime,
+            false,
+            description.to_string(),
+            AstarteType::DateTime(value),
+        );
+    }
+
+    // empty arrays, and arrays containing every edge case of the type
+    let arrays = [
+        (
+            OutputType::Double,
+            AstarteType::DoubleArray(Vec::new()),
+            AstarteType::DoubleArray(doubles.iter().map(|(_, value)| *value).collect()),
+        ),
+        (
+            OutputType::Integer,
+            AstarteType::IntegerArray(Vec::new()),
+            AstarteType::IntegerArray(integers.to_vec()),
+        ),
+        (
+            OutputType::Boolean,
+            AstarteType::BooleanArray(Vec::new()),
+            AstarteType::BooleanArray(vec![false, true]),
+        ),
+        (
+            OutputType::LongInteger,
+            AstarteType::LongIntegerArray(Vec::new()),
+            AstarteType::LongIntegerArray(LONGINTEGER_BOUNDARIES.to_vec()),
+        ),
+        (
+            OutputType::String,
+            AstarteType::StringArray(Vec::new()),
+            AstarteType::StringArray(strings.into_iter().map(|(_, value)| value).collect()),
+        ),
+        (
+            OutputType::BinaryBlob,
+            AstarteType::BinaryBlobArray(Vec::new()),
+            AstarteType::BinaryBlobArray(blobs.into_iter().map(|(_, value)| value).collect()),
+        ),
+        (
+            OutputType::DateTime,
+            AstarteType::DateTimeArray(Vec::new()),
+            AstarteType::DateTimeArray(datetimes.iter().map(|(_, value)| *value).collect()),
+        ),
+    ];
+
+    for (output_type, empty, all) in arrays {
+        push(output_type, true, "empty".to_string(), empty);
+        push(output_type, true, "of every edge case".to_string(), all);
+    }
+
+    cases
+}
+
+/// Cycle through the edge cases of every type
+#[derive(Debug, Clone)]
+pub struct TypeFuzzer {
+    cases: Vec<EdgeCase>,
+    next: usize,
+}
+
+impl Default for TypeFuzzer {
+    fn default() -> Self {
+        Self {
+            cases: edge_cases(),
+            next: 0,
+        }
+    }
+}
+
+impl TypeFuzzer {
+    /// Next edge case to send, restarting from the first one after the last
+    pub fn next_case(&mut self) -> &EdgeCase {
+        let idx = self.next;
+        self.next = (self.next + 1) % self.cases.len();
+
+        &self.cases[idx]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_edge_cases() {
+        let cases = edge_cases();
+
+        for case in &cases {
+            if let AstarteType::Double(value) = case.value {
+                assert!(AstarteType::try_from(value).is_ok(), "{}", case.description);
+            }
+        }
+
+        let mut fuzzer = TypeFuzzer::default();
+        let first = fuzzer.next_case().clone();
+        assert_eq!(
+            first.interface,
+            "org.astarte-platform.genericsensors.Values"
+        );
+
+        for _ in 1..cases.len() {
+            fuzzer.next_case();
+        }
+        assert_eq!(*fuzzer.next_case(), first);
+    }
+}
@@ -0,0 +1,218 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validation schema for the stream parameters updatable from Astarte.
+//!
+//! The schema is read from a TOML file defining the constraints of each parameter, for instance:
+//!
+//! ```toml
+//! [interval_btw_samples]
+//! min = 100
+//! max = 60000
+//!
+//! [scale]
+//! min = 0.1
+//! max = 10.0
+//!
+//! [function]
+//! allowed = ["sin", "saw", "const"]
+//!
+//! [output_type]
+//! allowed = ["double", "integer"]
+//!
+//! [blob_size]
+//! max = 65536
+//!
+//! [burst_size]
+//! max = 100
+//! ```
+//!
+//! Parameters missing from the schema are not constrained.
+
+use crate::duration::Millis;
+use crate::math::MathFunction;
+use crate::output::OutputType;
+use crate::stream::StreamConfigUpdate;
+use color_eyre::eyre;
+use color_eyre::eyre::{ensure, WrapErr};
+use serde::Deserialize;
+use std::fmt::{Debug, Display};
+use std::path::Path;
+
+/// Constraints on the parameters updatable from Astarte
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidationSchema {
+    /// Bounds of the milliseconds between two samples
+    #[serde(default)]
+    interval_btw_samples: Bounds<Millis>,
+    /// Bounds of the scale
+    #[serde(default)]
+    scale: Bounds<f64>,
+    /// Math functions that can be selected
+    #[serde(default)]
+    function: AllowedSet<MathFunction>,
+    /// Output types that can be selected
+    #[serde(default)]
+    output_type: AllowedSet<OutputType>,
+    /// Bounds of the size in bytes of the binary blobs
+    #[serde(default)]
+    blob_size: Bounds<usize>,
+    /// Bounds of the number of samples sent back-to-back
+    #[serde(default)]
+    burst_size: Bounds<usize>,
+}
+
+/// Inclusive bounds of a numeric parameter
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Bounds<T> {
+    min: Option<T>,
+    max: Option<T>,
+}
+
+// derive would require T: Default
+impl<T> Default for Bounds<T> {
+    fn default() -> Self {
+        Self {
+            min: None,
+            max: None,
+        }
+    }
+}
+
+impl<T> Bounds<T>
+where
+    T: PartialOrd + Display,
+{
+    fn check(&self, value: &T) -> eyre::Result<()> {
+        if let Some(min) = &self.min {
+            ensure!(
+                value >= min,
+                "value {value} is lower than the minimum {min}"
+            );
+        }
+
+        if let Some(max) = &self.max {
+            ensure!(
+                value <= max,
+                "value {value} is greater than the maximum {max}"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Set of values allowed for a parameter
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AllowedSet<T> {
+    allowed: Option<Vec<T>>,
+}
+
+// derive would require T: Default
+impl<T> Default for AllowedSet<T> {
+    fn default() -> Self {
+        Self { allowed: None }
+    }
+}
+
+impl<T> AllowedSet<T>
+where
+    T: PartialEq + Display,
+{
+    fn check(&self, value: &T) -> eyre::Result<()> {
+        let Some(allowed) = &self.allowed else {
+            return Ok(());
+        };
+
+        ensure!(allowed.contains(value), "value {value} is not allowed");
+
+        Ok(())
+    }
+}
+
+impl ValidationSchema {
+    /// Read the schema from a TOML file
+    pub async fn from_toml(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let file = tokio::fs::read_to_string(&path)
+            .await
+            .wrap_err_with(|| format!("couldn't read {}", path.as_ref().display()))?;
+
+        toml::from_str(&file).wrap_err("invalid validation schema")
+    }
+
+    /// Check that an update satisfies the constraints of the schema
+    pub fn validate(&self, update: &StreamConfigUpdate) -> eyre::Result<()> {
+        match update {
+            StreamConfigUpdate::State(_) => Ok(()),
+            StreamConfigUpdate::MathFunction(function) => self.function.check(function),
+            StreamConfigUpdate::Interval(interval) => {
+                let millis = u64::try_from(interval.as_millis()).unwrap_or(u64::MAX);
+
+                self.interval_btw_samples.check(&Millis(millis))
+            }
+            StreamConfigUpdate::Scale(scale) => self.scale.check(scale),
+            StreamConfigUpdate::OutputType(output_type) => self.output_type.check(output_type),
+            StreamConfigUpdate::BlobSize(size) => self.blob_size.check(size),
+            StreamConfigUpdate::BurstSize(size) => self.burst_size.check(size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_validation_schema() {
+        let schema: ValidationSchema = toml::from_str(
+            r#"
+            [interval_btw_samples]
+            min = 100
+
+            [scale]
+            min = 0.1
+            max = 10.0
+
+            [function]
+            allowed = ["sin", "noise-sin"]
+            "#,
+        )
+        .unwrap();
+
+        let valid = [
+            StreamConfigUpdate::Interval(Duration::from_millis(100)),
+            StreamConfigUpdate::Interval(Duration::MAX),
+            StreamConfigUpdate::Scale(10.0),
+            StreamConfigUpdate::MathFunction(MathFunction::NoiseSin),
+            StreamConfigUpdate::OutputType(OutputType::Boolean),
+        ];
+
+        for update in valid {
+            assert!(
+                schema.validate(&update).is_ok(),
+                "{update:?} should be valid"
+            );
+        }
+
+        let invalid = [
+            StreamConfigUpdate::Interval(Duration::from_millis(99)),
+            StreamConfigUpdate::Scale(0.0),
+            StreamConfigUpdate::Scale(10.1),
+            StreamConfigUpdate::MathFunction(MathFunction::Saw),
+        ];
+
+        for update in invalid {
+            assert!(
+                schema.validate(&update).is_err(),
+                "{update:?} should be invalid"
+            );
+        }
+    }
+}
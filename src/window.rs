@@ -0,0 +1,175 @@
+// This file is part of Astarte.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Daily time windows during which the streams send data, to simulate devices with business-hours
+//! activity.
+//!
+//! A window is written as `HH:MM-HH:MM`, in UTC, optionally prefixed by the days of the week it
+//! applies to, e.g. `08:00-18:00`, `mon-fri@08:00-18:00` or `sat@10:00-12:00`. A window ending
+//! before its start spans midnight, e.g. `22:00-06:00`, and belongs to the day it starts on.
+
+use astarte_device_sdk::chrono::{DateTime, Datelike, NaiveTime, Timelike, Utc, Weekday};
+use color_eyre::eyre;
+use color_eyre::eyre::{eyre, WrapErr};
+use std::fmt::Display;
+
+/// Daily time window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveWindow {
+    /// First and last day of the week, inclusive
+    days: Option<(Weekday, Weekday)>,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl ActiveWindow {
+    /// Parse a `[<day>[-<day>]@]HH:MM-HH:MM` window
+    pub fn parse(s: &str) -> eyre::Result<Self> {
+        let (days, times) = match s.split_once('@') {
+            Some((days, times)) => (Some(parse_days(days)?), times),
+            None => (None, s),
+        };
+
+        let (start, end) = times
+            .split_once('-')
+            .ok_or_else(|| eyre!("expected a HH:MM-HH:MM window, got {s}"))?;
+
+        let time = |time: &str| {
+            NaiveTime::parse_from_str(time, "%H:%M")
+                .wrap_err_with(|| format!("invalid time {time} in window {s}"))
+        };
+
+        Ok(Self {
+            days,
+            start: time(start)?,
+            end: time(end)?,
+        })
+    }
+
+    /// Check if the window contains an instant
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let time = now.time();
+        let day = now.weekday();
+
+        // a window with the same start and end spans the whole day
+        if self.start == self.end {
+            return self.on_day(day);
+        }
+
+        if self.start < self.end {
+            return self.start <= time && time < self.end && self.on_day(day);
+        }
+
+        if time >= self.start {
+            self.on_day(day)
+        } else if time < self.end {
+            // past midnight, the window started the day before
+            self.on_day(day.pred())
+        } else {
+            false
+        }
+    }
+
+    /// Check if the window applies to a day of the week
+    fn on_day(&self, day: Weekday) -> bool {
+        let Some((first, last)) = self.days else {
+            return true;
+        };
+
+        let day = day.num_days_from_monday();
+        let first = first.num_days_from_monday();
+        let last = last.num_days_from_monday();
+
+        if first <= last {
+            (first..=last).contains(&day)
+        } else {
+            // the range wraps around the week, e.g. sat-mon
+            day >= first || day <= last
+        }
+    }
+}
+
+/// Parse a `<day>[-<day>]` range of days of the week
+fn parse_days(s: &str) -> eyre::Result<(Weekday, Weekday)> {
+    let day = |day: &str| {
+        day.parse::<Weekday>()
+            .map_err(|_| eyre!("invalid day of the week {day}, expected mon, tue..."))
+    };
+
+    match s.split_once('-') {
+        Some((first, last)) => Ok((day(first)?, day(last)?)),
+        None => {
+            let day = day(s)?;
+
+            Ok((day, day))
+        }
+    }
+}
+
+impl Display for ActiveWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((first, last)) = self.days {
+            if first == last {
+                write!(f, "{first}@")?;
+            } else {
+                write!(f, "{first}-{last}@")?;
+            }
+        }
+
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start.hour(),
+            self.start.minute(),
+            self.end.hour(),
+            self.end.minute()
+        )
+    }
+}
+
+/// Check if an instant is in any of the windows, always true without windows
+pub fn is_active(windows: &[ActiveWindow], now: DateTime<Utc>) -> bool {
+    windows.is_empty() || windows.iter().any(|window| window.contains(now))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use astarte_device_sdk::chrono::TimeZone;
+
+    /// 2026-10-12 is a Monday
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_active_window() {
+        let window = ActiveWindow::parse("08:00-18:00").unwrap();
+        assert!(window.contains(at(12, 8, 0)));
+        assert!(window.contains(at(18, 17, 59)));
+        assert!(!window.contains(at(12, 18, 0)));
+        assert!(!window.contains(at(12, 7, 59)));
+
+        let window = ActiveWindow::parse("mon-fri@08:00-18:00").unwrap();
+        assert!(window.contains(at(16, 12, 0)));
+        assert!(!window.contains(at(17, 12, 0)));
+        assert_eq!(window.to_string(), "Mon-Fri@08:00-18:00");
+
+        // the night of friday belongs to friday
+        let window = ActiveWindow::parse("fri@22:00-06:00").unwrap();
+        assert!(window.contains(at(16, 23, 0)));
+        assert!(window.contains(at(17, 5, 0)));
+        assert!(!window.contains(at(17, 23, 0)));
+        assert!(!window.contains(at(16, 5, 0)));
+
+        assert!(is_active(&[], at(12, 0, 0)));
+
+        for window in ["08:00", "8-18", "08:00-25:00", "xyz@08:00-18:00"] {
+            assert!(ActiveWindow::parse(window).is_err(), "{window}");
+        }
+    }
+}